@@ -0,0 +1,70 @@
+//! Time decoding and encoding a large animated cursor (48 frames in 4 sizes).
+//!
+//! Run with `cargo bench -p ani`.
+
+use std::hint::black_box;
+use std::io;
+
+use ani::de::{Ani, AniRef};
+use ani::ser::AniBuilder;
+use criterion::{criterion_group, criterion_main, Criterion};
+use ico::IconImage;
+
+const FRAMES: usize = 48;
+const SIZES: [u32; 4] = [32, 48, 64, 96];
+
+fn decode(c: &mut Criterion) {
+    let data = animation();
+    let mut group = c.benchmark_group("decode");
+
+    group.bench_function("from_bytes", |b| {
+        b.iter(|| Ani::from_bytes(black_box(&data)));
+    });
+    group.bench_function("from_bytes_strict", |b| {
+        b.iter(|| Ani::from_bytes_strict(black_box(&data)));
+    });
+    group.bench_function("from_reader", |b| {
+        b.iter(|| Ani::from_reader(io::Cursor::new(black_box(&data))));
+    });
+    group.bench_function("AniRef::new", |b| {
+        b.iter(|| AniRef::new(black_box(&data)));
+    });
+
+    group.finish();
+}
+
+fn encode(c: &mut Criterion) {
+    let ani = Ani::from_bytes(&animation()).expect("expected the animation to be valid");
+
+    c.bench_function("encode", |b| b.iter(|| black_box(&ani).encode()));
+}
+
+/// Build an animation with a different gradient in every frame, so nothing is deduplicated.
+fn animation() -> Vec<u8> {
+    let mut builder = AniBuilder::new();
+
+    for frame in 0..FRAMES {
+        let images = SIZES
+            .iter()
+            .map(|&size| {
+                let rgba = (0..size * size)
+                    .flat_map(|i| {
+                        let value = (i as usize + frame) as u8;
+                        [value, value.wrapping_mul(3), 0x80, value | 0x0f]
+                    })
+                    .collect();
+                let mut image = IconImage::from_rgba_data(size, size, rgba);
+                image.set_cursor_hotspot(Some((0, 0)));
+                image
+            })
+            .collect();
+        builder = builder.frame(images);
+    }
+
+    builder
+        .encode()
+        .expect("expected the animation to be valid")
+}
+
+criterion_group!(benches, decode, encode);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+#![no_main]
+
+mod riff;
+
+use std::io;
+
+use libfuzzer_sys::fuzz_target;
+
+// The input is the contents of a single `icon` chunk, either an ICO (or CUR) file or a raw
+// bitmap described by the header.
+fuzz_target!(|input: (bool, u16, u16, u8, &[u8])| {
+    let (icon, width, height, bit_count, frame) = input;
+    let flags = if icon { riff::ICON } else { 0 };
+
+    let data = riff::ani(&[
+        riff::header(1, width.into(), height.into(), bit_count.into(), flags),
+        riff::list(b"fram", &riff::chunk(b"icon", frame)),
+    ]);
+
+    _ = ani::de::Ani::from_bytes(&data);
+
+    if let Ok(mut reader) = ani::de::AniReader::new(io::Cursor::new(&data)) {
+        _ = reader.frame(0);
+    }
+});
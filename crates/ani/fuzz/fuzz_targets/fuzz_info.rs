@@ -0,0 +1,18 @@
+#![no_main]
+
+mod riff;
+
+use libfuzzer_sys::fuzz_target;
+
+// The input is the contents of the `INFO` list (i.e., the `INAM` and `IART` chunks).
+fuzz_target!(|data: &[u8]| {
+    let data = riff::ani(&[
+        riff::list(b"INFO", data),
+        riff::header(0, 0, 0, 0, riff::ICON),
+        riff::list(b"fram", &[]),
+    ]);
+
+    _ = ani::de::Ani::from_bytes(&data);
+    _ = ani::de::Ani::from_bytes_strict(&data);
+    _ = ani::de::Ani::from_reader(&data[..]);
+});
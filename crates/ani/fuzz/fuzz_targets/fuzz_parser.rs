@@ -1,7 +1,25 @@
 #![no_main]
 
+use std::io;
+
 use libfuzzer_sys::fuzz_target;
 
+// The decoders must never panic, no matter how malformed the input is.
 fuzz_target!(|data: &[u8]| {
     _ = ani::de::Ani::from_bytes(data);
+    _ = ani::de::Ani::from_bytes_strict(data);
+    _ = ani::de::Ani::from_reader(data);
+
+    // Corrupt frames should surface as `DecodeError::InvalidFrame` when decoded lazily, too.
+    if let Ok(mut reader) = ani::de::AniReader::new(io::Cursor::new(data)) {
+        for index in 0..reader.frame_count() {
+            _ = reader.frame(index);
+        }
+    }
+
+    if let Ok(ani) = ani::de::AniRef::new(data) {
+        for index in 0..ani.frame_count() {
+            _ = ani.frame(index);
+        }
+    }
 });
@@ -0,0 +1,161 @@
+#![no_main]
+
+use ani::de::Ani;
+use ani::ser::AniBuilder;
+use ico::IconImage;
+use libfuzzer_sys::arbitrary::{self, Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+
+/// An animation that the encoder accepts.
+#[derive(Debug)]
+struct Animation {
+    title: Option<String>,
+    author: Option<String>,
+    jif_rate: u32,
+    rates: Option<Vec<u32>>,
+    sequence: Option<Vec<u32>>,
+    frames: Vec<Vec<Image>>,
+}
+
+/// An image of up to 32 by 32 pixels, with a hotspot inside of it.
+#[derive(Debug)]
+struct Image {
+    width: u16,
+    height: u16,
+    hotspot: (u16, u16),
+    rgba: Vec<u8>,
+}
+
+impl Image {
+    fn to_icon_image(&self) -> IconImage {
+        let mut image =
+            IconImage::from_rgba_data(self.width.into(), self.height.into(), self.rgba.clone());
+        image.set_cursor_hotspot(Some(self.hotspot));
+        image
+    }
+}
+
+impl<'a> Arbitrary<'a> for Animation {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let frames = (0..u.int_in_range(1..=8)?)
+            .map(|_| (0..u.int_in_range(1..=2)?).map(|_| u.arbitrary()).collect())
+            .collect::<arbitrary::Result<Vec<Vec<_>>>>()?;
+
+        let sequence = if u.arbitrary()? {
+            let last = u32::try_from(frames.len() - 1).unwrap();
+            let steps = u.int_in_range(1..=16)?;
+            Some(
+                (0..steps)
+                    .map(|_| u.int_in_range(0..=last))
+                    .collect::<Result<_, _>>()?,
+            )
+        } else {
+            None
+        };
+
+        let steps = sequence.as_ref().map_or(frames.len(), Vec::len);
+        let rates = if u.arbitrary()? {
+            Some(
+                (0..steps)
+                    .map(|_| u.arbitrary())
+                    .collect::<Result<_, _>>()?,
+            )
+        } else {
+            None
+        };
+
+        Ok(Self {
+            title: text(u)?,
+            author: text(u)?,
+            jif_rate: u.arbitrary()?,
+            rates,
+            sequence,
+            frames,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Image {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let width = u.int_in_range(1..=32)?;
+        let height = u.int_in_range(1..=32)?;
+
+        Ok(Self {
+            width,
+            height,
+            hotspot: (
+                u.int_in_range(0..=width - 1)?,
+                u.int_in_range(0..=height - 1)?,
+            ),
+            rgba: u
+                .bytes(usize::from(width) * usize::from(height) * 4)?
+                .to_vec(),
+        })
+    }
+}
+
+/// An `INFO` string, which can't contain the NUL that terminates it.
+fn text(u: &mut Unstructured) -> arbitrary::Result<Option<String>> {
+    let text: Option<String> = u.arbitrary()?;
+    Ok(text.filter(|text| !text.is_empty() && !text.contains('\0')))
+}
+
+// Whatever the encoder produces, the decoders must read back unchanged.
+fuzz_target!(|animation: Animation| {
+    let mut builder = AniBuilder::new().jif_rate(animation.jif_rate);
+    if let Some(title) = &animation.title {
+        builder = builder.title(title);
+    }
+    if let Some(author) = &animation.author {
+        builder = builder.author(author);
+    }
+    if let Some(rates) = &animation.rates {
+        builder = builder.rates(rates.clone());
+    }
+    if let Some(sequence) = &animation.sequence {
+        builder = builder.sequence(sequence.clone());
+    }
+    for images in &animation.frames {
+        builder = builder.frame(images.iter().map(Image::to_icon_image).collect());
+    }
+
+    let data = builder.encode().expect("expected animation to be valid");
+
+    for ani in [Ani::from_bytes(&data), Ani::from_bytes_strict(&data)] {
+        let ani = ani.expect("expected encoded data to be valid");
+        let metadata = ani.metadata();
+
+        assert_eq!(metadata.and_then(|m| m.title()), animation.title.as_deref());
+        assert_eq!(
+            metadata.and_then(|m| m.author()),
+            animation.author.as_deref()
+        );
+        assert_eq!(ani.header().jif_rate(), animation.jif_rate);
+        assert_eq!(ani.rates(), animation.rates.as_deref());
+        assert_eq!(ani.sequence(), animation.sequence.as_deref());
+        assert_eq!(ani.frames().len(), animation.frames.len());
+
+        for (frame, images) in ani.frames().iter().zip(&animation.frames) {
+            assert_eq!(frame.images().len(), images.len());
+
+            for (decoded, image) in frame.images().iter().zip(images) {
+                assert_eq!(decoded.width(), u32::from(image.width));
+                assert_eq!(decoded.height(), u32::from(image.height));
+                assert_eq!(decoded.cursor_hotspot(), Some(image.hotspot));
+                // The color of fully transparent pixels isn't kept (see `AniBuilder::frame`).
+                let expected = image
+                    .rgba
+                    .chunks_exact(4)
+                    .flat_map(|pixel| {
+                        if pixel[3] == 0 {
+                            [0; 4]
+                        } else {
+                            pixel.try_into().unwrap()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(decoded.rgba_data(), expected);
+            }
+        }
+    }
+});
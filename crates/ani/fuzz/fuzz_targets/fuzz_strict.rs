@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The strict decoder gives up on anything out of order, so it has its own target; sharing one
+// with the tolerant decoders would let them drown it out.
+fuzz_target!(|data: &[u8]| {
+    _ = ani::de::Ani::from_bytes_strict(data);
+});
@@ -0,0 +1,36 @@
+//! Wrap fuzzer input in the chunks of an otherwise valid ANI file, so the fuzzer spends its time
+//! on the part of the decoder under test rather than on getting past the file signature.
+
+#![allow(dead_code)]
+
+/// The flag marking frames as ICO (or CUR) files.
+pub const ICON: u32 = 0x1;
+
+/// A RIFF chunk, including the padding byte required after odd-sized data.
+pub fn chunk(identifier: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let size = u32::try_from(data.len()).unwrap_or(u32::MAX);
+
+    let mut output = identifier.to_vec();
+    output.extend_from_slice(&size.to_le_bytes());
+    output.extend_from_slice(data);
+    if !data.len().is_multiple_of(2) {
+        output.push(0);
+    }
+    output
+}
+
+/// A `LIST` chunk of type `list_type`.
+pub fn list(list_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    chunk(b"LIST", &[&list_type[..], data].concat())
+}
+
+/// An `anih` chunk for `frames` frames of `width` by `height` pixels.
+pub fn header(frames: u32, width: u32, height: u32, bit_count: u32, flags: u32) -> Vec<u8> {
+    let fields = [36, frames, frames, width, height, bit_count, 1, 6, flags];
+    chunk(b"anih", &fields.map(u32::to_le_bytes).concat())
+}
+
+/// A complete ANI file made of `chunks`.
+pub fn ani(chunks: &[Vec<u8>]) -> Vec<u8> {
+    chunk(b"RIFF", &[&b"ACON"[..], &chunks.concat()].concat())
+}
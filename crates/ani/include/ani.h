@@ -0,0 +1,62 @@
+#ifndef ANI_H
+#define ANI_H
+
+/* Generated by cbindgen from src/capi.rs; don't edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A decoded animated cursor, owned by the caller until it is passed to [`ani_free`].
+typedef struct AniCursor AniCursor;
+
+// Read and decode the ANI (or static cursor) file at `path`, a NUL-terminated UTF-8 string.
+//
+// Returns `NULL` if the file can't be read or decoded. The result must be freed with
+// [`ani_free`].
+//
+// # Safety
+//
+// `path` must be `NULL` or point to a NUL-terminated string.
+AniCursor *ani_open(const char *path);
+
+// The number of frames of `cursor`, or 0 if `cursor` is `NULL`.
+//
+// # Safety
+//
+// `cursor` must be `NULL` or a pointer returned by [`ani_open`] that hasn't been freed.
+size_t ani_frame_count(const AniCursor *cursor);
+
+// The number of images (one per size) of the frame at `frame`, or 0 if there is no such frame.
+//
+// # Safety
+//
+// `cursor` must be `NULL` or a pointer returned by [`ani_open`] that hasn't been freed.
+size_t ani_image_count(const AniCursor *cursor, size_t frame);
+
+// The pixels of image `image` of the frame at `frame`, as RGBA in row-major order (4 bytes per
+// pixel), storing its dimensions in `width` and `height`.
+//
+// Returns `NULL` (leaving `width` and `height` untouched) if there is no such image. The
+// pixels belong to `cursor`, and stay valid until it is freed.
+//
+// # Safety
+//
+// `cursor` must be `NULL` or a pointer returned by [`ani_open`] that hasn't been freed.
+// `width` and `height` must each be `NULL` or valid for writes.
+const uint8_t *ani_frame_rgba(const AniCursor *cursor,
+                              size_t frame,
+                              size_t image,
+                              uint32_t *width,
+                              uint32_t *height);
+
+// Free a cursor returned by [`ani_open`]. Does nothing if `cursor` is `NULL`.
+//
+// # Safety
+//
+// `cursor` must be `NULL` or a pointer returned by [`ani_open`] that hasn't been freed.
+void ani_free(AniCursor *cursor);
+
+#endif  /* ANI_H */
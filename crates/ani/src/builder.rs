@@ -1,3 +0,0 @@
-#![allow(dead_code, reason = "todo")]
-
-pub struct AniBuilder;
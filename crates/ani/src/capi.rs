@@ -0,0 +1,166 @@
+//! A C ABI for the decoder, so C programs (e.g., GTK cursor utilities) can read ANI files.
+//!
+//! The declarations are in `include/ani.h`. The library has to be built as a C library for them
+//! to link against, e.g.:
+//!
+//! ```sh
+//! cargo rustc -p ani --release --features capi --crate-type cdylib
+//! ```
+//!
+//! After changing this module, regenerate the header with:
+//!
+//! ```sh
+//! cbindgen --config crates/ani/cbindgen.toml --crate ani --output crates/ani/include/ani.h
+//! ```
+
+use std::ffi::{c_char, CStr};
+use std::ptr;
+
+use crate::de::Ani;
+
+/// A decoded animated cursor, owned by the caller until it is passed to [`ani_free`].
+pub struct AniCursor {
+    ani: Ani,
+}
+
+/// Read and decode the ANI (or static cursor) file at `path`, a NUL-terminated UTF-8 string.
+///
+/// Returns `NULL` if the file can't be read or decoded. The result must be freed with
+/// [`ani_free`].
+///
+/// # Safety
+///
+/// `path` must be `NULL` or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ani_open(path: *const c_char) -> *mut AniCursor {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+
+    // SAFETY: The caller guarantees `path` points to a NUL-terminated string.
+    let Ok(path) = unsafe { CStr::from_ptr(path) }.to_str() else {
+        return ptr::null_mut();
+    };
+
+    Ani::open(path).map_or(ptr::null_mut(), |ani| {
+        Box::into_raw(Box::new(AniCursor { ani }))
+    })
+}
+
+/// The number of frames of `cursor`, or 0 if `cursor` is `NULL`.
+///
+/// # Safety
+///
+/// `cursor` must be `NULL` or a pointer returned by [`ani_open`] that hasn't been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ani_frame_count(cursor: *const AniCursor) -> usize {
+    // SAFETY: The caller guarantees `cursor` is valid (or `NULL`).
+    unsafe { cursor.as_ref() }.map_or(0, |cursor| cursor.ani.frames().len())
+}
+
+/// The number of images (one per size) of the frame at `frame`, or 0 if there is no such frame.
+///
+/// # Safety
+///
+/// `cursor` must be `NULL` or a pointer returned by [`ani_open`] that hasn't been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ani_image_count(cursor: *const AniCursor, frame: usize) -> usize {
+    // SAFETY: The caller guarantees `cursor` is valid (or `NULL`).
+    unsafe { cursor.as_ref() }
+        .and_then(|cursor| cursor.ani.frames().get(frame))
+        .map_or(0, |frame| frame.images().len())
+}
+
+/// The pixels of image `image` of the frame at `frame`, as RGBA in row-major order (4 bytes per
+/// pixel), storing its dimensions in `width` and `height`.
+///
+/// Returns `NULL` (leaving `width` and `height` untouched) if there is no such image. The
+/// pixels belong to `cursor`, and stay valid until it is freed.
+///
+/// # Safety
+///
+/// `cursor` must be `NULL` or a pointer returned by [`ani_open`] that hasn't been freed.
+/// `width` and `height` must each be `NULL` or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ani_frame_rgba(
+    cursor: *const AniCursor,
+    frame: usize,
+    image: usize,
+    width: *mut u32,
+    height: *mut u32,
+) -> *const u8 {
+    // SAFETY: The caller guarantees `cursor` is valid (or `NULL`).
+    let Some(image) = unsafe { cursor.as_ref() }
+        .and_then(|cursor| cursor.ani.frames().get(frame))
+        .and_then(|frame| frame.images().get(image))
+    else {
+        return ptr::null();
+    };
+
+    // SAFETY: The caller guarantees `width` and `height` are valid for writes (or `NULL`).
+    unsafe {
+        if let Some(width) = width.as_mut() {
+            *width = image.width();
+        }
+        if let Some(height) = height.as_mut() {
+            *height = image.height();
+        }
+    }
+
+    image.rgba_data().as_ptr()
+}
+
+/// Free a cursor returned by [`ani_open`]. Does nothing if `cursor` is `NULL`.
+///
+/// # Safety
+///
+/// `cursor` must be `NULL` or a pointer returned by [`ani_open`] that hasn't been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ani_free(cursor: *mut AniCursor) {
+    if !cursor.is_null() {
+        // SAFETY: The caller guarantees `cursor` came from `ani_open`, which boxed it.
+        drop(unsafe { Box::from_raw(cursor) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn open() {
+        let image = ico::IconImage::from_rgba_data(2, 1, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let data = crate::ser::AniBuilder::new()
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let dir = crate::test_dir();
+        let path = dir.path().join("capi.ani");
+        fs::write(&path, data).unwrap();
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+
+        // SAFETY: Every pointer is either valid or `NULL`, and `cursor` is only freed once.
+        unsafe {
+            let cursor = ani_open(c_path.as_ptr());
+            assert!(!cursor.is_null());
+            assert_eq!(ani_frame_count(cursor), 1);
+            assert_eq!(ani_image_count(cursor, 0), 1);
+
+            let (mut width, mut height) = (0, 0);
+            let rgba = ani_frame_rgba(cursor, 0, 0, &raw mut width, &raw mut height);
+            assert_eq!((width, height), (2, 1));
+            assert_eq!(
+                std::slice::from_raw_parts(rgba, 8),
+                [1, 2, 3, 4, 5, 6, 7, 8]
+            );
+            assert!(ani_frame_rgba(cursor, 1, 0, ptr::null_mut(), ptr::null_mut()).is_null());
+
+            ani_free(cursor);
+            assert!(ani_open(ptr::null()).is_null());
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Decode frames stored as raw bitmaps, rather than ICO files.
+//!
+//! When [`Flag::ICON`](crate::de::Flag::ICON) is not set, each `icon` chunk holds a device
+//! independent bitmap (DIB). Some writers include the `BITMAPINFOHEADER` (and possibly the
+//! `BITMAPFILEHEADER` before it), while others only store the pixels and rely on the size and
+//! bit count from the `anih` chunk.
+//!
+//! The bitmaps of ICO (and CUR) entries are decoded here as well, rather than by the `ico`
+//! crate, so both kinds of frames handle color tables and AND masks the same way:
+//!
+//! - Only the colors listed by `biClrUsed` are read from the color table.
+//! - Pixels set in the AND mask are transparent, except for 32-bit bitmaps with an alpha
+//!   channel (which takes precedence).
+//! - Pixels that Windows would draw by inverting the screen (set in the AND mask, but not black)
+//!   can't be represented in other formats, so they are drawn in opaque black instead.
+
+use ico::IconImage;
+
+use crate::de::{FrameError, Header};
+
+/// The size of the `BITMAPFILEHEADER` that starts a `.bmp` file.
+const FILE_HEADER_SIZE: usize = 14;
+
+/// The size of the smallest supported `BITMAPINFOHEADER`.
+const INFO_HEADER_SIZE: u32 = 40;
+
+/// `BI_RGB`: the pixels are not compressed.
+const BI_RGB: u32 = 0;
+
+/// `BI_BITFIELDS`: the pixels are not compressed, but use custom color masks.
+const BI_BITFIELDS: u32 = 3;
+
+/// Describes the layout of the pixel data.
+struct Layout {
+    width: u32,
+    height: u32,
+    bit_count: u32,
+    bottom_up: bool,
+
+    /// Whether an AND mask follows the pixels.
+    masked: bool,
+}
+
+/// Decode a bitmap frame, using `header` for the details the frame doesn't contain itself.
+pub(crate) fn decode(data: &[u8], header: &Header) -> Result<IconImage, FrameError> {
+    let data = match data {
+        [b'B', b'M', ..] => data.get(FILE_HEADER_SIZE..).unwrap_or_default(),
+        _ => data,
+    };
+
+    let (layout, palette, pixels) = if read_u32(data, 0) == Some(INFO_HEADER_SIZE)
+        || read_u32(data, 0).is_some_and(|size| size > INFO_HEADER_SIZE && size <= 124)
+    {
+        parse_info_header(data, header.height())?
+    } else {
+        let layout = Layout {
+            width: header.width(),
+            height: header.height(),
+            bit_count: header.bit_count(),
+            bottom_up: true,
+            masked: false,
+        };
+        (layout, &[][..], data)
+    };
+
+    let rgba = to_rgba(&layout, palette, pixels)?;
+    Ok(IconImage::from_rgba_data(layout.width, layout.height, rgba))
+}
+
+/// Decode the bitmap of an ICO (or CUR) entry, which declares twice its height to include the
+/// AND mask.
+pub(crate) fn decode_entry(data: &[u8]) -> Result<IconImage, FrameError> {
+    let (mut layout, palette, pixels) = parse_info_header(data, 0)?;
+    layout.height /= 2;
+    layout.masked = true;
+
+    let rgba = to_rgba(&layout, palette, pixels)?;
+    Ok(IconImage::from_rgba_data(layout.width, layout.height, rgba))
+}
+
+/// Read the `BITMAPINFOHEADER`, returning the layout, color table and pixel data.
+///
+/// If the bitmap declares twice the `expected_height`, it is taken from an ICO file, and
+/// includes an AND mask.
+fn parse_info_header(
+    data: &[u8],
+    expected_height: u32,
+) -> Result<(Layout, &[u8], &[u8]), FrameError> {
+    let field = |offset| read_u32(data, offset).ok_or_else(|| invalid("truncated bitmap header"));
+
+    let size = field(0)?;
+    let width = field(4)?.cast_signed();
+    let height = field(8)?.cast_signed();
+    let bit_count = field(12)? >> 16;
+    let compression = field(16)?;
+    let colors_used = field(32)?;
+
+    if compression != BI_RGB && !(compression == BI_BITFIELDS && bit_count == 32) {
+        return Err(invalid("compressed bitmaps are not supported"));
+    }
+
+    let mut height_abs = height.unsigned_abs();
+    let mut masked = false;
+
+    // Bitmaps taken from ICO files declare twice their height, to include the AND mask.
+    if expected_height != 0 && height_abs == expected_height.saturating_mul(2) {
+        height_abs = expected_height;
+        masked = true;
+    }
+
+    let colors = match colors_used {
+        0 if bit_count <= 8 => 1 << bit_count,
+        0 => 0,
+        n => n,
+    };
+
+    // The color masks follow the header when using `BI_BITFIELDS`.
+    let masks = if compression == BI_BITFIELDS && size == INFO_HEADER_SIZE {
+        12
+    } else {
+        0
+    };
+
+    let palette_start = usize::try_from(size).map_err(|_| invalid("bitmap header too large"))?;
+    let palette_start = palette_start + masks;
+    let palette_end = usize::try_from(colors)
+        .ok()
+        .and_then(|colors| colors.checked_mul(4))
+        .and_then(|len| palette_start.checked_add(len))
+        .ok_or_else(|| invalid("bitmap color table too large"))?;
+
+    let palette = data
+        .get(palette_start..palette_end)
+        .ok_or_else(|| invalid("truncated bitmap color table"))?;
+    let pixels = &data[palette_end..];
+
+    let layout = Layout {
+        width: width.unsigned_abs(),
+        height: height_abs,
+        bit_count,
+        bottom_up: height > 0,
+        masked,
+    };
+
+    Ok((layout, palette, pixels))
+}
+
+/// Convert the pixel data to RGBA, in row-major order from the top.
+fn to_rgba(layout: &Layout, palette: &[u8], pixels: &[u8]) -> Result<Vec<u8>, FrameError> {
+    let &Layout {
+        width,
+        height,
+        bit_count,
+        bottom_up,
+        masked,
+    } = layout;
+
+    if width == 0 || height == 0 {
+        return Err(invalid("bitmap has no pixels"));
+    }
+
+    if !matches!(bit_count, 1 | 4 | 8 | 16 | 24 | 32) {
+        return Err(invalid("unsupported bitmap bit count"));
+    }
+
+    if bit_count <= 8 && palette.is_empty() {
+        return Err(invalid("bitmap is missing its color table"));
+    }
+
+    let too_large = || invalid("bitmap too large");
+    let width = usize::try_from(width).map_err(|_| too_large())?;
+    let height = usize::try_from(height).map_err(|_| too_large())?;
+    let bits = usize::try_from(bit_count).map_err(|_| too_large())?;
+
+    // Rows are padded to a multiple of 4 bytes.
+    let stride = width
+        .checked_mul(bits)
+        .and_then(|row| row.checked_add(31))
+        .map(|row| row / 32 * 4)
+        .ok_or_else(too_large)?;
+
+    if stride
+        .checked_mul(height)
+        .is_none_or(|len| len > pixels.len())
+    {
+        return Err(invalid("truncated bitmap pixel data"));
+    }
+
+    let mut rgba = Vec::with_capacity(width * height * 4);
+
+    for y in 0..height {
+        let row = if bottom_up { height - 1 - y } else { y };
+        let row = &pixels[row * stride..(row + 1) * stride];
+
+        for x in 0..width {
+            let pixel = match bit_count {
+                32 => [row[x * 4 + 2], row[x * 4 + 1], row[x * 4], row[x * 4 + 3]],
+                24 => [row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 0xff],
+                16 => {
+                    // 5 bits for each channel (X1R5G5B5).
+                    let color = u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]);
+                    let channel = |shift: u16| {
+                        let value = (color >> shift) & 0x1f;
+                        u8::try_from((value * 255 + 15) / 31).unwrap_or(u8::MAX)
+                    };
+                    [channel(10), channel(5), channel(0), 0xff]
+                }
+                _ => {
+                    let per_byte = 8 / bits;
+                    let shift = 8 - bits - (x % per_byte) * bits;
+                    let index = usize::from(row[x / per_byte] >> shift) & ((1 << bits) - 1);
+                    let color = palette.get(index * 4..index * 4 + 3).unwrap_or(&[0; 3]);
+                    [color[2], color[1], color[0], 0xff]
+                }
+            };
+
+            rgba.extend_from_slice(&pixel);
+        }
+    }
+
+    // Many 32-bit bitmaps leave the alpha channel empty, rather than fully transparent.
+    if bit_count == 32 && rgba.chunks(4).all(|pixel| pixel[3] == 0) {
+        rgba.chunks_mut(4).for_each(|pixel| pixel[3] = 0xff);
+    } else if bit_count == 32 {
+        // The alpha channel takes precedence over the AND mask.
+        return Ok(rgba);
+    }
+
+    // Some writers leave out the AND mask, so the image is kept opaque.
+    if let Some(mask) = masked.then(|| pixels.get(stride * height..)).flatten() {
+        apply_mask(&mut rgba, width, height, bottom_up, mask);
+    }
+
+    Ok(rgba)
+}
+
+/// Make the pixels set in the AND `mask` transparent (or black, if they invert the screen).
+fn apply_mask(rgba: &mut [u8], width: usize, height: usize, bottom_up: bool, mask: &[u8]) {
+    // Rows of the mask are padded to a multiple of 4 bytes, too.
+    let stride = width.div_ceil(32) * 4;
+    if mask.len() < stride * height {
+        return;
+    }
+
+    for (y, pixels) in rgba.chunks_exact_mut(width * 4).enumerate() {
+        let row = if bottom_up { height - 1 - y } else { y };
+        let row = &mask[row * stride..(row + 1) * stride];
+
+        for (x, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            if row[x / 8] >> (7 - x % 8) & 1 == 0 {
+                continue;
+            }
+
+            if pixel[..3] == [0; 3] {
+                pixel[3] = 0;
+            } else {
+                pixel.copy_from_slice(&[0, 0, 0, 0xff]);
+            }
+        }
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn invalid(reason: &'static str) -> FrameError {
+    FrameError::InvalidBitmap { reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_pixels() {
+        // A 2x2 image at 24 bits per pixel, stored from the bottom row up (rows padded to 8).
+        let data = [
+            0, 0, 0xff, 0, 0xff, 0, 0, 0, // Bottom: red, green
+            0xff, 0, 0, 0xff, 0xff, 0xff, 0, 0, // Top: blue, white
+        ];
+        let layout = Layout {
+            width: 2,
+            height: 2,
+            bit_count: 24,
+            bottom_up: true,
+            masked: false,
+        };
+
+        let rgba = to_rgba(&layout, &[], &data).expect("expected hardcoded bytes to be valid");
+        assert_eq!(
+            rgba,
+            [
+                0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // Blue, white
+                0xff, 0, 0, 0xff, 0, 0xff, 0, 0xff, // Red, green
+            ]
+        );
+    }
+
+    #[test]
+    fn palette() {
+        // A 2x1 image at 1 bit per pixel (black, white).
+        let palette = [0, 0, 0, 0, 0xff, 0xff, 0xff, 0];
+        let data = [0b0100_0000, 0, 0, 0];
+        let layout = Layout {
+            width: 2,
+            height: 1,
+            bit_count: 1,
+            bottom_up: true,
+            masked: false,
+        };
+
+        let rgba = to_rgba(&layout, &palette, &data).expect("expected hardcoded bytes to be valid");
+        assert_eq!(rgba, [0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff]);
+
+        let truncated = to_rgba(&layout, &palette, &data[..2]);
+        assert!(truncated.is_err());
+    }
+
+    /// Create the bitmap of an ICO entry (with twice its height), listing `colors` colors.
+    fn entry(width: u32, height: u32, bit_count: u32, colors: u32, rest: &[u8]) -> Vec<u8> {
+        let fields = [
+            INFO_HEADER_SIZE,
+            width,
+            height * 2,
+            1 | bit_count << 16,
+            0,
+            0,
+            0,
+            0,
+            colors,
+            0,
+        ];
+        [&fields.map(u32::to_le_bytes).concat(), rest].concat()
+    }
+
+    #[test]
+    fn masked() {
+        // A 2x2 image at 4 bits per pixel, with 3 colors: black, white, red.
+        let palette = [0, 0, 0, 0, 0xff, 0xff, 0xff, 0, 0, 0, 0xff, 0];
+        let pixels = [
+            0x01, 0, 0, 0, // Bottom: black, white
+            0x21, 0, 0, 0, // Top: red, white
+        ];
+        let mask = [
+            0b1000_0000,
+            0,
+            0,
+            0, // Bottom: transparent, opaque
+            0b0100_0000,
+            0,
+            0,
+            0, // Top: opaque, inverted
+        ];
+        let data = entry(2, 2, 4, 3, &[&palette[..], &pixels, &mask].concat());
+
+        let image = decode_entry(&data).expect("expected hardcoded bytes to be valid");
+        assert_eq!((image.width(), image.height()), (2, 2));
+        assert_eq!(
+            image.rgba_data(),
+            [
+                0xff, 0, 0, 0xff, 0, 0, 0, 0xff, // Red, black (inverted)
+                0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, // Transparent, white
+            ]
+        );
+
+        // Without an alpha channel, 32-bit bitmaps use the AND mask as well.
+        let pixels = [0; 4];
+        let mask = [0b1000_0000, 0, 0, 0];
+        let data = entry(1, 1, 32, 0, &[&pixels[..], &mask].concat());
+        let image = decode_entry(&data).expect("expected hardcoded bytes to be valid");
+        assert_eq!(image.rgba_data(), [0; 4]);
+
+        let pixels = [0xff, 0xff, 0xff, 0x80];
+        let data = entry(1, 1, 32, 0, &[&pixels[..], &mask].concat());
+        let image = decode_entry(&data).expect("expected hardcoded bytes to be valid");
+        assert_eq!(image.rgba_data(), [0xff, 0xff, 0xff, 0x80]);
+    }
+}
@@ -0,0 +1,289 @@
+use crate::de::parser::{to_usize, Parser};
+use crate::de::{
+    decode_frame, parse_anih_chunk, parse_info_chunk, parse_rate_chunk, parse_seq_chunk,
+    read_chunk_tolerant, read_icon_chunks, salvage_icon_chunks, validate_signature, Ani,
+    DecodeError, Frame, Header, Kind, Limits, Metadata, Warning,
+};
+
+/// Decode the frames of ANI data on demand, borrowing them from the data.
+///
+/// Parsing copies none of the data: each frame stays a slice of the data until it is decoded,
+/// on its own with [`Self::frame`] or all at once with [`Self::decode`]. This makes it cheap
+/// to look at the chunks of many files (e.g., when fuzzing, or to list a directory of
+/// cursors), or to decode only a few of the frames. The chunks may be in any order, as with
+/// [`Ani::from_bytes`] (which decodes through this).
+///
+/// ```no_run
+/// # use ani::de::AniRef;
+/// let data = std::fs::read("Wait.ani").unwrap();
+/// let ani = AniRef::new(&data).unwrap();
+/// let first = ani.frame(0).unwrap();
+///
+/// assert!(ani.frame_count() > 0);
+/// ```
+pub struct AniRef<'a> {
+    /// The animation, without its frames.
+    ani: Ani,
+
+    /// The contents of the `icon` chunk of each frame.
+    frames: Vec<&'a [u8]>,
+    limits: Limits,
+}
+
+impl<'a> AniRef<'a> {
+    /// Parse the chunks of ANI data, without decoding any of the frames.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - Data has an invalid file signature.
+    /// - Data does not follow the ANI file format specification.
+    /// - Data exceeds the default [`Limits`].
+    pub fn new(data: &'a [u8]) -> Result<Self, DecodeError> {
+        Self::with_limits(data, Limits::default())
+    }
+
+    /// Like [`Self::new`], but with custom [`Limits`] instead of the defaults.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error for the same reasons as [`Self::new`].
+    pub fn with_limits(data: &'a [u8], limits: Limits) -> Result<Self, DecodeError> {
+        Self::parse(data, limits, false)
+    }
+
+    /// Parse the chunks of ANI data, keeping the complete frames of a file that ends early if
+    /// `recover` is set (see [`crate::de::DecodeMode::Recover`]).
+    pub(super) fn parse(
+        data: &'a [u8],
+        limits: Limits,
+        recover: bool,
+    ) -> Result<Self, DecodeError> {
+        let mut parser = Parser::new(data);
+        validate_signature(&mut parser, !recover)?;
+        let mut chunks = Vec::new();
+        let mut warnings = Vec::new();
+        let mut truncated = false;
+
+        while parser.bytes_remaining() > 0 {
+            if parser.bytes_remaining() == 1 {
+                // A single stray byte can't hold another chunk (e.g., padding that the RIFF size
+                // accounts for, but that follows an even-sized chunk).
+                _ = parser.read_bytes(1);
+                continue;
+            }
+
+            let chunk = match read_chunk_tolerant(&mut parser, &limits, recover) {
+                Ok(chunk) => chunk,
+                Err(DecodeError::NotEnoughBytes { .. } | DecodeError::TruncatedChunk { .. })
+                    if recover =>
+                {
+                    truncated = true;
+                    break;
+                }
+                Err(err) => return Err(err),
+            };
+
+            if chunk.kind.is_none() {
+                warnings.push(Warning::SkippedChunk {
+                    identifier: chunk.name,
+                    size: chunk.size,
+                });
+            }
+            chunks.push(chunk);
+        }
+
+        let find = |kind: Kind| {
+            chunks
+                .iter()
+                .find(|chunk| chunk.kind == Some(kind))
+                .map(|chunk| Parser::new(chunk.data))
+        };
+
+        let metadata = find(Kind::Metadata)
+            .map(|mut parser| parse_info_chunk(&mut parser))
+            .transpose()?;
+
+        let header = find(Kind::Header)
+            .ok_or(DecodeError::MissingChunk { expected: *b"anih" })
+            .and_then(|mut parser| parse_anih_chunk(&mut parser))?;
+
+        let rates = find(Kind::Rate)
+            .map(|mut parser| parse_rate_chunk(&mut parser))
+            .transpose()?;
+
+        let sequence = find(Kind::Sequence)
+            .map(|mut parser| parse_seq_chunk(&mut parser))
+            .transpose()?;
+
+        let mut fram =
+            find(Kind::Frames).ok_or(DecodeError::MissingChunk { expected: *b"fram" })?;
+        let frames = if recover {
+            salvage_icon_chunks(&mut fram, &header, &limits)?
+        } else {
+            read_icon_chunks(&mut fram, &header, &limits)?
+        };
+
+        let partial =
+            truncated || to_usize(header.frames()).is_ok_and(|declared| declared > frames.len());
+        if partial {
+            warnings.push(Warning::Truncated {
+                declared: header.frames(),
+                recovered: frames.len(),
+            });
+        }
+
+        let mut ani = Ani {
+            metadata,
+            header,
+            rates,
+            sequence,
+            frames: Vec::new(),
+            warnings,
+            partial,
+        };
+        if partial {
+            ani.repair_timing(frames.len());
+        }
+        ani.warnings.extend(ani.rate_warnings());
+
+        Ok(Self {
+            ani,
+            frames,
+            limits,
+        })
+    }
+
+    /// Decode the frame at `index`.
+    ///
+    /// The limit on pixels applies to the frame on its own, rather than to every frame combined.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - There is no frame at `index`.
+    /// - The frame's data could not be decoded.
+    pub fn frame(&self, index: usize) -> Result<Frame, DecodeError> {
+        let frame = decode_frame(self.frame_bytes(index)?, index, &self.ani.header)?;
+        self.limits.check_pixels(frame.pixels())?;
+
+        Ok(frame)
+    }
+
+    /// The frame at `index` without decoding it (see [`Frame::raw`]), borrowed from the data.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if there is no frame at `index`.
+    pub fn frame_bytes(&self, index: usize) -> Result<&'a [u8], DecodeError> {
+        self.frames
+            .get(index)
+            .copied()
+            .ok_or(DecodeError::MissingFrame { index })
+    }
+
+    /// The number of frames found in the `fram` chunk.
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Additional information about the cursor (e.g., title, author, copyright).
+    #[must_use]
+    pub const fn metadata(&self) -> Option<&Metadata> {
+        self.ani.metadata()
+    }
+
+    /// Additional context for building the animated cursor.
+    #[must_use]
+    pub const fn header(&self) -> &Header {
+        self.ani.header()
+    }
+
+    /// Display rate for each of the frames, if available.
+    #[must_use]
+    pub fn rates(&self) -> Option<&[u32]> {
+        self.ani.rates()
+    }
+
+    /// Ordering of the frames, if available.
+    #[must_use]
+    pub fn sequence(&self) -> Option<&[u32]> {
+        self.ani.sequence()
+    }
+
+    /// Problems with the data that were worked around while parsing it (see
+    /// [`Ani::warnings`]).
+    #[must_use]
+    pub fn warnings(&self) -> &[Warning] {
+        self.ani.warnings()
+    }
+
+    /// Whether the data ended early, so only the frames before the end were kept (see
+    /// [`Ani::is_partial`]).
+    #[must_use]
+    pub const fn is_partial(&self) -> bool {
+        self.ani.is_partial()
+    }
+
+    /// Decode every frame.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - A frame's data could not be decoded.
+    /// - The frames exceed the limit on pixels, combined.
+    pub fn decode(self) -> Result<Ani, DecodeError> {
+        let mut ani = self.ani;
+        ani.frames.reserve_exact(self.frames.len());
+
+        let mut pixels = 0_u64;
+        for (index, data) in self.frames.into_iter().enumerate() {
+            let frame = decode_frame(data, index, &ani.header)?;
+            pixels = pixels.saturating_add(frame.pixels());
+            self.limits.check_pixels(pixels)?;
+            ani.frames.push(frame);
+        }
+
+        Ok(ani)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrow_frames() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let data = crate::ser::AniBuilder::new()
+            .title("Wait")
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let ani = AniRef::new(&data).expect("expected data to be valid");
+        assert_eq!(ani.frame_count(), 2);
+        assert_eq!(ani.metadata().and_then(Metadata::title), Some("Wait"));
+
+        // The frames point into the data rather than at a copy of it.
+        let raw = ani.frame_bytes(1).expect("expected a second frame");
+        assert!(data.as_ptr_range().contains(&raw.as_ptr()));
+        assert_eq!(
+            ani.frame(1).expect("expected frame to be valid").raw(),
+            Some(raw)
+        );
+        assert!(matches!(
+            ani.frame(2),
+            Err(DecodeError::MissingFrame { index: 2 })
+        ));
+
+        let decoded = ani.decode().expect("expected frames to be valid");
+        assert_eq!(decoded.frames().len(), 2);
+        assert_eq!(decoded.frames()[1].raw(), Some(raw));
+    }
+}
@@ -2,10 +2,11 @@ use std::{error, fmt, io};
 
 use crate::de::parser::Identifier;
 
+/// Represents a failure to decode an ANI file.
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum DecodeError {
-    /// An error occurred while attempting to read from a file.
+    /// An error occurred while attempting to read from a file (or another [`io::Read`]).
     ReadFailure {
         /// The underlying error that caused the failure.
         source: io::Error,
@@ -48,22 +49,85 @@ pub enum DecodeError {
     /// The chunk size indicates the value is not properly aligned for `u32`s.
     InvalidAlignmentU32,
 
+    /// A required chunk was not found.
     MissingChunk {
+        /// The chunk identifier that was expected.
         expected: Identifier,
     },
+
+    /// A size read from the file cannot be represented on the current architecture.
+    ArchitectureLimit {
+        /// The size that was read.
+        value: u32,
+    },
+
+    /// The data inside of an `icon` chunk could not be decoded.
+    InvalidFrame {
+        /// The position of the frame within the `fram` chunk.
+        index: usize,
+        /// Why the frame could not be decoded.
+        source: FrameError,
+    },
+
+    /// There is no frame at the requested position.
+    MissingFrame {
+        /// The position of the requested frame.
+        index: usize,
+    },
+
+    /// A chunk declared a size larger than the amount of data remaining.
+    TruncatedChunk {
+        /// The identifier of the truncated chunk.
+        identifier: Identifier,
+        /// The size declared by the chunk.
+        declared: u32,
+        /// The number of bytes that were actually available.
+        available: usize,
+    },
+
+    /// The file has more frames than allowed by the [`crate::de::Limits`].
+    TooManyFrames {
+        /// The number of frames declared by the file.
+        declared: u32,
+        /// The maximum number of frames.
+        limit: u32,
+    },
+
+    /// A chunk is larger than allowed by the [`crate::de::Limits`].
+    ChunkTooLarge {
+        /// The identifier of the chunk.
+        identifier: Identifier,
+        /// The size declared by the chunk.
+        size: u32,
+        /// The maximum size of a chunk.
+        limit: u32,
+    },
+
+    /// The frames have more pixels combined than allowed by the [`crate::de::Limits`].
+    TooManyPixels {
+        /// The maximum number of pixels.
+        limit: u64,
+    },
 }
 
 impl error::Error for DecodeError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
             Self::ReadFailure { ref source } => Some(source),
+            Self::InvalidFrame { ref source, .. } => Some(source),
             Self::NotEnoughBytes { .. }
             | Self::UnexpectedIdentifier { .. }
             | Self::UnknownIdentifier { .. }
             | Self::SizeMismatch { .. }
             | Self::InvalidHeaderSize { .. }
             | Self::InvalidAlignmentU32
-            | Self::MissingChunk { .. } => None,
+            | Self::MissingChunk { .. }
+            | Self::ArchitectureLimit { .. }
+            | Self::MissingFrame { .. }
+            | Self::TruncatedChunk { .. }
+            | Self::TooManyFrames { .. }
+            | Self::ChunkTooLarge { .. }
+            | Self::TooManyPixels { .. } => None,
         }
     }
 }
@@ -94,8 +158,82 @@ impl fmt::Display for DecodeError {
                 "expected chunk size to be properly aligned for u32".fmt(f)
             }
             Self::MissingChunk { expected } => {
+                let expected = String::from_utf8_lossy(&expected).to_string();
                 write!(f, "chunk not found: {expected:?}")
             }
+            Self::ArchitectureLimit { value } => {
+                write!(f, "size {value} is too large for the current architecture")
+            }
+            Self::InvalidFrame { index, .. } => write!(f, "failed to decode frame {index}"),
+            Self::MissingFrame { index } => write!(f, "frame {index} does not exist"),
+            Self::TruncatedChunk {
+                identifier,
+                declared,
+                available,
+            } => {
+                let identifier = String::from_utf8_lossy(&identifier).to_string();
+                write!(
+                    f,
+                    "chunk {identifier:?} declared {declared} bytes, but only {available} remain"
+                )
+            }
+            Self::TooManyFrames { declared, limit } => {
+                write!(
+                    f,
+                    "file declares {declared} frames, but at most {limit} are allowed"
+                )
+            }
+            Self::ChunkTooLarge {
+                identifier,
+                size,
+                limit,
+            } => {
+                let identifier = String::from_utf8_lossy(&identifier).to_string();
+                write!(
+                    f,
+                    "chunk {identifier:?} is {size} bytes, but at most {limit} are allowed"
+                )
+            }
+            Self::TooManyPixels { limit } => {
+                write!(f, "frames exceed the limit of {limit} pixels")
+            }
+        }
+    }
+}
+
+/// Why the data of a frame could not be decoded (see [`DecodeError::InvalidFrame`]).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// The frame is a bitmap (or an image of an ICO file stored as one) that is malformed, or
+    /// that uses a feature that isn't supported (e.g., compression).
+    InvalidBitmap {
+        /// What is wrong with the bitmap.
+        reason: &'static str,
+    },
+
+    /// The frame isn't a valid ICO (or CUR) file, or one of its images isn't a valid PNG image.
+    InvalidImage {
+        /// What is wrong with the image, as described by the `ico` crate.
+        message: String,
+    },
+}
+
+impl From<io::Error> for FrameError {
+    fn from(err: io::Error) -> Self {
+        Self::InvalidImage {
+            message: err.to_string(),
+        }
+    }
+}
+
+impl error::Error for FrameError {}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::InvalidBitmap { reason } => reason.fmt(f),
+            Self::InvalidImage { ref message } => message.fmt(f),
         }
     }
 }
@@ -0,0 +1,187 @@
+use ico::IconImage;
+
+/// Represents a single frame of the animation.
+///
+/// A frame contains one image for each size the cursor is available in.
+#[derive(Clone)]
+pub struct Frame {
+    images: Vec<IconImage>,
+
+    /// The contents of the `icon` chunk the frame was decoded from.
+    raw: Option<Vec<u8>>,
+}
+
+impl Frame {
+    /// Create a frame from its images.
+    #[must_use]
+    pub const fn new(images: Vec<IconImage>) -> Self {
+        Self { images, raw: None }
+    }
+
+    /// Keep the encoded bytes the frame was decoded from.
+    pub(crate) fn with_raw(mut self, raw: Vec<u8>) -> Self {
+        self.raw = Some(raw);
+        self
+    }
+
+    /// The number of pixels of every image combined.
+    pub(crate) fn pixels(&self) -> u64 {
+        self.images
+            .iter()
+            .map(|image| u64::from(image.width()) * u64::from(image.height()))
+            .sum()
+    }
+
+    /// The images contained in the frame, one per size.
+    #[must_use]
+    pub fn images(&self) -> &[IconImage] {
+        &self.images
+    }
+
+    /// Mutable access to the images of the frame (e.g., to edit their pixels).
+    ///
+    /// Changes aren't reflected in [`Self::raw`].
+    pub fn images_mut(&mut self) -> &mut [IconImage] {
+        &mut self.images
+    }
+
+    /// The encoded frame (an ICO or CUR file, or a bitmap if [`crate::de::Flag::ICON`] isn't set),
+    /// exactly as it was read.
+    ///
+    /// This can be written out as-is to split an animation into static cursors without
+    /// re-encoding the images. Returns `None` if the frame wasn't decoded from an `icon` chunk
+    /// (e.g., it was created with [`Self::new`] or read from a PNG).
+    #[must_use]
+    pub fn raw(&self) -> Option<&[u8]> {
+        self.raw.as_deref()
+    }
+
+    /// The pixels of the image at `index` as RGBA, in row-major order (4 bytes per pixel).
+    ///
+    /// Use [`IconImage::width`] and [`IconImage::height`] on the matching entry of
+    /// [`Self::images`] for the dimensions.
+    #[must_use]
+    pub fn rgba(&self, index: usize) -> Option<&[u8]> {
+        self.images.get(index).map(IconImage::rgba_data)
+    }
+
+    /// Copy the image at `index` into an [`image::RgbaImage`].
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn to_rgba_image(&self, index: usize) -> Option<image::RgbaImage> {
+        let image = self.images.get(index)?;
+        image::RgbaImage::from_raw(image.width(), image.height(), image.rgba_data().to_vec())
+    }
+
+    /// Copy every image of the frame into an [`image::RgbaImage`], one per size.
+    #[cfg(feature = "image")]
+    #[must_use]
+    pub fn to_rgba_images(&self) -> Vec<image::RgbaImage> {
+        (0..self.images.len())
+            .filter_map(|index| self.to_rgba_image(index))
+            .collect()
+    }
+
+    /// The hotspot of the image at `index`, if the image is a cursor.
+    ///
+    /// Returns `None` if there is no image at `index` or if the frame was stored as an icon
+    /// (which does not carry a hotspot).
+    #[must_use]
+    pub fn hotspot(&self, index: usize) -> Option<Hotspot> {
+        self.images
+            .get(index)
+            .and_then(IconImage::cursor_hotspot)
+            .map(|(x, y)| Hotspot { x, y })
+    }
+
+    /// The size and hotspot of each image, without their pixels (e.g., for reports).
+    #[must_use]
+    pub fn summary(&self) -> FrameSummary {
+        let images = self
+            .images
+            .iter()
+            .enumerate()
+            .map(|(index, image)| ImageSummary {
+                width: image.width(),
+                height: image.height(),
+                hotspot: self.hotspot(index),
+            })
+            .collect();
+
+        FrameSummary { images }
+    }
+}
+
+/// The images of a frame without their pixels (see [`Frame::summary`]).
+///
+/// Serializes as a list of images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct FrameSummary {
+    images: Vec<ImageSummary>,
+}
+
+impl FrameSummary {
+    /// The size and hotspot of each image, one per size.
+    #[must_use]
+    pub fn images(&self) -> &[ImageSummary] {
+        &self.images
+    }
+}
+
+/// The size and hotspot of a single image of a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ImageSummary {
+    width: u32,
+    height: u32,
+    hotspot: Option<Hotspot>,
+}
+
+impl ImageSummary {
+    /// The width of the image, in pixels.
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image, in pixels.
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The hotspot of the image, if it is a cursor (see [`Frame::hotspot`]).
+    #[must_use]
+    pub const fn hotspot(&self) -> Option<Hotspot> {
+        self.hotspot
+    }
+}
+
+/// The position within a cursor image that corresponds to the pointer's location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Hotspot {
+    x: u16,
+    y: u16,
+}
+
+impl Hotspot {
+    /// Create a hotspot at the given coordinates.
+    #[must_use]
+    pub const fn new(x: u16, y: u16) -> Self {
+        Self { x, y }
+    }
+
+    /// The horizontal offset from the left edge of the image, in pixels.
+    #[must_use]
+    pub const fn x(&self) -> u16 {
+        self.x
+    }
+
+    /// The vertical offset from the top edge of the image, in pixels.
+    #[must_use]
+    pub const fn y(&self) -> u16 {
+        self.y
+    }
+}
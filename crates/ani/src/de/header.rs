@@ -1,3 +1,5 @@
+use std::mem;
+
 use bitflags::bitflags;
 
 bitflags! {
@@ -21,7 +23,7 @@ pub struct Header {
     frames: u32,
     steps: u32,
 
-    // Not used.
+    // Only used when the frames are raw bitmaps (i.e., `Flag::ICON` is not set).
     x: u32,
     y: u32,
     bit_count: u32,
@@ -31,29 +33,123 @@ pub struct Header {
     flags: Flag,
 }
 
+// The header is read directly from the file, so its layout must match the specification.
+const _: () = assert!(mem::size_of::<Header>() == 36);
+
 impl Header {
+    /// The header of an animation made up of a single icon frame (e.g., a static cursor).
+    pub(crate) const fn single_frame() -> Self {
+        Self {
+            size: 36,
+            frames: 1,
+            steps: 1,
+            x: 0,
+            y: 0,
+            bit_count: 0,
+            planes: 0,
+            jif_rate: 0,
+            flags: Flag::ICON,
+        }
+    }
+
+    /// The header of an animation made up of `frames` icon frames, shown in order.
+    pub(crate) const fn icon_frames(frames: u32, jif_rate: u32) -> Self {
+        Self {
+            frames,
+            steps: frames,
+            jif_rate,
+            ..Self::single_frame()
+        }
+    }
+
+    /// Update the number of frames and steps, and whether the animation has a custom sequence.
+    pub(crate) fn set_counts(&mut self, frames: u32, steps: u32, has_sequence: bool) {
+        self.frames = frames;
+        self.steps = steps;
+        self.flags.set(Flag::SEQUENCE, has_sequence);
+    }
+
+    pub(crate) const fn set_jif_rate(&mut self, jif_rate: u32) {
+        self.jif_rate = jif_rate;
+    }
+
     /// The length of the ANI header (should always be 36).
+    #[must_use]
     pub const fn size(&self) -> u32 {
         self.size
     }
 
     /// The number of frames we can expect to find in the `fram` chunk.
+    #[must_use]
     pub const fn frames(&self) -> u32 {
         self.frames
     }
 
     /// The number of steps in the animation loop.
+    #[must_use]
     pub const fn steps(&self) -> u32 {
         self.steps
     }
 
+    /// The width of the frames, if they are raw bitmaps.
+    #[must_use]
+    pub const fn width(&self) -> u32 {
+        self.x
+    }
+
+    /// The height of the frames, if they are raw bitmaps.
+    #[must_use]
+    pub const fn height(&self) -> u32 {
+        self.y
+    }
+
+    /// The number of bits per pixel, if the frames are raw bitmaps.
+    #[must_use]
+    pub const fn bit_count(&self) -> u32 {
+        self.bit_count
+    }
+
+    /// The number of color planes (should always be 1), if the frames are raw bitmaps.
+    #[must_use]
+    pub const fn planes(&self) -> u32 {
+        self.planes
+    }
+
     /// The default display rate in, jiffies (1/60 seconds).
+    #[must_use]
     pub const fn jif_rate(&self) -> u32 {
         self.jif_rate
     }
 
     /// Bit flags.
+    #[must_use]
     pub const fn flags(&self) -> &Flag {
         &self.flags
     }
 }
+
+/// Serializes the fields by their getters' names (e.g., `width` rather than `x`), and the flags
+/// as a list of names (e.g., `["ICON"]`). The size, which is always 36, is left out.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Header {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct as _;
+
+        let flags = self
+            .flags
+            .iter_names()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+
+        let mut header = serializer.serialize_struct("Header", 8)?;
+        header.serialize_field("frames", &self.frames)?;
+        header.serialize_field("steps", &self.steps)?;
+        header.serialize_field("width", &self.x)?;
+        header.serialize_field("height", &self.y)?;
+        header.serialize_field("bit_count", &self.bit_count)?;
+        header.serialize_field("planes", &self.planes)?;
+        header.serialize_field("jif_rate", &self.jif_rate)?;
+        header.serialize_field("flags", &flags)?;
+        header.end()
+    }
+}
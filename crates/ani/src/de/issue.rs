@@ -0,0 +1,95 @@
+use std::fmt;
+
+/// Represents an inconsistency within a decoded ANI file (see [`crate::de::Ani::validate`]).
+///
+/// Issues don't prevent the animation from being used, but the result may not look as intended
+/// (e.g., a frame may be shown for the wrong amount of time).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    /// The number of display rates doesn't match the number of steps in the header.
+    ///
+    /// Steps without a rate are shown for the default display rate, and extra rates are ignored
+    /// (see [`crate::de::Ani::timeline`]).
+    RateCountMismatch {
+        /// The number of steps declared by the header.
+        expected: u32,
+        /// The number of display rates in the `rate` chunk.
+        actual: usize,
+    },
+
+    /// The length of the sequence doesn't match the number of steps in the header.
+    ///
+    /// The sequence is played as is, so it decides the number of steps.
+    SequenceLengthMismatch {
+        /// The number of steps declared by the header.
+        expected: u32,
+        /// The number of entries in the `seq ` chunk.
+        actual: usize,
+    },
+
+    /// A step of the sequence refers to a frame that does not exist.
+    SequenceOutOfRange {
+        /// The position within the sequence.
+        step: usize,
+        /// The frame the step refers to.
+        index: u32,
+        /// The number of frames available.
+        frames: usize,
+    },
+
+    /// The number of frames doesn't match the number declared by the header.
+    FrameCountMismatch {
+        /// The number of frames declared by the header.
+        expected: u32,
+        /// The number of frames in the `fram` chunk.
+        actual: usize,
+    },
+
+    /// There is no sequence, but the number of steps in the header doesn't match the number of
+    /// frames (which it must, since each frame is a step).
+    ///
+    /// Each frame is shown once, in order, as if the header declared one step per frame.
+    StepCountMismatch {
+        /// The number of steps declared by the header.
+        steps: u32,
+        /// The number of frames in the `fram` chunk.
+        frames: usize,
+    },
+
+    /// The frames are stored as ICO files, but the header doesn't set [`crate::de::Flag::ICON`].
+    MissingIconFlag,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::RateCountMismatch { expected, actual } => write!(
+                f,
+                "expected {expected} display rates (one per step), found {actual}"
+            ),
+            Self::SequenceLengthMismatch { expected, actual } => write!(
+                f,
+                "expected {expected} steps in the sequence, found {actual}"
+            ),
+            Self::SequenceOutOfRange {
+                step,
+                index,
+                frames,
+            } => write!(
+                f,
+                "step {step} refers to frame {index}, but there are only {frames} frames"
+            ),
+            Self::FrameCountMismatch { expected, actual } => {
+                write!(f, "expected {expected} frames, found {actual}")
+            }
+            Self::StepCountMismatch { steps, frames } => write!(
+                f,
+                "expected {frames} steps (one per frame, without a sequence), found {steps}"
+            ),
+            Self::MissingIconFlag => {
+                "frames are stored as icons, but the ICON flag is not set".fmt(f)
+            }
+        }
+    }
+}
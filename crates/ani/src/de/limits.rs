@@ -0,0 +1,119 @@
+use crate::de::error::DecodeError;
+use crate::de::parser::Identifier;
+
+/// Bounds on the resources an ANI file may claim while it is decoded.
+///
+/// The sizes and counts in an ANI file can't be trusted: a file of a few bytes can declare
+/// billions of frames, or a chunk of several gigabytes. Decoding fails with a dedicated error
+/// (e.g., [`DecodeError::TooManyFrames`]) as soon as a limit is exceeded, before the memory is
+/// allocated. The defaults are generous for real cursors, which rarely have more than a few
+/// hundred frames of up to 256x256 pixels.
+///
+/// ```
+/// # use ani::de::Limits;
+/// let limits = Limits::default().with_max_frames(64);
+/// assert_eq!(limits.max_frames(), 64);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    frames: u32,
+    chunk_size: u32,
+    pixels: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            frames: 4096,
+            chunk_size: 256 * 1024 * 1024,
+            pixels: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl Limits {
+    /// No limits at all, for input that is trusted.
+    #[must_use]
+    pub const fn unlimited() -> Self {
+        Self {
+            frames: u32::MAX,
+            chunk_size: u32::MAX,
+            pixels: u64::MAX,
+        }
+    }
+
+    /// Limit the number of frames (defaults to 4096).
+    #[must_use]
+    pub const fn with_max_frames(mut self, max_frames: u32) -> Self {
+        self.frames = max_frames;
+        self
+    }
+
+    /// Limit the size of any single chunk, in bytes (defaults to 256 MiB).
+    #[must_use]
+    pub const fn with_max_chunk_size(mut self, max_chunk_size: u32) -> Self {
+        self.chunk_size = max_chunk_size;
+        self
+    }
+
+    /// Limit the number of pixels of every image of every frame combined (defaults to 64 Mi,
+    /// or 256 MiB as RGBA).
+    #[must_use]
+    pub const fn with_max_pixels(mut self, max_pixels: u64) -> Self {
+        self.pixels = max_pixels;
+        self
+    }
+
+    /// The maximum number of frames.
+    #[must_use]
+    pub const fn max_frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// The maximum size of any single chunk, in bytes.
+    #[must_use]
+    pub const fn max_chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    /// The maximum number of pixels of every image of every frame combined.
+    #[must_use]
+    pub const fn max_pixels(&self) -> u64 {
+        self.pixels
+    }
+
+    pub(crate) const fn check_frames(&self, frames: u32) -> Result<(), DecodeError> {
+        if frames > self.frames {
+            return Err(DecodeError::TooManyFrames {
+                declared: frames,
+                limit: self.frames,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) const fn check_chunk(
+        &self,
+        identifier: Identifier,
+        size: u32,
+    ) -> Result<(), DecodeError> {
+        if size > self.chunk_size {
+            return Err(DecodeError::ChunkTooLarge {
+                identifier,
+                size,
+                limit: self.chunk_size,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) const fn check_pixels(&self, pixels: u64) -> Result<(), DecodeError> {
+        if pixels > self.pixels {
+            return Err(DecodeError::TooManyPixels { limit: self.pixels });
+        }
+
+        Ok(())
+    }
+}
@@ -1,21 +1,85 @@
-#[derive(Debug, Clone)]
+use std::collections::BTreeMap;
+
+use crate::de::parser::Identifier;
+
+/// Represents the `INFO` list of an ANI file.
+///
+/// Every subchunk of the list is kept, keyed by its identifier (e.g., `INAM` for the title). The
+/// common ones have their own getters, and the rest can be read with [`Self::get`].
+#[derive(Debug, Clone, Default)]
 pub struct Metadata {
-    title: Option<String>,
-    author: Option<String>,
+    fields: BTreeMap<Identifier, String>,
 }
 
 impl Metadata {
-    pub const fn new(title: Option<String>, author: Option<String>) -> Self {
-        Self { title, author }
+    /// Create metadata from its individual fields.
+    #[must_use]
+    pub fn new(title: Option<String>, author: Option<String>) -> Self {
+        let fields = [(*b"INAM", title), (*b"IART", author)]
+            .into_iter()
+            .filter_map(|(identifier, value)| Some((identifier, value?)))
+            .collect();
+
+        Self { fields }
+    }
+
+    /// Create metadata from the subchunks of an `INFO` list.
+    #[must_use]
+    pub const fn from_fields(fields: BTreeMap<Identifier, String>) -> Self {
+        Self { fields }
     }
 
-    /// The name of the cursor, if available.
+    /// The name of the cursor (`INAM`), if available.
+    #[must_use]
     pub fn title(&self) -> Option<&str> {
-        self.title.as_deref()
+        self.get(*b"INAM")
     }
 
-    /// The author of the cursor, if available.
+    /// The author of the cursor (`IART`), if available.
+    #[must_use]
     pub fn author(&self) -> Option<&str> {
-        self.author.as_deref()
+        self.get(*b"IART")
+    }
+
+    /// The copyright notice (`ICOP`), if available.
+    #[must_use]
+    pub fn copyright(&self) -> Option<&str> {
+        self.get(*b"ICOP")
+    }
+
+    /// Comments about the cursor (`ICMT`), if available.
+    #[must_use]
+    pub fn comment(&self) -> Option<&str> {
+        self.get(*b"ICMT")
+    }
+
+    /// The software used to create the cursor (`ISFT`), if available.
+    #[must_use]
+    pub fn software(&self) -> Option<&str> {
+        self.get(*b"ISFT")
+    }
+
+    /// The value of the subchunk `identifier`, if available.
+    #[must_use]
+    pub fn get(&self, identifier: Identifier) -> Option<&str> {
+        self.fields.get(&identifier).map(String::as_str)
+    }
+
+    /// Every subchunk of the `INFO` list, keyed by identifier.
+    #[must_use]
+    pub const fn fields(&self) -> &BTreeMap<Identifier, String> {
+        &self.fields
+    }
+}
+
+/// Serializes as a map from each identifier (as a string, e.g., `"INAM"`) to its value.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Metadata {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.fields
+                .iter()
+                .map(|(identifier, value)| (String::from_utf8_lossy(identifier), value)),
+        )
     }
 }
@@ -2,41 +2,109 @@
 
 #![allow(dead_code)]
 
+mod bitmap;
+mod borrowed;
 mod error;
+mod frame;
 mod header;
+mod issue;
+mod limits;
 mod metadata;
+mod options;
 mod parser;
+mod reader;
+mod warning;
 
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::mem;
 use std::path::Path;
-use std::{fs, io, mem};
 
-use error::DecodeError;
-use header::Header;
-use ico::IconImage;
-use metadata::Metadata;
-use parser::Parser;
+pub use borrowed::AniRef;
+pub use error::{DecodeError, FrameError};
+pub use frame::{Frame, FrameSummary, Hotspot, ImageSummary};
+pub use header::{Flag, Header};
+pub use issue::Issue;
+pub use limits::Limits;
+pub use metadata::Metadata;
+pub use options::{DecodeMode, DecodeOptions};
+pub use parser::Identifier;
+use parser::{to_usize, Parser, IDENTIFIER_SIZE};
+pub use reader::AniReader;
 use tracing::debug;
-
-use crate::de::parser::Identifier;
+pub use warning::Warning;
 
 /// The unit of measurement for a frame's display rate.
+///
+/// Prefer [`jiffies_to_ms`] (or [`jiffies_to_ms_f64`]) for conversions: rounding the length of
+/// a jiffy (to 17ms) makes every rate longer than it should be (e.g., 102ms for 6 jiffies).
 pub const JIFFY: f32 = 1000.0 / 60.0;
 
+/// Convert a display rate in jiffies (1/60 seconds) to milliseconds, rounded to the nearest one.
+///
+/// The conversion is done with integers (`jiffies * 1000 / 60`), rounding each rate on its own,
+/// so the error never exceeds half a millisecond per step (e.g., 6 jiffies are exactly 100ms).
+/// Rates too long to fit in a `u32` of milliseconds saturate at [`u32::MAX`].
+#[must_use]
+pub fn jiffies_to_ms(jiffies: u32) -> u32 {
+    let milliseconds = (u64::from(jiffies) * 1000 + 30) / 60;
+    u32::try_from(milliseconds).unwrap_or(u32::MAX)
+}
+
+/// Convert a display rate in jiffies (1/60 seconds) to fractional milliseconds (e.g., for
+/// display, or to scale it before rounding).
+#[must_use]
+pub fn jiffies_to_ms_f64(jiffies: u32) -> f64 {
+    f64::from(jiffies) * 1000.0 / 60.0
+}
+
+/// Convert a duration in milliseconds to the nearest number of jiffies (1/60 seconds).
+///
+/// Durations shorter than half a jiffy become zero, which players either skip or clamp (see
+/// [`Warning::ZeroDisplayRate`]).
+#[must_use]
+pub fn ms_to_jiffies(milliseconds: u32) -> u32 {
+    let jiffies = (u64::from(milliseconds) * 60 + 500) / 1000;
+    u32::try_from(jiffies).unwrap_or(u32::MAX)
+}
+
 /// Represents the contents of an ANI file.
+///
+/// Besides being decoded, an animation can be created with [`Self::new`] and edited (e.g., to
+/// drop or reorder frames, or change their timing), then encoded again with [`Self::encode`].
+#[derive(Clone)]
 pub struct Ani {
     metadata: Option<Metadata>,
     header: Header,
     rates: Option<Vec<u32>>,
     sequence: Option<Vec<u32>>,
-    frames: Vec<Vec<IconImage>>,
+    frames: Vec<Frame>,
+    warnings: Vec<Warning>,
+    partial: bool,
 }
 
 impl Ani {
+    /// Create an animation that shows each of `frames` in order, at the default display rate
+    /// ([`crate::ser::DEFAULT_JIF_RATE`]).
+    #[must_use]
+    pub fn new(frames: Vec<Frame>) -> Self {
+        let count = u32::try_from(frames.len()).unwrap_or(u32::MAX);
+
+        Self {
+            metadata: None,
+            header: Header::icon_frames(count, crate::ser::DEFAULT_JIF_RATE),
+            rates: None,
+            sequence: None,
+            frames,
+            warnings: Vec::new(),
+            partial: false,
+        }
+    }
+
     /// Read and decode an ANI file.
     ///
-    /// # Panics
-    ///
-    /// This function panics on architectures where `usize` is smaller than a `u32`.
+    /// The file is decoded as leniently as [`Self::from_bytes`] does. To be strict, or to change
+    /// the [`Limits`], use [`DecodeOptions`] instead.
     ///
     /// # Errors
     ///
@@ -44,14 +112,66 @@ impl Ani {
     ///
     /// - Cannot read the file at path.
     /// - Data does not follow the ANI file format specification.
-    pub fn open(path: &Path, strict: bool) -> Result<Self, DecodeError> {
-        let data = fs::read(path).map_err(|err| DecodeError::ReadFailure { source: err })?;
+    /// - Data exceeds the default [`Limits`].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DecodeError> {
+        DecodeOptions::new().open(path)
+    }
+
+    /// Decode ANI data from a stream, one chunk at a time.
+    ///
+    /// Unlike [`Self::from_bytes`], the data doesn't have to be loaded into memory up front;
+    /// only the chunk currently being decoded is. Like [`Self::from_bytes`], the chunks may be
+    /// in any order.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - Cannot read from `reader`.
+    /// - Data has an invalid file signature.
+    /// - Data does not follow the ANI file format specification.
+    /// - Data exceeds the default [`Limits`].
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self, DecodeError> {
+        Self::from_reader_with_limits(reader, &Limits::default())
+    }
+
+    /// Like [`Self::from_reader`], but with custom [`Limits`] instead of the defaults.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error for the same reasons as [`Self::from_reader`].
+    pub fn from_reader_with_limits<R: Read>(
+        mut reader: R,
+        limits: &Limits,
+    ) -> Result<Self, DecodeError> {
+        let mut fram = Vec::new();
+        let chunks = reader::scan(&mut reader, limits, |reader, size| {
+            fram = reader::read_chunk_body(reader, *b"LIST", size)?;
+            Ok(())
+        })?;
 
-        if strict {
-            Self::from_bytes_strict(&data)
-        } else {
-            Self::from_bytes(&data)
+        let header = chunks
+            .header
+            .ok_or(DecodeError::MissingChunk { expected: *b"anih" })?;
+
+        if !chunks.has_frames {
+            return Err(DecodeError::MissingChunk { expected: *b"fram" });
         }
+
+        let frames = parse_fram_chunk(&mut Parser::new(&fram), &header, limits)?;
+
+        let mut ani = Self {
+            metadata: chunks.metadata,
+            header,
+            rates: chunks.rates,
+            sequence: chunks.sequence,
+            frames,
+            warnings: chunks.warnings,
+            partial: false,
+        };
+        ani.warnings.extend(ani.rate_warnings());
+
+        Ok(ani)
     }
 
     /// Decode ANI data.
@@ -60,26 +180,39 @@ impl Ani {
     /// specification. If you are not sure whether the data is structured properly, use
     /// [`Self::from_bytes`] instead.
     ///
-    /// # Panics
-    ///
-    /// This function panics on architectures where `usize` is smaller than a `u32`.
-    ///
     /// # Errors
     ///
     /// This function returns an error if:
     ///
     /// - Data has an invalid file signature.
     /// - Data does not follow the ANI file format specification.
+    /// - Data exceeds the default [`Limits`].
     pub fn from_bytes_strict(data: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_bytes_strict_with_limits(data, &Limits::default())
+    }
+
+    /// Like [`Self::from_bytes_strict`], but with custom [`Limits`] instead of the defaults.
+    pub(crate) fn from_bytes_strict_with_limits(
+        data: &[u8],
+        limits: &Limits,
+    ) -> Result<Self, DecodeError> {
         let mut parser = Parser::new(data);
-        validate_signature(&mut parser)?;
+        validate_signature(&mut parser, true)?;
 
         let metadata = match parser.expect_identifier(*b"LIST") {
-            Ok(()) => parser
-                .read_size()
-                .and_then(|_| parser.expect_identifier(*b"INFO"))
-                .and_then(|()| parse_info_chunk(&mut parser))
-                .map(Some)?,
+            Ok(()) => {
+                let size = parser.read_size()?;
+                parser.expect_identifier(*b"INFO")?;
+                // The list type is included in the size of the chunk.
+                let size = size.checked_sub(4).ok_or(DecodeError::TruncatedChunk {
+                    identifier: *b"LIST",
+                    declared: size,
+                    available: parser.bytes_remaining(),
+                })?;
+                let data = parser.read_chunk(*b"LIST", size)?;
+                parser.skip_padding(size);
+                Some(parse_info_chunk(&mut Parser::new(data))?)
+            }
             Err(DecodeError::UnexpectedIdentifier { .. }) => None,
             Err(err) => return Err(err),
         };
@@ -104,7 +237,7 @@ impl Ani {
             .expect_identifier(*b"LIST")
             .and_then(|()| parser.read_size())
             .and_then(|_| parser.expect_identifier(*b"fram"))
-            .and_then(|()| parse_fram_chunk(&mut parser, header.frames()))?;
+            .and_then(|()| parse_fram_chunk(&mut parser, &header, limits))?;
 
         Ok(Self {
             metadata,
@@ -112,137 +245,103 @@ impl Ani {
             rates,
             sequence,
             frames,
+            warnings: Vec::new(),
+            partial: false,
         })
     }
 
     /// Decode ANI data.
     ///
     /// This function does its best to parse the data, whether the chunks are the proper order
-    /// or not. If you know that the data is structured correctly, you can use
+    /// or not. Chunks that aren't part of the ANI file format are skipped, and reported by
+    /// [`Self::warnings`]. If you know that the data is structured correctly, you can use
     /// [`Self::from_bytes_strict`] instead.
     ///
-    /// # Panics
-    ///
-    /// This function panics on architectures where `usize` is smaller than a `u32`.
-    ///
     /// # Errors
     ///
     /// This function returns an error if:
     ///
     /// - Data has an invalid file signature.
     /// - Data does not follow the ANI file format specification.
+    /// - Data exceeds the default [`Limits`].
     pub fn from_bytes(data: &[u8]) -> Result<Self, DecodeError> {
-        #[derive(PartialEq, Eq)]
-        enum Kind {
-            Metadata,
-            Header,
-            Rate,
-            Sequence,
-            Frames,
-        }
-
-        struct Chunk {
-            kind: Kind,
-            data: Vec<u8>,
-        }
-
-        let mut parser = Parser::new(data);
-        validate_signature(&mut parser)?;
-        let mut chunks = Vec::<Chunk>::new();
-
-        while parser.bytes_remaining() > 0 {
-            if parser.bytes_remaining() == 1 {
-                // TODO: Padding byte maybe?
-                // https://en.wikipedia.org/wiki/Resource_Interchange_File_Format#Explanation
-                _ = parser.read_bytes(1);
-                continue;
-            }
-
-            let identifier = parser.read::<Identifier>()?;
-            debug!("identifier: {:?}", String::from_utf8_lossy(&identifier));
-            debug!("bytes remaining: {}", parser.bytes_remaining());
-
-            let (kind, size) = match &identifier {
-                b"LIST" => {
-                    let s = parser.read_size()?;
-                    let next = parser.read::<Identifier>()?;
+        Self::from_bytes_with_limits(data, &Limits::default())
+    }
 
-                    match &next {
-                        b"info" => (Kind::Metadata, s - 4),
-                        b"fram" => (Kind::Frames, s - 4),
-                        _ => return Err(DecodeError::UnknownIdentifier { actual: next }),
-                    }
-                }
-                b"anih" => {
-                    let size = parser.peek_size()?;
-                    (Kind::Header, 4 + size)
-                }
-                b"rate" => {
-                    let size = parser.peek_size()?;
-                    (Kind::Rate, 4 + size)
-                }
-                b"seq " => {
-                    let size = parser.peek_size()?;
-                    (Kind::Sequence, 4 + size)
-                }
-                _ => return Err(DecodeError::UnknownIdentifier { actual: identifier }),
-            };
+    /// Like [`Self::from_bytes`], but with custom [`Limits`] instead of the defaults.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error for the same reasons as [`Self::from_bytes`].
+    pub fn from_bytes_with_limits(data: &[u8], limits: &Limits) -> Result<Self, DecodeError> {
+        Self::from_bytes_tolerant(data, limits, false)
+    }
 
-            chunks.push(Chunk {
-                kind,
-                data: parser.read_bytes(usize::try_from(size).expect("u32 overflowed usize"))?,
-            });
-        }
+    /// Decode ANI data as [`Self::from_bytes`] does, but if `recover` is set, keep the complete
+    /// frames of a file that ends early instead of failing (see [`DecodeMode::Recover`]).
+    pub(crate) fn from_bytes_tolerant(
+        data: &[u8],
+        limits: &Limits,
+        recover: bool,
+    ) -> Result<Self, DecodeError> {
+        AniRef::parse(data, *limits, recover)?.decode()
+    }
 
-        let metadata = if let Some(chunk) = chunks.iter().find(|c| c.kind == Kind::Metadata) {
-            let mut parser = Parser::new(&chunk.data);
-            Some(parse_info_chunk(&mut parser)?)
-        } else {
-            None
-        };
+    /// Like [`Self::from_bytes`], but hands over the warnings instead of keeping them, so
+    /// [`Self::warnings`] of the result is empty.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error for the same reasons as [`Self::from_bytes`].
+    pub fn from_bytes_with_warnings(data: &[u8]) -> Result<(Self, Vec<Warning>), DecodeError> {
+        let mut ani = Self::from_bytes(data)?;
+        let warnings = mem::take(&mut ani.warnings);
 
-        let header = chunks
-            .iter()
-            .find(|chunk| chunk.kind == Kind::Header)
-            .ok_or(DecodeError::MissingChunk { expected: *b"anih" })
-            .and_then(|chunk| {
-                let mut parser = Parser::new(&chunk.data);
-                parse_anih_chunk(&mut parser)
-            })?;
+        Ok((ani, warnings))
+    }
 
-        let rates = if let Some(chunk) = chunks.iter().find(|c| c.kind == Kind::Rate) {
-            let mut parser = Parser::new(&chunk.data);
-            Some(parse_rate_chunk(&mut parser)?)
-        } else {
-            None
-        };
+    /// Decode a static Windows cursor (`.cur`) as an animation with a single frame.
+    ///
+    /// This allows themes that mix animated and static cursors to be handled the same way.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the data is not a valid CUR (or ICO) file.
+    pub fn from_cur_bytes(data: &[u8]) -> Result<Self, DecodeError> {
+        decode_icon(data, 0).map(Self::single_frame)
+    }
 
-        let sequence = if let Some(chunk) = chunks.iter().find(|c| c.kind == Kind::Sequence) {
-            let mut parser = Parser::new(&chunk.data);
-            Some(parse_seq_chunk(&mut parser)?)
-        } else {
-            None
-        };
+    /// Decode a PNG image as an animation with a single frame.
+    ///
+    /// PNG images don't carry a hotspot, so [`Frame::hotspot`] always returns `None`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the data is not a valid PNG image.
+    pub fn from_png_bytes(data: &[u8]) -> Result<Self, DecodeError> {
+        let image = ico::IconImage::read_png(io::Cursor::new(data)).map_err(|err| {
+            DecodeError::InvalidFrame {
+                index: 0,
+                source: err.into(),
+            }
+        })?;
 
-        let frames = chunks
-            .iter()
-            .find(|chunk| chunk.kind == Kind::Frames)
-            .ok_or(DecodeError::MissingChunk { expected: *b"fram" })
-            .and_then(|chunk| {
-                let mut parser = Parser::new(&chunk.data);
-                parse_fram_chunk(&mut parser, header.frames())
-            })?;
+        Ok(Self::single_frame(Frame::new(vec![image])))
+    }
 
-        Ok(Self {
-            metadata,
-            header,
-            rates,
-            sequence,
-            frames,
-        })
+    fn single_frame(frame: Frame) -> Self {
+        Self {
+            metadata: None,
+            header: Header::single_frame(),
+            rates: None,
+            sequence: None,
+            frames: vec![frame],
+            warnings: Vec::new(),
+            partial: false,
+        }
     }
 
-    /// Additional information about the cursor (title, author).
+    /// Additional information about the cursor (e.g., title, author, copyright).
     #[must_use]
     pub const fn metadata(&self) -> Option<&Metadata> {
         self.metadata.as_ref()
@@ -270,9 +369,399 @@ impl Ani {
 
     /// Collection of images stored within the ANI file.
     #[must_use]
-    pub fn frames(&self) -> &[Vec<IconImage>] {
+    pub fn frames(&self) -> &[Frame] {
         &self.frames
     }
+
+    /// The steps of the animation as they are played back: the index of the frame to show, and
+    /// for how long (in milliseconds).
+    ///
+    /// The header, the sequence and the display rates may disagree on the number of steps (see
+    /// [`Self::validate`]), which is resolved as follows:
+    ///
+    /// - With a sequence, there is one step per entry of the sequence. The frame indices are
+    ///   taken as is, so they may be out of range (see [`Issue::SequenceOutOfRange`]).
+    /// - Without a sequence, each frame is shown once, in order, whatever the number of steps
+    ///   in the header (see [`Issue::StepCountMismatch`]).
+    /// - Each step is shown for the display rate at the same position. Steps without one use the
+    ///   default from the header, and extra rates are ignored.
+    pub fn timeline(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        self.timeline_jiffies()
+            .map(|(frame, jiffies)| (frame, jiffies_to_ms(jiffies)))
+    }
+
+    /// The steps of the animation, like [`Self::timeline`], but with how long each frame is shown
+    /// in jiffies (1/60 seconds), as they are stored.
+    ///
+    /// Use this to scale the durations before rounding them (e.g., with [`jiffies_to_ms_f64`]),
+    /// rather than rounding them twice.
+    pub fn timeline_jiffies(&self) -> impl Iterator<Item = (usize, u32)> + '_ {
+        let steps = self.sequence.as_ref().map_or(self.frames.len(), Vec::len);
+
+        (0..steps).map(|step| {
+            let frame = self.sequence.as_ref().map_or(step, |sequence| {
+                to_usize(sequence[step]).unwrap_or(usize::MAX)
+            });
+            let jiffies = self
+                .rates
+                .as_ref()
+                .and_then(|rates| rates.get(step).copied())
+                .unwrap_or_else(|| self.header.jif_rate());
+
+            (frame, jiffies)
+        })
+    }
+
+    /// Replace the additional information about the cursor.
+    pub fn set_metadata(&mut self, metadata: Option<Metadata>) {
+        self.metadata = metadata;
+    }
+
+    /// Set the default display rate, in jiffies (1/60 seconds), used for steps without a rate.
+    pub const fn set_jif_rate(&mut self, jif_rate: u32) {
+        self.header.set_jif_rate(jif_rate);
+    }
+
+    /// Set (or remove) the display rate for each step of the animation, in jiffies.
+    ///
+    /// The number of rates should match the number of steps; see [`Self::validate`].
+    pub fn set_rates(&mut self, rates: Option<Vec<u32>>) {
+        self.rates = rates;
+    }
+
+    /// Set (or remove) the order in which the frames are displayed.
+    ///
+    /// The number of steps in the header is updated to match. Without a sequence, each frame is
+    /// shown once, in order.
+    pub fn set_sequence(&mut self, sequence: Option<Vec<u32>>) {
+        self.sequence = sequence;
+        self.update_header();
+    }
+
+    /// Append a frame to the end of the animation.
+    ///
+    /// Without a sequence, this adds a step that shows the frame; otherwise, the frame is only
+    /// shown once the sequence refers to it.
+    pub fn push_frame(&mut self, frame: Frame) {
+        self.frames.push(frame);
+        self.update_header();
+    }
+
+    /// Remove the frame at `index`, along with every step that shows it (and its rate).
+    ///
+    /// Steps that show a later frame are updated to keep showing it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_frame(&mut self, index: usize) -> Frame {
+        let frame = self.frames.remove(index);
+
+        let shown: Vec<bool> = match self.sequence {
+            Some(ref sequence) => sequence
+                .iter()
+                .map(|&i| to_usize(i).is_ok_and(|i| i != index))
+                .collect(),
+            None => (0..=self.frames.len()).map(|i| i != index).collect(),
+        };
+
+        if let Some(ref mut rates) = self.rates {
+            *rates = retain_steps(rates, &shown);
+        }
+
+        if let Some(ref mut sequence) = self.sequence {
+            *sequence = retain_steps(sequence, &shown)
+                .into_iter()
+                .map(|i| {
+                    if to_usize(i).is_ok_and(|i| i > index) {
+                        i - 1
+                    } else {
+                        i
+                    }
+                })
+                .collect();
+        }
+
+        self.update_header();
+        frame
+    }
+
+    /// Swap the frames at `a` and `b`, so that steps showing one now show the other.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` is out of bounds.
+    pub fn swap_frames(&mut self, a: usize, b: usize) {
+        self.frames.swap(a, b);
+    }
+
+    /// Mutable access to the frames, e.g., to edit their images.
+    ///
+    /// Use [`Self::push_frame`] and [`Self::remove_frame`] to add or remove frames, so the
+    /// header, rates and sequence stay consistent.
+    pub fn frames_mut(&mut self) -> &mut [Frame] {
+        &mut self.frames
+    }
+
+    /// Encode the animation as an ANI file.
+    ///
+    /// Only the title and author of the metadata are kept, and every frame is re-encoded as a
+    /// cursor.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the animation is invalid (see
+    /// [`crate::ser::AniBuilder::encode`]).
+    pub fn encode(&self) -> Result<Vec<u8>, crate::ser::EncodeError> {
+        crate::ser::AniBuilder::from(self).encode()
+    }
+
+    /// Whether the file ended early, so only the frames before the end were recovered (see
+    /// [`DecodeMode::Recover`]).
+    ///
+    /// The timing of a partial animation only covers the recovered frames: steps that showed a
+    /// missing frame are dropped, and rates that no longer line up with the steps are replaced
+    /// by the default display rate.
+    #[must_use]
+    pub const fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    /// Drop the steps (and rates) that show frames that weren't recovered, and the rates that
+    /// don't match the steps that are left. Only the first `frames` frames were recovered.
+    fn repair_timing(&mut self, frames: usize) {
+        if let Some(sequence) = self.sequence.take() {
+            let shown: Vec<bool> = sequence
+                .iter()
+                .map(|&i| to_usize(i).is_ok_and(|i| i < frames))
+                .collect();
+
+            self.rates = self
+                .rates
+                .take()
+                .filter(|rates| rates.len() == shown.len())
+                .map(|rates| retain_steps(&rates, &shown));
+            self.sequence = Some(retain_steps(&sequence, &shown)).filter(|s| !s.is_empty());
+        } else if let Some(ref mut rates) = self.rates {
+            rates.truncate(frames);
+        }
+
+        // Rates that don't line up with the steps are no better than the default display rate.
+        let steps = self.sequence.as_ref().map_or(frames, Vec::len);
+        self.rates = self.rates.take().filter(|rates| rates.len() == steps);
+
+        self.set_counts(frames);
+    }
+
+    /// Keep the header's counts in sync with the frames and sequence.
+    fn update_header(&mut self) {
+        self.set_counts(self.frames.len());
+    }
+
+    /// Set the header's counts for `frames` frames and the sequence.
+    fn set_counts(&mut self, frames: usize) {
+        let frames = u32::try_from(frames).unwrap_or(u32::MAX);
+        let steps = self
+            .sequence
+            .as_ref()
+            .map_or(Ok(frames), |sequence| u32::try_from(sequence.len()))
+            .unwrap_or(u32::MAX);
+
+        self.header
+            .set_counts(frames, steps, self.sequence.is_some());
+    }
+
+    /// Check the decoded animation for inconsistencies between its chunks.
+    ///
+    /// Decoding succeeds as long as each chunk is well-formed on its own, so this can be used
+    /// to find problems that would otherwise go unnoticed (e.g., a rate for every frame instead
+    /// of every step). An empty list means no issues were found; see [`Self::timeline`] for how
+    /// the animation is played back despite them.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let steps = self.header.steps();
+
+        if let Some(ref rates) = self.rates
+            && to_usize(steps).is_ok_and(|steps| steps != rates.len())
+        {
+            issues.push(Issue::RateCountMismatch {
+                expected: steps,
+                actual: rates.len(),
+            });
+        }
+
+        if let Some(ref sequence) = self.sequence {
+            if to_usize(steps).is_ok_and(|steps| steps != sequence.len()) {
+                issues.push(Issue::SequenceLengthMismatch {
+                    expected: steps,
+                    actual: sequence.len(),
+                });
+            }
+
+            for (step, &index) in sequence.iter().enumerate() {
+                if to_usize(index).map_or(true, |index| index >= self.frames.len()) {
+                    issues.push(Issue::SequenceOutOfRange {
+                        step,
+                        index,
+                        frames: self.frames.len(),
+                    });
+                }
+            }
+        } else if !self.frames.is_empty()
+            && to_usize(steps).is_ok_and(|steps| steps != self.frames.len())
+        {
+            issues.push(Issue::StepCountMismatch {
+                steps,
+                frames: self.frames.len(),
+            });
+        }
+
+        if to_usize(self.header.frames()).is_ok_and(|frames| frames != self.frames.len()) {
+            issues.push(Issue::FrameCountMismatch {
+                expected: self.header.frames(),
+                actual: self.frames.len(),
+            });
+        }
+
+        let is_icon = |frame: &Frame| frame.raw().is_some_and(is_icon_dir);
+        if !self.header.flags().contains(Flag::ICON) && self.frames.iter().any(is_icon) {
+            issues.push(Issue::MissingIconFlag);
+        }
+
+        issues
+    }
+
+    /// Problems with the data that were worked around while decoding it.
+    #[must_use]
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Warn about display rates that are missing or zero, which the animation's timing
+    /// depends on.
+    fn rate_warnings(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        match self.rates {
+            Some(ref rates) => {
+                for (step, _) in rates.iter().enumerate().filter(|&(_, &rate)| rate == 0) {
+                    warnings.push(Warning::ZeroDisplayRate { step: Some(step) });
+                }
+            }
+            None if self.header.steps() > 1 => {
+                let default = self.header.jif_rate();
+                warnings.push(Warning::MissingRateChunk { default });
+
+                if default == 0 {
+                    warnings.push(Warning::ZeroDisplayRate { step: None });
+                }
+            }
+            None => {}
+        }
+
+        warnings
+    }
+}
+
+/// The values of the steps for which `shown` is true (steps past the end of `shown` are kept).
+fn retain_steps(values: &[u32], shown: &[bool]) -> Vec<u32> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|&(step, _)| shown.get(step).copied().unwrap_or(true))
+        .map(|(_, &value)| value)
+        .collect()
+}
+
+/// The chunks the tolerant decoder knows about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Metadata,
+    Header,
+    Rate,
+    Sequence,
+    Frames,
+}
+
+/// A chunk found by the tolerant decoder.
+struct Chunk<'a> {
+    /// What the chunk contains, or `None` for unknown chunks, which are skipped.
+    kind: Option<Kind>,
+    /// The identifier of the chunk (or the list type, for `LIST` chunks) to report it by.
+    name: Identifier,
+    size: u32,
+    data: &'a [u8],
+}
+
+/// Read the next chunk, whatever it is.
+///
+/// If `recover` is set, a `fram` list that extends past the end of the data keeps whatever data
+/// there is, so the frames before the end can still be decoded.
+fn read_chunk_tolerant<'a>(
+    parser: &mut Parser<'a>,
+    limits: &Limits,
+    recover: bool,
+) -> Result<Chunk<'a>, DecodeError> {
+    let identifier = parser.read::<Identifier>()?;
+    debug!("identifier: {:?}", String::from_utf8_lossy(&identifier));
+    debug!("bytes remaining: {}", parser.bytes_remaining());
+
+    // Unknown chunks are skipped; `name` is what gets reported for them.
+    let (kind, name, size) = match &identifier {
+        b"LIST" => {
+            let s = parser.read_size()?;
+            let next = parser.read::<Identifier>()?;
+            // The list type is included in the size of the chunk.
+            let size = s.checked_sub(4).ok_or(DecodeError::TruncatedChunk {
+                identifier,
+                declared: s,
+                available: parser.bytes_remaining(),
+            })?;
+
+            let kind = match &next {
+                b"INFO" => Some(Kind::Metadata),
+                b"fram" => Some(Kind::Frames),
+                _ => None,
+            };
+            (kind, next, size)
+        }
+        b"anih" | b"rate" | b"seq " => {
+            let kind = match &identifier {
+                b"anih" => Kind::Header,
+                b"rate" => Kind::Rate,
+                _ => Kind::Sequence,
+            };
+
+            // Keep the size in the chunk data; the chunk parsers expect to read it.
+            let s = parser.peek_size()?;
+            let size = s.checked_add(4).ok_or(DecodeError::TruncatedChunk {
+                identifier,
+                declared: s,
+                available: parser.bytes_remaining(),
+            })?;
+            (Some(kind), identifier, size)
+        }
+        _ => (None, identifier, parser.read_size()?),
+    };
+
+    limits.check_chunk(name, size)?;
+
+    let data = if recover && kind == Some(Kind::Frames) {
+        let available = u32::try_from(parser.bytes_remaining()).unwrap_or(u32::MAX);
+        parser.read_chunk(identifier, size.min(available))?
+    } else {
+        parser.read_chunk(identifier, size)?
+    };
+    // <https://en.wikipedia.org/wiki/Resource_Interchange_File_Format#Explanation>
+    parser.skip_padding(size);
+
+    Ok(Chunk {
+        kind,
+        name,
+        size,
+        data,
+    })
 }
 
 /// Check if the file contains a valid signature (A.K.A. magic number).
@@ -282,22 +771,19 @@ impl Ani {
 /// the first chunk's identifier (always `RIFF`), followed by the chunk size (size of the ANI data),
 /// followed by the ANI chunk's identifier, `ACON`.
 ///
-/// # Panics
-///
-/// This function panics on architectures where `usize` is smaller than `u32`.
-///
 /// # Errors
 ///
 /// This function returns an error if:
 ///
 /// - There is not enough data remaining.
+/// - `check_size` is set and the data is smaller than the chunk size says.
 /// - The file signature is invalid.
-fn validate_signature(parser: &mut Parser) -> Result<(), DecodeError> {
+fn validate_signature(parser: &mut Parser, check_size: bool) -> Result<(), DecodeError> {
     parser.expect_identifier(*b"RIFF")?;
     let s = parser.read_size()?;
-    let size = usize::try_from(s).expect("u32 overflowed usize");
+    let size = to_usize(s)?;
 
-    if parser.bytes_remaining() < size {
+    if check_size && parser.bytes_remaining() < size {
         return Err(DecodeError::SizeMismatch {
             expected: size,
             actual: parser.bytes_remaining(),
@@ -310,35 +796,25 @@ fn validate_signature(parser: &mut Parser) -> Result<(), DecodeError> {
 
 /// Decode the chunk containing cursor metadata.
 ///
-/// # Panics
-///
-/// This function panics on architectures where `usize` is smaller than `u32`.
+/// The subchunks (e.g., `INAM`, `IART`, `ICOP`) may appear in any order. If a subchunk appears
+/// more than once, the last one wins.
 fn parse_info_chunk(parser: &mut Parser) -> Result<Metadata, DecodeError> {
-    let title = match parser.expect_identifier(*b"INAM") {
-        Ok(()) => {
-            let s = parser.read_size()?;
-            let size = usize::try_from(s).expect("u32 overflowed usize");
-            let bytes = parser.read_bytes(size)?;
-            let title = String::from_utf8_lossy(&bytes).to_string();
-            Some(title)
-        }
-        Err(DecodeError::UnexpectedIdentifier { .. }) => None,
-        Err(err) => return Err(err),
-    };
-
-    let author = match parser.expect_identifier(*b"IART") {
-        Ok(()) => {
-            let s = parser.read_size()?;
-            let size = usize::try_from(s).expect("u32 overflowed usize");
-            let bytes = parser.read_bytes(size)?;
-            let author = String::from_utf8_lossy(&bytes).to_string();
-            Some(author)
-        }
-        Err(DecodeError::UnexpectedIdentifier { .. }) => None,
-        Err(err) => return Err(err),
-    };
+    let mut fields = BTreeMap::new();
+
+    while parser.bytes_remaining() > 1 {
+        let identifier = parser.read::<Identifier>()?;
+        let size = parser.read_size()?;
+        let bytes = parser.read_chunk(identifier, size)?;
+        parser.skip_padding(size);
+
+        // INFO strings are NUL-terminated.
+        let value = String::from_utf8_lossy(bytes)
+            .trim_end_matches('\0')
+            .to_owned();
+        fields.insert(identifier, value);
+    }
 
-    Ok(Metadata::new(title, author))
+    Ok(Metadata::from_fields(fields))
 }
 
 /// Decode the chunk containing the ANI header.
@@ -349,15 +825,13 @@ fn parse_anih_chunk(parser: &mut Parser) -> Result<Header, DecodeError> {
         return Err(DecodeError::InvalidHeaderSize { actual: size });
     }
 
-    assert_eq!(mem::size_of::<Header>(), 36);
     let header = parser.read::<Header>()?;
     Ok(header)
 }
 
 /// Decode the chunk containing the display rate for each frame.
 fn parse_rate_chunk(parser: &mut Parser) -> Result<Vec<u32>, DecodeError> {
-    let s = parser.read_size()?;
-    let size = usize::try_from(s).expect("u32 overflowed usize");
+    let size = to_usize(parser.read_size()?)?;
 
     if !size.is_multiple_of(mem::size_of::<u32>()) {
         return Err(DecodeError::InvalidAlignmentU32);
@@ -376,8 +850,7 @@ fn parse_rate_chunk(parser: &mut Parser) -> Result<Vec<u32>, DecodeError> {
 
 /// Decode the chunk containing the frame ordering.
 fn parse_seq_chunk(parser: &mut Parser) -> Result<Vec<u32>, DecodeError> {
-    let s = parser.read_size()?;
-    let size = usize::try_from(s).expect("u32 overflowed usize");
+    let size = to_usize(parser.read_size()?)?;
 
     if !size.is_multiple_of(mem::size_of::<u32>()) {
         return Err(DecodeError::InvalidAlignmentU32);
@@ -397,42 +870,178 @@ fn parse_seq_chunk(parser: &mut Parser) -> Result<Vec<u32>, DecodeError> {
 /// Decode the chunk containing the frames.
 fn parse_fram_chunk(
     parser: &mut Parser,
-    frames_count: u32,
-) -> Result<Vec<Vec<IconImage>>, DecodeError> {
-    let mut frames = Vec::with_capacity(frames_count as usize);
+    header: &Header,
+    limits: &Limits,
+) -> Result<Vec<Frame>, DecodeError> {
+    let frames_count = header.frames();
+    limits.check_frames(frames_count)?;
+
+    // Each frame needs at least an identifier and a size, so the header can't be trusted to
+    // pre-allocate more frames than the remaining data could possibly hold.
+    let max_frames = parser.bytes_remaining() / (IDENTIFIER_SIZE + mem::size_of::<u32>());
+    let mut frames = Vec::with_capacity(to_usize(frames_count)?.min(max_frames));
+
+    let mut pixels = 0_u64;
+    for index in 0..to_usize(frames_count)? {
+        frames.push(parse_icon_chunk(
+            parser,
+            index,
+            header,
+            limits,
+            &mut pixels,
+        )?);
+    }
 
-    for _ in 0..frames_count {
-        parser.expect_identifier(*b"icon")?;
-        let s = parser.read_size()?;
-        let size = usize::try_from(s).expect("u32 overflowed usize");
+    Ok(frames)
+}
 
-        let buffer = parser.read_bytes(size)?;
-        let reader = io::Cursor::new(&buffer);
+/// Read the `icon` chunks of the frames without decoding them, borrowing their data.
+fn read_icon_chunks<'a>(
+    parser: &mut Parser<'a>,
+    header: &Header,
+    limits: &Limits,
+) -> Result<Vec<&'a [u8]>, DecodeError> {
+    let frames_count = header.frames();
+    limits.check_frames(frames_count)?;
+
+    // See `parse_fram_chunk`.
+    let max_frames = parser.bytes_remaining() / (IDENTIFIER_SIZE + mem::size_of::<u32>());
+    let mut frames = Vec::with_capacity(to_usize(frames_count)?.min(max_frames));
+
+    for _ in 0..to_usize(frames_count)? {
+        frames.push(read_icon_chunk(parser, limits)?);
+    }
 
-        let icon_dir = ico::IconDir::read(reader).expect("todo");
-        let mut images = Vec::with_capacity(icon_dir.entries().len());
+    Ok(frames)
+}
 
-        for entry in icon_dir.entries() {
-            let image = entry.decode().expect("todo");
-            images.push(image);
+/// Like [`read_icon_chunks`], but keeps the frames before the data runs out (unless there are
+/// none).
+fn salvage_icon_chunks<'a>(
+    parser: &mut Parser<'a>,
+    header: &Header,
+    limits: &Limits,
+) -> Result<Vec<&'a [u8]>, DecodeError> {
+    limits.check_frames(header.frames())?;
+    let mut frames = Vec::new();
+
+    for _ in 0..to_usize(header.frames())? {
+        match read_icon_chunk(parser, limits) {
+            Ok(frame) => frames.push(frame),
+            Err(DecodeError::NotEnoughBytes { .. } | DecodeError::TruncatedChunk { .. })
+                if !frames.is_empty() =>
+            {
+                break;
+            }
+            Err(err) => return Err(err),
         }
-
-        frames.push(images);
     }
 
     Ok(frames)
 }
 
+/// Decode the `icon` chunk of the frame at `index`, adding its pixels to `pixels`.
+fn parse_icon_chunk(
+    parser: &mut Parser,
+    index: usize,
+    header: &Header,
+    limits: &Limits,
+    pixels: &mut u64,
+) -> Result<Frame, DecodeError> {
+    let buffer = read_icon_chunk(parser, limits)?;
+    let frame = decode_frame(buffer, index, header)?;
+    *pixels = pixels.saturating_add(frame.pixels());
+    limits.check_pixels(*pixels)?;
+
+    Ok(frame)
+}
+
+/// Read the data of the next `icon` chunk.
+fn read_icon_chunk<'a>(parser: &mut Parser<'a>, limits: &Limits) -> Result<&'a [u8], DecodeError> {
+    parser.expect_identifier(*b"icon")?;
+    let size = parser.read_size()?;
+    limits.check_chunk(*b"icon", size)?;
+    let buffer = parser.read_chunk(*b"icon", size)?;
+    parser.skip_padding(size);
+
+    Ok(buffer)
+}
+
+/// Check if `data` starts with the header of an ICO (or CUR) file.
+fn is_icon_dir(data: &[u8]) -> bool {
+    // Reserved (always 0), followed by the resource type (1 for icons, 2 for cursors).
+    matches!(data, [0, 0, 1 | 2, 0, ..])
+}
+
+/// Decode the contents of an `icon` chunk, which the header describes.
+fn decode_frame(data: &[u8], index: usize, header: &Header) -> Result<Frame, DecodeError> {
+    // Some writers store ICO files without setting the flag (see `Issue::MissingIconFlag`).
+    if header.flags().contains(Flag::ICON) || is_icon_dir(data) {
+        return decode_icon(data, index);
+    }
+
+    let image = bitmap::decode(data, header)
+        .map_err(|source| DecodeError::InvalidFrame { index, source })?;
+
+    Ok(Frame::new(vec![image]).with_raw(data.to_vec()))
+}
+
+/// Decode a frame stored in the Windows ICO (or CUR) format.
+fn decode_icon(data: &[u8], index: usize) -> Result<Frame, DecodeError> {
+    let reader = io::Cursor::new(data);
+
+    let invalid_frame = |source| DecodeError::InvalidFrame { index, source };
+    let icon_dir = ico::IconDir::read(reader).map_err(|err| invalid_frame(err.into()))?;
+    let images = icon_dir
+        .entries()
+        .iter()
+        .map(|entry| decode_entry(entry).map_err(invalid_frame))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Frame::new(images).with_raw(data.to_vec()))
+}
+
+/// Decode an image of an ICO (or CUR) file.
+///
+/// Bitmaps are decoded by [`bitmap`], which handles their color tables and AND masks the same
+/// way as frames stored as raw bitmaps.
+fn decode_entry(entry: &ico::IconDirEntry) -> Result<ico::IconImage, FrameError> {
+    if entry.is_png() {
+        return Ok(entry.decode()?);
+    }
+
+    let mut image = bitmap::decode_entry(entry.data())?;
+    image.set_cursor_hotspot(entry.cursor_hotspot());
+    Ok(image)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use header::Flag;
+
+    /// Create a header for `frames` frames of the given size, at 24 bits per pixel.
+    fn header(frames: u32, width: u32, height: u32, flags: Flag) -> Header {
+        let fields = [
+            36,
+            36,
+            frames,
+            frames,
+            width,
+            height,
+            24,
+            1,
+            0,
+            flags.bits(),
+        ];
+        let data = fields.map(u32::to_le_bytes).concat();
+        parse_anih_chunk(&mut Parser::new(&data)).expect("expected header to be valid")
+    }
 
     #[test]
     fn signature() {
         let data = b"RIFF\x04\0\0\0ACON";
         let mut parser = Parser::new(data);
-        validate_signature(&mut parser).expect("expected hardcoded bytes to be valid");
+        validate_signature(&mut parser, true).expect("expected hardcoded bytes to be valid");
     }
 
     #[test]
@@ -443,6 +1052,21 @@ mod tests {
 
         assert_eq!(metadata.title(), Some("Default - Hoshimachi Suisei v1"));
         assert_eq!(metadata.author(), Some("Hoshiyomi"));
+
+        // Subchunks may appear in any order, and odd-sized ones are padded.
+        let data = b"ICOP\x05\0\0\0(c) N\0ISFT\x04\0\0\0ani\0IARL\x02\0\0\0US";
+        let mut parser = Parser::new(data);
+        let metadata = parse_info_chunk(&mut parser).expect("expected hardcoded bytes to be valid");
+
+        assert_eq!(metadata.title(), None);
+        assert_eq!(metadata.copyright(), Some("(c) N"));
+        assert_eq!(metadata.software(), Some("ani"));
+        assert_eq!(metadata.get(*b"IARL"), Some("US"));
+        assert_eq!(metadata.fields().len(), 3);
+
+        let mut parser = Parser::new(b"INAM\x06\0\0\0Arrow\0");
+        let metadata = parse_info_chunk(&mut parser).expect("expected hardcoded bytes to be valid");
+        assert_eq!(metadata.title(), Some("Arrow"));
     }
 
     #[test]
@@ -469,4 +1093,458 @@ mod tests {
         assert!(header.flags().contains(Flag::ICON));
         assert!(header.flags().contains(Flag::SEQUENCE));
     }
+
+    #[test]
+    fn truncated_chunk() {
+        let data = b"RIFF\x0e\0\0\0ACONrate\xff\0\0\0\0\0";
+        let result = Ani::from_bytes(data);
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::TruncatedChunk {
+                identifier: [b'r', b'a', b't', b'e'],
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn list_chunk_too_small() {
+        let data = b"RIFF\x10\0\0\0ACONLIST\x02\0\0\0fram";
+        let result = Ani::from_bytes(data);
+
+        assert!(matches!(result, Err(DecodeError::TruncatedChunk { .. })));
+    }
+
+    #[test]
+    fn invalid_frame() {
+        let data = b"icon\x04\0\0\0\xde\xad\xbe\xef";
+        let mut parser = Parser::new(data);
+        let result = parse_fram_chunk(&mut parser, &Header::single_frame(), &Limits::default());
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::InvalidFrame {
+                index: 0,
+                source: FrameError::InvalidImage { .. },
+            })
+        ));
+
+        // Without the icon flag, the frame is a raw bitmap, which is too short for its header.
+        let mut parser = Parser::new(data);
+        let result = parse_fram_chunk(
+            &mut parser,
+            &header(1, 2, 2, Flag::empty()),
+            &Limits::default(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::InvalidFrame {
+                index: 0,
+                source: FrameError::InvalidBitmap { .. },
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_frame_index() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let mut data = crate::ser::AniBuilder::new()
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        // Corrupt the icon directory of the second frame.
+        let second = data
+            .windows(4)
+            .enumerate()
+            .filter(|&(_, window)| window == b"icon")
+            .nth(1)
+            .map(|(i, _)| i)
+            .expect("expected two frames");
+        data[second + 8..second + 12].copy_from_slice(&[0xff; 4]);
+
+        let Err(err) = Ani::from_bytes(&data) else {
+            panic!("expected corrupt frame to be rejected");
+        };
+        assert!(matches!(err, DecodeError::InvalidFrame { index: 1, .. }));
+        assert_eq!(err.to_string(), "failed to decode frame 1");
+    }
+
+    #[test]
+    fn excessive_frame_count() {
+        let mut parser = Parser::new(&[]);
+        let header = header(u32::MAX, 0, 0, Flag::ICON);
+        let result = parse_fram_chunk(&mut parser, &header, &Limits::unlimited());
+
+        assert!(matches!(result, Err(DecodeError::NotEnoughBytes { .. })));
+    }
+
+    #[test]
+    fn limits() {
+        let mut parser = Parser::new(&[]);
+        let result = parse_fram_chunk(
+            &mut parser,
+            &header(u32::MAX, 0, 0, Flag::ICON),
+            &Limits::default(),
+        );
+        assert!(matches!(
+            result,
+            Err(DecodeError::TooManyFrames {
+                declared: u32::MAX,
+                limit: 4096
+            })
+        ));
+
+        let image = ico::IconImage::from_rgba_data(4, 4, vec![0; 4 * 4 * 4]);
+        let data = crate::ser::AniBuilder::new()
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let limits = Limits::default().with_max_pixels(16);
+        let result = Ani::from_bytes_with_limits(&data, &limits);
+        assert!(matches!(
+            result,
+            Err(DecodeError::TooManyPixels { limit: 16 })
+        ));
+        assert!(Ani::from_reader_with_limits(&data[..], &limits).is_err());
+        assert!(Ani::from_bytes_with_limits(&data, &limits.with_max_pixels(32)).is_ok());
+
+        let limits = Limits::default().with_max_chunk_size(8);
+        let result = Ani::from_bytes_with_limits(&data, &limits);
+        assert!(matches!(result, Err(DecodeError::ChunkTooLarge { .. })));
+        assert!(matches!(
+            Ani::from_reader_with_limits(&data[..], &limits),
+            Err(DecodeError::ChunkTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn static_cursor() {
+        let mut image = ico::IconImage::from_rgba_data(2, 2, vec![0xff; 2 * 2 * 4]);
+        image.set_cursor_hotspot(Some((1, 0)));
+
+        let mut icon_dir = ico::IconDir::new(ico::ResourceType::Cursor);
+        icon_dir.add_entry(ico::IconDirEntry::encode(&image).unwrap());
+        let mut data = Vec::new();
+        icon_dir.write(&mut data).unwrap();
+
+        let ani = Ani::from_cur_bytes(&data).expect("expected encoded cursor to be valid");
+
+        assert_eq!(ani.header().frames(), 1);
+        assert_eq!(ani.frames().len(), 1);
+        assert_eq!(ani.frames()[0].hotspot(0), Some(Hotspot::new(1, 0)));
+        assert_eq!(ani.frames()[0].rgba(0), Some(&[0xff; 2 * 2 * 4][..]));
+        assert_eq!(ani.frames()[0].raw(), Some(&data[..]));
+
+        #[cfg(feature = "image")]
+        assert_eq!(
+            ani.frames()[0]
+                .to_rgba_image(0)
+                .map(|image| image.dimensions()),
+            Some((2, 2))
+        );
+    }
+
+    #[test]
+    fn from_reader() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let data = crate::ser::AniBuilder::new()
+            .title("Arrow")
+            .author("Nic")
+            .rates(vec![1, 2, 3])
+            .sequence(vec![0, 1, 0])
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let ani = Ani::from_reader(io::Cursor::new(&data)).expect("expected data to be valid");
+        assert_eq!(ani.metadata().and_then(Metadata::title), Some("Arrow"));
+        assert_eq!(ani.rates(), Some(&[1, 2, 3][..]));
+        assert_eq!(ani.sequence(), Some(&[0, 1, 0][..]));
+        assert_eq!(ani.frames().len(), 2);
+
+        let truncated = Ani::from_reader(io::Cursor::new(&data[..data.len() - 3]));
+        assert!(matches!(
+            truncated,
+            Err(DecodeError::TruncatedChunk {
+                identifier: [b'L', b'I', b'S', b'T'],
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rate_warnings() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let builder = crate::ser::AniBuilder::new()
+            .frame(vec![image.clone()])
+            .frame(vec![image]);
+
+        let data = builder.clone().rates(vec![0, 2]).encode().unwrap();
+        let (ani, warnings) = Ani::from_bytes_with_warnings(&data).unwrap();
+        assert!(ani.warnings().is_empty());
+        assert_eq!(warnings, [Warning::ZeroDisplayRate { step: Some(0) }]);
+
+        let data = builder.jif_rate(0).encode().unwrap();
+        let ani = Ani::from_reader(data.as_slice()).unwrap();
+        assert_eq!(
+            ani.warnings(),
+            [
+                Warning::MissingRateChunk { default: 0 },
+                Warning::ZeroDisplayRate { step: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn repair_timing() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let partial = |rates: Option<Vec<u32>>, sequence: Option<Vec<u32>>| {
+            let mut ani = Ani {
+                metadata: None,
+                header: header(4, 1, 1, Flag::ICON | Flag::SEQUENCE),
+                rates,
+                sequence,
+                frames: vec![Frame::new(vec![image.clone()]); 2],
+                warnings: Vec::new(),
+                partial: true,
+            };
+            ani.repair_timing(ani.frames.len());
+            ani
+        };
+
+        // Only 2 of the 4 frames were recovered, so the steps showing frames 2 and 3 are dropped.
+        let ani = partial(Some(vec![1, 2, 3, 4, 5]), Some(vec![0, 2, 1, 3, 0]));
+        assert_eq!(ani.sequence(), Some([0, 1, 0].as_slice()));
+        assert_eq!(ani.rates(), Some([1, 3, 5].as_slice()));
+        assert_eq!(ani.header().frames(), 2);
+        assert_eq!(ani.header().steps(), 3);
+        assert!(ani.header().flags().contains(Flag::SEQUENCE));
+
+        // Rates that didn't match the sequence to begin with are dropped.
+        let ani = partial(Some(vec![1, 2]), Some(vec![0, 2, 1]));
+        assert_eq!(ani.sequence(), Some([0, 1].as_slice()));
+        assert_eq!(ani.rates(), None);
+
+        // A sequence that only showed missing frames is dropped, along with its rates.
+        let ani = partial(Some(vec![1, 2]), Some(vec![3, 2]));
+        assert_eq!(ani.sequence(), None);
+        assert_eq!(ani.rates(), None);
+        assert_eq!(ani.header().steps(), 2);
+        assert!(!ani.header().flags().contains(Flag::SEQUENCE));
+    }
+
+    #[test]
+    fn skip_unknown_chunks() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let data = crate::ser::AniBuilder::new()
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        // Insert a `DISP` chunk and an unknown list right after the `ACON` form type.
+        let extra = b"DISP\x04\0\0\0\x01\0\0\0LIST\x08\0\0\0PAL \0\0\0\0";
+        let riff_size = u32::from_le_bytes(data[4..8].try_into().unwrap())
+            + u32::try_from(extra.len()).unwrap();
+        let data = [
+            b"RIFF",
+            &riff_size.to_le_bytes()[..],
+            &data[8..12],
+            extra,
+            &data[12..],
+        ]
+        .concat();
+
+        let expected = [
+            Warning::SkippedChunk {
+                identifier: *b"DISP",
+                size: 4,
+            },
+            Warning::SkippedChunk {
+                identifier: *b"PAL ",
+                size: 4,
+            },
+        ];
+
+        let ani = Ani::from_bytes(&data).expect("expected unknown chunks to be skipped");
+        assert_eq!(ani.warnings(), expected);
+        assert_eq!(ani.frames().len(), 1);
+
+        let ani = Ani::from_reader(data.as_slice()).expect("expected unknown chunks to be skipped");
+        assert_eq!(ani.warnings(), expected);
+
+        assert!(Ani::from_bytes_strict(&data).is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let mut data = crate::ser::AniBuilder::new()
+            .rates(vec![1, 2])
+            .sequence(vec![0, 1])
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let ani = Ani::from_bytes(&data).expect("expected data to be valid");
+        assert_eq!(ani.validate(), []);
+
+        data[28] = 3; // Steps
+        data[52] = Flag::SEQUENCE.bits().to_le_bytes()[0]; // Flags
+        data[84] = 5; // Second step of the sequence
+
+        let ani = Ani::from_bytes(&data).expect("expected data to be valid");
+        assert_eq!(
+            ani.validate(),
+            [
+                Issue::RateCountMismatch {
+                    expected: 3,
+                    actual: 2
+                },
+                Issue::SequenceLengthMismatch {
+                    expected: 3,
+                    actual: 2
+                },
+                Issue::SequenceOutOfRange {
+                    step: 1,
+                    index: 5,
+                    frames: 2
+                },
+                Issue::MissingIconFlag,
+            ]
+        );
+
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let mut data = crate::ser::AniBuilder::new()
+            .jif_rate(6)
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+        data[28] = 3; // Steps
+
+        // Without a sequence, the frames are shown once each rather than wrapping around.
+        let ani = Ani::from_bytes(&data).expect("expected data to be valid");
+        assert_eq!(
+            ani.validate(),
+            [Issue::StepCountMismatch {
+                steps: 3,
+                frames: 2
+            }]
+        );
+        assert_eq!(ani.timeline().collect::<Vec<_>>(), [(0, 100), (1, 100)]);
+    }
+
+    #[test]
+    fn padding() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let mut data = crate::ser::AniBuilder::new()
+            .title("Wait")
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        // `INAM` holds 5 bytes ("Wait\0"), so it's followed by a padding byte. Shrinking the
+        // list to exclude it leaves the list itself with an odd size, and the same padding byte.
+        assert_eq!(&data[12..16], b"LIST");
+        assert_eq!(data[16], 18);
+        data[16] = 17;
+
+        for ani in [
+            Ani::from_bytes(&data),
+            Ani::from_bytes_strict(&data),
+            Ani::from_reader(data.as_slice()),
+        ] {
+            let ani = ani.expect("expected padding to be skipped");
+            assert_eq!(ani.metadata().and_then(Metadata::title), Some("Wait"));
+            assert_eq!(ani.frames().len(), 1);
+        }
+    }
+
+    #[test]
+    fn bitmap_frames() {
+        // A 1x1 image at 24 bits per pixel, with the row padded to 4 bytes.
+        let data = b"icon\x04\0\0\0\x00\x80\xff\0";
+        let mut parser = Parser::new(data);
+        let frames = parse_fram_chunk(
+            &mut parser,
+            &header(1, 1, 1, Flag::empty()),
+            &Limits::default(),
+        )
+        .expect("expected raw bitmap to be valid");
+
+        assert_eq!(frames[0].rgba(0), Some(&[0xff, 0x80, 0x00, 0xff][..]));
+        assert_eq!(frames[0].hotspot(0), None);
+    }
+
+    #[test]
+    fn jiffies() {
+        assert_eq!(jiffies_to_ms(1), 17);
+        assert_eq!(jiffies_to_ms(6), 100);
+        assert_eq!(jiffies_to_ms(10), 167);
+        assert_eq!(jiffies_to_ms(60), 1000);
+        assert_eq!(jiffies_to_ms(u32::MAX), u32::MAX);
+        assert!((jiffies_to_ms_f64(1) - 16.667).abs() < 0.001);
+
+        assert_eq!(ms_to_jiffies(8), 0);
+        assert_eq!(ms_to_jiffies(9), 1);
+        assert_eq!(ms_to_jiffies(100), 6);
+        assert_eq!(ms_to_jiffies(u32::MAX), 257_698_038);
+
+        for jiffies in 0..1000 {
+            assert_eq!(ms_to_jiffies(jiffies_to_ms(jiffies)), jiffies);
+        }
+    }
+
+    #[test]
+    fn timeline() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let frames = vec![Frame::new(vec![image.clone()]), Frame::new(vec![image])];
+
+        let mut ani = Ani::new(frames);
+        ani.set_jif_rate(6);
+        assert_eq!(ani.timeline().collect::<Vec<_>>(), [(0, 100), (1, 100)]);
+
+        // Rates are per step, rather than per frame.
+        ani.set_sequence(Some(vec![1, 0, 1]));
+        ani.set_rates(Some(vec![1, 3]));
+        assert_eq!(
+            ani.timeline().collect::<Vec<_>>(),
+            [(1, 17), (0, 50), (1, 100)]
+        );
+    }
+
+    #[test]
+    fn edit_frames() {
+        let frame = |x| {
+            let mut image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+            image.set_cursor_hotspot(Some((x, 0)));
+            Frame::new(vec![image])
+        };
+
+        let mut ani = Ani::new(vec![frame(0), frame(1)]);
+        ani.push_frame(frame(2));
+        ani.set_sequence(Some(vec![0, 1, 2, 1]));
+        ani.set_rates(Some(vec![1, 2, 3, 4]));
+        assert_eq!(ani.header().steps(), 4);
+
+        let removed = ani.remove_frame(1);
+        assert_eq!(removed.hotspot(0), Some(Hotspot::new(1, 0)));
+        assert_eq!(ani.sequence(), Some(&[0, 1][..]));
+        assert_eq!(ani.rates(), Some(&[1, 3][..]));
+        assert_eq!(ani.header().frames(), 2);
+        assert_eq!(ani.header().steps(), 2);
+
+        let data = ani.encode().expect("expected edited animation to be valid");
+        let ani = Ani::from_bytes_strict(&data).expect("expected encoded data to be valid");
+        assert!(ani.validate().is_empty());
+        assert_eq!(ani.frames()[1].hotspot(0), Some(Hotspot::new(2, 0)));
+    }
 }
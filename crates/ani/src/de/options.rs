@@ -0,0 +1,244 @@
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::mem;
+use std::path::Path;
+
+use crate::de::{Ani, DecodeError, Limits};
+
+/// How forgiving to be of data that doesn't follow the ANI file format specification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodeMode {
+    /// Reject anything that doesn't follow the specification exactly (see
+    /// [`Ani::from_bytes_strict`]).
+    Strict,
+
+    /// Work around the mistakes commonly found in the wild, such as chunks out of order (see
+    /// [`Ani::from_bytes`]).
+    #[default]
+    Tolerant,
+
+    /// Like [`Self::Tolerant`], but also salvage as many frames as possible from a file that ends
+    /// early (e.g., an interrupted download), reporting what was recovered as a
+    /// [`crate::de::Warning::Truncated`].
+    Recover,
+}
+
+/// Options for decoding ANI files, for when the defaults of [`Ani::open`] aren't enough.
+///
+/// ```no_run
+/// # use ani::de::{DecodeMode, DecodeOptions, Limits};
+/// let ani = DecodeOptions::new()
+///     .mode(DecodeMode::Strict)
+///     .limits(Limits::default().with_max_frames(64))
+///     .open("Wait.ani")
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct DecodeOptions {
+    mode: DecodeMode,
+    limits: Limits,
+    warnings: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            mode: DecodeMode::default(),
+            limits: Limits::default(),
+            warnings: true,
+        }
+    }
+}
+
+impl DecodeOptions {
+    /// The options used by [`Ani::open`]: tolerant, with the default [`Limits`], and keeping
+    /// warnings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How forgiving to be of data that doesn't follow the specification (defaults to
+    /// [`DecodeMode::Tolerant`]).
+    #[must_use]
+    pub const fn mode(mut self, mode: DecodeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Limit the resources the data may claim (see [`Limits`]).
+    #[must_use]
+    pub const fn limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Keep what is found while decoding in [`Ani::warnings`] (the default), or discard it.
+    #[must_use]
+    pub const fn warnings(mut self, warnings: bool) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Read and decode the ANI file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - Cannot read the file at path.
+    /// - Data does not follow the ANI file format specification.
+    /// - Data exceeds the [`Limits`].
+    pub fn open(&self, path: impl AsRef<Path>) -> Result<Ani, DecodeError> {
+        let read_failure = |source| DecodeError::ReadFailure { source };
+
+        if self.mode == DecodeMode::Tolerant {
+            let file = File::open(path).map_err(read_failure)?;
+            self.decode_reader(BufReader::new(file))
+        } else {
+            let data = fs::read(path).map_err(read_failure)?;
+            self.decode(&data)
+        }
+    }
+
+    /// Decode ANI data.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - Data has an invalid file signature.
+    /// - Data does not follow the ANI file format specification.
+    /// - Data exceeds the [`Limits`].
+    pub fn decode(&self, data: &[u8]) -> Result<Ani, DecodeError> {
+        let ani = match self.mode {
+            DecodeMode::Strict => Ani::from_bytes_strict_with_limits(data, &self.limits),
+            DecodeMode::Tolerant => Ani::from_bytes_tolerant(data, &self.limits, false),
+            DecodeMode::Recover => Ani::from_bytes_tolerant(data, &self.limits, true),
+        }?;
+
+        Ok(self.finish(ani))
+    }
+
+    /// Decode ANI data from a stream.
+    ///
+    /// When decoding is tolerant, only the chunk currently being decoded is kept in memory (see
+    /// [`Ani::from_reader`]); otherwise, all of the data is read first.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - Cannot read from `reader`.
+    /// - Data has an invalid file signature.
+    /// - Data does not follow the ANI file format specification.
+    /// - Data exceeds the [`Limits`].
+    pub fn decode_reader<R: Read>(&self, mut reader: R) -> Result<Ani, DecodeError> {
+        if self.mode != DecodeMode::Tolerant {
+            let mut data = Vec::new();
+            reader
+                .read_to_end(&mut data)
+                .map_err(|source| DecodeError::ReadFailure { source })?;
+            return self.decode(&data);
+        }
+
+        let ani = Ani::from_reader_with_limits(reader, &self.limits)?;
+        Ok(self.finish(ani))
+    }
+
+    fn finish(&self, mut ani: Ani) -> Ani {
+        if !self.warnings {
+            drop(mem::take(&mut ani.warnings));
+        }
+
+        ani
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::de::Warning;
+    use crate::ser::AniBuilder;
+
+    #[test]
+    fn options() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let data = AniBuilder::new()
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        // The missing `rate` chunk is reported, unless warnings are discarded.
+        let ani = DecodeOptions::new().decode(&data).unwrap();
+        assert!(!ani.warnings().is_empty());
+        let ani = DecodeOptions::new().warnings(false).decode(&data).unwrap();
+        assert!(ani.warnings().is_empty());
+
+        let limits = Limits::default().with_max_pixels(0);
+        for mode in [
+            DecodeMode::Strict,
+            DecodeMode::Tolerant,
+            DecodeMode::Recover,
+        ] {
+            let options = DecodeOptions::new().mode(mode).limits(limits);
+            assert!(options.decode_reader(&data[..]).is_err());
+        }
+    }
+
+    #[test]
+    fn recover() {
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let data = AniBuilder::new()
+            .rates(vec![1, 2, 3])
+            .frame(vec![image.clone()])
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        // Cut the file off partway through the last frame.
+        let truncated = &data[..data.len() - 10];
+        assert!(Ani::from_bytes(truncated).is_err());
+
+        let options = DecodeOptions::new().mode(DecodeMode::Recover);
+        let ani = options.decode(truncated).unwrap();
+        assert!(ani.is_partial());
+        assert_eq!(ani.frames().len(), 2);
+        assert_eq!(ani.header().frames(), 2);
+        assert_eq!(ani.rates(), Some(&[1, 2][..]));
+        assert_eq!(
+            ani.warnings(),
+            [Warning::Truncated {
+                declared: 3,
+                recovered: 2
+            }]
+        );
+
+        // Complete files decode the same as they would otherwise.
+        let ani = options.decode(&data).unwrap();
+        assert!(!ani.is_partial());
+        assert_eq!(ani.frames().len(), 3);
+        assert!(ani.warnings().is_empty());
+
+        // Steps showing a frame that is missing are dropped, along with their rates.
+        let image = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let data = AniBuilder::new()
+            .sequence(vec![0, 1, 0, 1])
+            .rates(vec![1, 2, 3, 4])
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let ani = options.decode(&data[..data.len() - 10]).unwrap();
+        assert_eq!(ani.sequence(), Some(&[0, 0][..]));
+        assert_eq!(ani.rates(), Some(&[1, 3][..]));
+        assert_eq!(ani.header().steps(), 2);
+        assert!(ani.validate().is_empty());
+
+        // There is nothing to recover without a complete frame.
+        assert!(options.decode(&data[..data.len() / 2]).is_err());
+    }
+}
@@ -4,8 +4,20 @@ use crate::de::error::DecodeError;
 
 pub const IDENTIFIER_SIZE: usize = 4;
 
+/// The four-character code at the start of every RIFF chunk (e.g., `b"anih"`).
 pub type Identifier = [u8; IDENTIFIER_SIZE];
 
+/// Convert a size read from the file into a `usize`.
+///
+/// # Errors
+///
+/// This function returns an error if:
+///
+/// - The value cannot be represented by a `usize` on the current architecture.
+pub fn to_usize(value: u32) -> Result<usize, DecodeError> {
+    usize::try_from(value).map_err(|_| DecodeError::ArchitectureLimit { value })
+}
+
 /// Represents an ongoing parse.
 pub struct Parser<'a> {
     data: &'a [u8],
@@ -15,21 +27,19 @@ impl<'a> Parser<'a> {
     pub const fn new(data: &'a [u8]) -> Self {
         Self { data }
     }
-}
 
-impl Parser<'_> {
     pub const fn bytes_remaining(&self) -> usize {
         self.data.len()
     }
 
-    /// Return the next `size` bytes.
+    /// Return the next `size` bytes, borrowed from the data being parsed.
     ///
     /// # Errors
     ///
     /// This function returns an error if:
     ///
     /// - There are not enough bytes to fill a buffer of size `size`.
-    pub fn read_bytes(&mut self, size: usize) -> Result<Vec<u8>, DecodeError> {
+    pub fn read_bytes(&mut self, size: usize) -> Result<&'a [u8], DecodeError> {
         let (result, data) =
             self.data
                 .split_at_checked(size)
@@ -38,7 +48,31 @@ impl Parser<'_> {
                 })?;
 
         self.data = data;
-        Ok(result.to_vec())
+        Ok(result)
+    }
+
+    /// Return the data of the chunk `identifier`, which declared a size of `size` bytes.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - The chunk declared more bytes than are available.
+    pub fn read_chunk(
+        &mut self,
+        identifier: Identifier,
+        size: u32,
+    ) -> Result<&'a [u8], DecodeError> {
+        let available = self.bytes_remaining();
+
+        self.read_bytes(to_usize(size)?).map_err(|err| match err {
+            DecodeError::NotEnoughBytes { .. } => DecodeError::TruncatedChunk {
+                identifier,
+                declared: size,
+                available,
+            },
+            err => err,
+        })
     }
 
     /// Return the next `size` bytes without advancing.
@@ -48,7 +82,7 @@ impl Parser<'_> {
     /// This function returns an error if:
     ///
     /// - There are not enough bytes to fill a buffer of size `size`.
-    pub fn peek_bytes(&mut self, size: usize) -> Result<Vec<u8>, DecodeError> {
+    pub fn peek_bytes(&mut self, size: usize) -> Result<&'a [u8], DecodeError> {
         let (result, _) =
             self.data
                 .split_at_checked(size)
@@ -56,7 +90,7 @@ impl Parser<'_> {
                     needed: size.saturating_sub(self.data.len()),
                 })?;
 
-        Ok(result.to_vec())
+        Ok(result)
     }
 
     pub fn read<T>(&mut self) -> Result<T, DecodeError>
@@ -135,4 +169,17 @@ impl Parser<'_> {
 
         Ok(value)
     }
+
+    /// Skip the padding byte that follows a chunk which declared a size of `size` bytes.
+    ///
+    /// RIFF chunks are word-aligned, so a chunk with an odd size is followed by a single byte
+    /// that isn't included in its size. Some writers leave the padding out, so the byte is only
+    /// skipped if it is zero (which no identifier starts with).
+    pub fn skip_padding(&mut self, size: u32) {
+        if size % 2 == 1
+            && let [0, rest @ ..] = self.data
+        {
+            self.data = rest;
+        }
+    }
 }
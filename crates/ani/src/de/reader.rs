@@ -0,0 +1,433 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::mem;
+
+use tracing::debug;
+
+use crate::de::parser::{to_usize, Identifier, Parser, IDENTIFIER_SIZE};
+use crate::de::{
+    decode_frame, parse_anih_chunk, parse_info_chunk, parse_rate_chunk, parse_seq_chunk,
+    DecodeError, Frame, Header, Limits, Metadata, Warning,
+};
+
+/// The size of the four-character code at the start of a `LIST` chunk.
+const LIST_TYPE_SIZE: u32 = 4;
+
+/// Decode the frames of an ANI file on demand.
+///
+/// Creating a reader parses everything except for the frames, and records where each frame is
+/// located so it can be decoded later with [`Self::frame`]. This is useful when only a few of the
+/// frames are needed (e.g., to preview a cursor).
+///
+/// ```no_run
+/// # use std::fs::File;
+/// # use std::io::BufReader;
+/// # use ani::de::AniReader;
+/// let file = File::open("Wait.ani").unwrap();
+/// let mut reader = AniReader::new(BufReader::new(file)).unwrap();
+/// let first = reader.frame(0).unwrap();
+///
+/// assert!(reader.frame_count() > 0);
+/// ```
+pub struct AniReader<R> {
+    reader: R,
+    metadata: Option<Metadata>,
+    header: Header,
+    rates: Option<Vec<u32>>,
+    sequence: Option<Vec<u32>>,
+    frames: Vec<FrameLocation>,
+    warnings: Vec<Warning>,
+    limits: Limits,
+}
+
+/// Where the data of an `icon` chunk is located within the stream.
+#[derive(Debug, Clone, Copy)]
+struct FrameLocation {
+    offset: u64,
+    size: u32,
+}
+
+impl<R: Read + Seek> AniReader<R> {
+    /// Parse the chunks of an ANI file, without decoding any of the frames.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - Cannot read from (or seek within) `reader`.
+    /// - Data has an invalid file signature.
+    /// - Data does not follow the ANI file format specification.
+    /// - Data exceeds the default [`Limits`].
+    pub fn new(reader: R) -> Result<Self, DecodeError> {
+        Self::with_limits(reader, Limits::default())
+    }
+
+    /// Like [`Self::new`], but with custom [`Limits`] instead of the defaults.
+    ///
+    /// The limit on pixels applies to each frame as it is decoded, rather than to every frame
+    /// combined.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error for the same reasons as [`Self::new`].
+    pub fn with_limits(mut reader: R, limits: Limits) -> Result<Self, DecodeError> {
+        let mut frames = Vec::new();
+
+        let chunks = scan(&mut reader, &limits, |reader, size| {
+            let mut remaining = u64::from(size);
+
+            while remaining > 0 {
+                let (identifier, size) =
+                    read_chunk_header(reader)?.ok_or(DecodeError::NotEnoughBytes { needed: 8 })?;
+
+                if identifier != *b"icon" {
+                    return Err(DecodeError::UnexpectedIdentifier {
+                        expected: *b"icon",
+                        actual: identifier,
+                    });
+                }
+
+                limits.check_chunk(identifier, size)?;
+                let count = u32::try_from(frames.len() + 1).unwrap_or(u32::MAX);
+                limits.check_frames(count)?;
+
+                let offset = reader.stream_position().map_err(read_failure)?;
+                let skip = u64::from(size) + u64::from(size % 2);
+                reader
+                    .seek(SeekFrom::Current(skip.cast_signed()))
+                    .map_err(read_failure)?;
+
+                frames.push(FrameLocation { offset, size });
+                remaining = remaining.saturating_sub(8 + skip);
+            }
+
+            Ok(())
+        })?;
+
+        let header = chunks
+            .header
+            .ok_or(DecodeError::MissingChunk { expected: *b"anih" })?;
+
+        if !chunks.has_frames {
+            return Err(DecodeError::MissingChunk { expected: *b"fram" });
+        }
+
+        Ok(Self {
+            reader,
+            metadata: chunks.metadata,
+            header,
+            rates: chunks.rates,
+            sequence: chunks.sequence,
+            frames,
+            warnings: chunks.warnings,
+            limits,
+        })
+    }
+
+    /// Decode the frame at `index`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - There is no frame at `index`.
+    /// - Cannot read from (or seek within) the underlying reader.
+    /// - The frame's data could not be decoded.
+    pub fn frame(&mut self, index: usize) -> Result<Frame, DecodeError> {
+        let data = self.frame_bytes(index)?;
+        let frame = decode_frame(&data, index, &self.header)?;
+        self.limits.check_pixels(frame.pixels())?;
+
+        Ok(frame)
+    }
+
+    /// Read the frame at `index` without decoding it (see [`Frame::raw`]).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - There is no frame at `index`.
+    /// - Cannot read from (or seek within) the underlying reader.
+    pub fn frame_bytes(&mut self, index: usize) -> Result<Vec<u8>, DecodeError> {
+        let Some(&FrameLocation { offset, size }) = self.frames.get(index) else {
+            return Err(DecodeError::MissingFrame { index });
+        };
+
+        self.reader
+            .seek(SeekFrom::Start(offset))
+            .map_err(read_failure)?;
+
+        read_chunk_body(&mut self.reader, *b"icon", size)
+    }
+
+    /// The number of frames found in the `fram` chunk.
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Additional information about the cursor (e.g., title, author, copyright).
+    #[must_use]
+    pub const fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Additional context for building the animated cursor.
+    #[must_use]
+    pub const fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// Display rate for each of the frames, if available.
+    #[must_use]
+    pub fn rates(&self) -> Option<&[u32]> {
+        self.rates.as_deref()
+    }
+
+    /// Ordering of the frames, if available.
+    #[must_use]
+    pub fn sequence(&self) -> Option<&[u32]> {
+        self.sequence.as_deref()
+    }
+
+    /// Chunks that were skipped because they aren't part of the ANI file format.
+    #[must_use]
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Every chunk of an ANI file except for the frames.
+#[derive(Default)]
+pub(super) struct Chunks {
+    pub metadata: Option<Metadata>,
+    pub header: Option<Header>,
+    pub rates: Option<Vec<u32>>,
+    pub sequence: Option<Vec<u32>>,
+    pub has_frames: bool,
+    pub warnings: Vec<Warning>,
+}
+
+/// Walk the chunks of an ANI file in order, decoding everything but the frames.
+///
+/// `on_frames` is called with the size of the `fram` list's contents (excluding the list type),
+/// and must consume exactly that many bytes from the reader.
+pub(super) fn scan<R, F>(
+    reader: &mut R,
+    limits: &Limits,
+    mut on_frames: F,
+) -> Result<Chunks, DecodeError>
+where
+    R: Read,
+    F: FnMut(&mut R, u32) -> Result<(), DecodeError>,
+{
+    let (identifier, riff_size) =
+        read_chunk_header(reader)?.ok_or(DecodeError::NotEnoughBytes { needed: 8 })?;
+
+    if identifier != *b"RIFF" {
+        return Err(DecodeError::UnexpectedIdentifier {
+            expected: *b"RIFF",
+            actual: identifier,
+        });
+    }
+
+    let form = read_identifier(reader, *b"RIFF")?;
+    if form != *b"ACON" {
+        return Err(DecodeError::UnexpectedIdentifier {
+            expected: *b"ACON",
+            actual: form,
+        });
+    }
+
+    let riff_size = to_usize(riff_size)?;
+    let mut consumed = IDENTIFIER_SIZE;
+    let mut chunks = Chunks::default();
+
+    while consumed < riff_size {
+        let Some((identifier, size)) = read_chunk_header(reader)? else {
+            return Err(DecodeError::SizeMismatch {
+                expected: riff_size,
+                actual: consumed,
+            });
+        };
+        debug!("identifier: {:?}", String::from_utf8_lossy(&identifier));
+
+        if identifier == *b"LIST" {
+            let list_type = read_identifier(reader, identifier)?;
+            let truncated = DecodeError::TruncatedChunk {
+                identifier,
+                declared: size,
+                available: IDENTIFIER_SIZE,
+            };
+            // The list type is included in the size of the chunk.
+            let list_size = size.checked_sub(LIST_TYPE_SIZE).ok_or(truncated)?;
+            limits.check_chunk(list_type, list_size)?;
+
+            match &list_type {
+                b"INFO" => {
+                    let data = read_chunk_body(reader, identifier, list_size)?;
+                    chunks.metadata = Some(parse_info_chunk(&mut Parser::new(&data))?);
+                }
+                b"fram" => {
+                    on_frames(reader, list_size)?;
+                    chunks.has_frames = true;
+                }
+                _ => {
+                    _ = read_chunk_body(reader, identifier, list_size)?;
+                    chunks.warnings.push(Warning::SkippedChunk {
+                        identifier: list_type,
+                        size: list_size,
+                    });
+                }
+            }
+        } else {
+            limits.check_chunk(identifier, size)?;
+            let body = read_chunk_body(reader, identifier, size)?;
+
+            // The chunk parsers for these expect to read the size themselves.
+            let data = [&size.to_le_bytes()[..], &body].concat();
+            let mut parser = Parser::new(&data);
+
+            match &identifier {
+                b"anih" => chunks.header = Some(parse_anih_chunk(&mut parser)?),
+                b"rate" => chunks.rates = Some(parse_rate_chunk(&mut parser)?),
+                b"seq " => chunks.sequence = Some(parse_seq_chunk(&mut parser)?),
+                _ => chunks
+                    .warnings
+                    .push(Warning::SkippedChunk { identifier, size }),
+            }
+        }
+
+        // Chunks are padded to an even number of bytes; the padding isn't part of the size.
+        if size % 2 == 1 {
+            _ = read_up_to(reader, 1)?;
+        }
+
+        consumed = consumed
+            .saturating_add(IDENTIFIER_SIZE + mem::size_of::<u32>())
+            .saturating_add(to_usize(size)?)
+            .saturating_add(usize::from(size % 2 == 1));
+    }
+
+    Ok(chunks)
+}
+
+/// Read the identifier and size of the next chunk, or `None` at the end of the stream.
+fn read_chunk_header<R: Read>(reader: &mut R) -> Result<Option<(Identifier, u32)>, DecodeError> {
+    let bytes = read_up_to(reader, IDENTIFIER_SIZE + mem::size_of::<u32>())?;
+
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+
+    let (Some((identifier, _)), Some((_, size))) = (
+        bytes.split_first_chunk::<IDENTIFIER_SIZE>(),
+        bytes.split_last_chunk::<4>().filter(|_| bytes.len() == 8),
+    ) else {
+        return Err(DecodeError::NotEnoughBytes {
+            needed: 8 - bytes.len(),
+        });
+    };
+
+    Ok(Some((*identifier, u32::from_le_bytes(*size))))
+}
+
+/// Read the four-character code that follows the size of a `RIFF` or `LIST` chunk.
+fn read_identifier<R: Read>(reader: &mut R, parent: Identifier) -> Result<Identifier, DecodeError> {
+    let bytes = read_up_to(reader, IDENTIFIER_SIZE)?;
+
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| DecodeError::TruncatedChunk {
+            identifier: parent,
+            declared: LIST_TYPE_SIZE,
+            available: bytes.len(),
+        })
+}
+
+/// Read `len` bytes, or fewer if the end of the stream is reached first.
+fn read_up_to<R: Read>(reader: &mut R, len: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut bytes = Vec::with_capacity(len);
+    reader
+        .take(len as u64)
+        .read_to_end(&mut bytes)
+        .map_err(read_failure)?;
+
+    Ok(bytes)
+}
+
+/// Read the data of the chunk `identifier`, which declared a size of `size` bytes.
+///
+/// The size isn't trusted to pre-allocate the buffer, since it may be larger than the stream.
+pub(super) fn read_chunk_body<R: Read>(
+    reader: &mut R,
+    identifier: Identifier,
+    size: u32,
+) -> Result<Vec<u8>, DecodeError> {
+    let mut body = Vec::new();
+    reader
+        .take(u64::from(size))
+        .read_to_end(&mut body)
+        .map_err(read_failure)?;
+
+    if body.len() < to_usize(size)? {
+        return Err(DecodeError::TruncatedChunk {
+            identifier,
+            declared: size,
+            available: body.len(),
+        });
+    }
+
+    Ok(body)
+}
+
+fn read_failure(source: std::io::Error) -> DecodeError {
+    DecodeError::ReadFailure { source }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+    use crate::de::Hotspot;
+
+    #[test]
+    fn lazy_frames() {
+        let first = ico::IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let mut second = ico::IconImage::from_rgba_data(3, 3, vec![0x80; 36]);
+        second.set_cursor_hotspot(Some((1, 2)));
+
+        let data = crate::ser::AniBuilder::new()
+            .rates(vec![5, 10])
+            .frame(vec![first])
+            .frame(vec![second])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let mut reader = AniReader::new(io::Cursor::new(&data)).expect("expected data to be valid");
+        assert_eq!(reader.frame_count(), 2);
+        assert_eq!(reader.header().frames(), 2);
+        assert_eq!(reader.rates(), Some(&[5, 10][..]));
+
+        // Frames can be decoded in any order, any number of times.
+        let frame = reader.frame(1).expect("expected frame to be valid");
+        assert_eq!(frame.hotspot(0), Some(Hotspot::new(1, 2)));
+        assert!(reader.frame(0).is_ok());
+        assert_eq!(
+            frame.raw(),
+            Some(&reader.frame_bytes(1).expect("expected frame to exist")[..])
+        );
+        assert!(reader.frame(1).is_ok());
+        assert!(matches!(
+            reader.frame(2),
+            Err(DecodeError::MissingFrame { index: 2 })
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+
+use crate::de::parser::Identifier;
+
+/// Represents a problem with an ANI file that was worked around while decoding it, or that may
+/// make it play differently than intended.
+///
+/// Warnings are only produced by the tolerant decoders (e.g., [`crate::de::Ani::from_bytes`]);
+/// the strict decoder fails instead.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// A chunk that isn't part of the ANI file format was skipped.
+    SkippedChunk {
+        /// The identifier of the chunk (or the list type, for `LIST` chunks).
+        identifier: Identifier,
+        /// The number of bytes that were skipped.
+        size: u32,
+    },
+
+    /// The animation has several steps but no `rate` chunk, so every step is shown for the
+    /// default display rate from the header.
+    MissingRateChunk {
+        /// The default display rate, in jiffies.
+        default: u32,
+    },
+
+    /// A step is shown for zero jiffies, which players either skip or clamp to a minimum.
+    ZeroDisplayRate {
+        /// The step with the rate, or `None` for the default display rate from the header.
+        step: Option<usize>,
+    },
+
+    /// The file ends early, so only the frames before the end were decoded (see
+    /// [`crate::de::DecodeMode::Recover`]).
+    Truncated {
+        /// The number of frames declared by the header.
+        declared: u32,
+        /// The number of frames that were recovered.
+        recovered: usize,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::SkippedChunk { identifier, size } => {
+                let identifier = String::from_utf8_lossy(&identifier).to_string();
+                write!(f, "skipped unknown chunk {identifier:?} ({size} bytes)")
+            }
+            Self::MissingRateChunk { default } => write!(
+                f,
+                "missing rate chunk; every step is shown for the default {default} jiffies"
+            ),
+            Self::ZeroDisplayRate { step: Some(step) } => {
+                write!(f, "step {step} has a display rate of 0 jiffies")
+            }
+            Self::ZeroDisplayRate { step: None } => "the default display rate is 0 jiffies".fmt(f),
+            Self::Truncated {
+                declared,
+                recovered,
+            } => write!(
+                f,
+                "file is truncated; recovered {recovered} of {declared} frames"
+            ),
+        }
+    }
+}
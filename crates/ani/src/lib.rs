@@ -1,4 +1,19 @@
 //! Encode and decode Windows' animated cursor format.
+//!
+//! # Platform support
+//!
+//! The crate requires `std`: frames are [`ico::IconImage`]s, and the `ico` crate decodes and
+//! encodes them through `std::io`. Errors don't depend on it otherwise: a frame that can't be
+//! decoded fails with a [`de::FrameError`], and only reading (see
+//! [`de::DecodeError::ReadFailure`]) fails with a [`std::io::Error`].
+//!
+//! Apart from [`de::Ani::open`] (and [`de::DecodeOptions::open`]), it doesn't touch the file
+//! system, so it builds for targets with a limited `std`, such as `wasm32-unknown-unknown`:
+//! decode from bytes with [`de::Ani::from_bytes`], or from any [`std::io::Read`] with
+//! [`de::Ani::from_reader`].
+//!
+//! With the `wasm-bindgen` feature, the decoder is also exposed to JavaScript as `decodeAni`
+//! (see the `wasm` module), e.g., to preview cursors in a browser.
 
 #![warn(
     missing_docs,
@@ -10,5 +25,19 @@
     clippy::pedantic
 )]
 
-mod builder;
+#[cfg(feature = "capi")]
+pub mod capi;
 pub mod de;
+pub mod ser;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+
+/// A directory of its own for a test, which is removed when it is dropped (even if the test
+/// fails).
+#[cfg(all(test, feature = "capi"))]
+fn test_dir() -> tempfile::TempDir {
+    tempfile::Builder::new()
+        .prefix("ani-test-")
+        .tempdir()
+        .expect("expected to create a temporary directory")
+}
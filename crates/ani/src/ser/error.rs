@@ -0,0 +1,76 @@
+use std::{error, fmt, io};
+
+/// Represents a failure to encode an ANI file.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum EncodeError {
+    /// An error occurred while attempting to write the encoded data.
+    WriteFailure {
+        /// The underlying error that caused the failure.
+        source: io::Error,
+    },
+
+    /// The animation does not contain any frames.
+    NoFrames,
+
+    /// The number of display rates does not match the number of steps in the animation.
+    RateCountMismatch {
+        /// The number of steps in the animation.
+        expected: usize,
+        /// The number of display rates provided.
+        actual: usize,
+    },
+
+    /// The sequence refers to a frame that does not exist.
+    SequenceOutOfRange {
+        /// The frame index found in the sequence.
+        index: u32,
+        /// The number of frames in the animation.
+        frames: usize,
+    },
+
+    /// The images of a frame could not be encoded.
+    InvalidFrame {
+        /// The position of the frame within the animation.
+        index: usize,
+        /// The underlying error that caused the failure.
+        source: io::Error,
+    },
+
+    /// The encoded data does not fit within the limits of the file format.
+    TooLarge,
+}
+
+impl error::Error for EncodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Self::WriteFailure { ref source } | Self::InvalidFrame { ref source, .. } => {
+                Some(source)
+            }
+            Self::NoFrames
+            | Self::RateCountMismatch { .. }
+            | Self::SequenceOutOfRange { .. }
+            | Self::TooLarge => None,
+        }
+    }
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::WriteFailure { .. } => "failed to write ANI data".fmt(f),
+            Self::NoFrames => "expected at least one frame".fmt(f),
+            Self::RateCountMismatch { expected, actual } => {
+                write!(f, "expected {expected} display rates, got {actual}")
+            }
+            Self::SequenceOutOfRange { index, frames } => {
+                write!(
+                    f,
+                    "sequence refers to frame {index}, but there are only {frames} frames"
+                )
+            }
+            Self::InvalidFrame { index, .. } => write!(f, "failed to encode frame {index}"),
+            Self::TooLarge => "encoded data exceeds the maximum chunk size".fmt(f),
+        }
+    }
+}
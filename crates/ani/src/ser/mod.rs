@@ -0,0 +1,383 @@
+//! Encode Windows animated cursors.
+
+mod error;
+
+use std::io::{self, Write};
+
+pub use error::EncodeError;
+use ico::{IconDir, IconDirEntry, IconImage, ResourceType};
+
+use crate::de::{Ani, Flag, Identifier};
+
+/// The display rate used when none is provided, in jiffies (1/60 seconds).
+pub const DEFAULT_JIF_RATE: u32 = 6;
+
+/// Assemble an ANI file from its frames and timing information.
+///
+/// ```
+/// # use ani::ser::AniBuilder;
+/// # use ico::IconImage;
+/// let image = IconImage::from_rgba_data(32, 32, vec![0; 32 * 32 * 4]);
+/// let data = AniBuilder::new()
+///     .title("Busy")
+///     .frame(vec![image])
+///     .encode()
+///     .unwrap();
+///
+/// assert_eq!(&data[..4], b"RIFF");
+/// ```
+#[derive(Clone)]
+pub struct AniBuilder {
+    title: Option<String>,
+    author: Option<String>,
+    jif_rate: u32,
+    rates: Option<Vec<u32>>,
+    sequence: Option<Vec<u32>>,
+    frames: Vec<Vec<IconImage>>,
+}
+
+impl Default for AniBuilder {
+    fn default() -> Self {
+        Self {
+            title: None,
+            author: None,
+            jif_rate: DEFAULT_JIF_RATE,
+            rates: None,
+            sequence: None,
+            frames: Vec::new(),
+        }
+    }
+}
+
+impl From<&Ani> for AniBuilder {
+    /// Start from a decoded (or edited) animation, keeping its title, author, timing and frames.
+    fn from(ani: &Ani) -> Self {
+        let metadata = ani.metadata();
+
+        Self {
+            title: metadata.and_then(|m| m.title()).map(str::to_owned),
+            author: metadata.and_then(|m| m.author()).map(str::to_owned),
+            jif_rate: ani.header().jif_rate(),
+            rates: ani.rates().map(<[u32]>::to_vec),
+            sequence: ani.sequence().map(<[u32]>::to_vec),
+            frames: ani
+                .frames()
+                .iter()
+                .map(|frame| frame.images().to_vec())
+                .collect(),
+        }
+    }
+}
+
+impl AniBuilder {
+    /// Create a builder for an animation with no frames.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the name of the cursor.
+    #[must_use]
+    pub fn title(self, title: impl Into<String>) -> Self {
+        Self {
+            title: Some(title.into()),
+            ..self
+        }
+    }
+
+    /// Set the author of the cursor.
+    #[must_use]
+    pub fn author(self, author: impl Into<String>) -> Self {
+        Self {
+            author: Some(author.into()),
+            ..self
+        }
+    }
+
+    /// Set the default display rate, in jiffies (1/60 seconds).
+    #[must_use]
+    pub fn jif_rate(self, jif_rate: u32) -> Self {
+        Self { jif_rate, ..self }
+    }
+
+    /// Set the display rate for each step of the animation, in jiffies (1/60 seconds).
+    #[must_use]
+    pub fn rates(self, rates: Vec<u32>) -> Self {
+        Self {
+            rates: Some(rates),
+            ..self
+        }
+    }
+
+    /// Set the order in which the frames are displayed.
+    #[must_use]
+    pub fn sequence(self, sequence: Vec<u32>) -> Self {
+        Self {
+            sequence: Some(sequence),
+            ..self
+        }
+    }
+
+    /// Append a frame, made up of one image per cursor size.
+    ///
+    /// The color of fully transparent pixels isn't kept; they are encoded as transparent black.
+    #[must_use]
+    pub fn frame(mut self, images: Vec<IconImage>) -> Self {
+        self.frames.push(images);
+        self
+    }
+
+    /// Encode the animation and write it to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - The animation is invalid (see [`Self::encode`]).
+    /// - Writing to `writer` fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), EncodeError> {
+        let data = self.encode()?;
+        writer
+            .write_all(&data)
+            .map_err(|err| EncodeError::WriteFailure { source: err })
+    }
+
+    /// Encode the animation.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - There are no frames.
+    /// - The number of rates does not match the number of steps.
+    /// - The sequence refers to a frame that does not exist.
+    /// - A frame's images cannot be encoded.
+    pub fn encode(&self) -> Result<Vec<u8>, EncodeError> {
+        if self.frames.is_empty() {
+            return Err(EncodeError::NoFrames);
+        }
+
+        let steps = self.sequence.as_ref().map_or(self.frames.len(), Vec::len);
+
+        if let Some(ref sequence) = self.sequence
+            && let Some(&index) = sequence
+                .iter()
+                .find(|&&index| usize::try_from(index).map_or(true, |i| i >= self.frames.len()))
+        {
+            return Err(EncodeError::SequenceOutOfRange {
+                index,
+                frames: self.frames.len(),
+            });
+        }
+
+        if let Some(ref rates) = self.rates
+            && rates.len() != steps
+        {
+            return Err(EncodeError::RateCountMismatch {
+                expected: steps,
+                actual: rates.len(),
+            });
+        }
+
+        let mut body = b"ACON".to_vec();
+
+        if self.title.is_some() || self.author.is_some() {
+            let mut info = b"INFO".to_vec();
+
+            for (identifier, value) in [(*b"INAM", &self.title), (*b"IART", &self.author)] {
+                if let Some(value) = value {
+                    // INFO strings are NUL-terminated.
+                    let mut bytes = value.as_bytes().to_vec();
+                    bytes.push(0);
+                    write_chunk(&mut info, identifier, &bytes)?;
+                }
+            }
+
+            write_chunk(&mut body, *b"LIST", &info)?;
+        }
+
+        write_chunk(&mut body, *b"anih", &self.encode_header(steps)?)?;
+
+        if let Some(ref rates) = self.rates {
+            write_chunk(&mut body, *b"rate", &encode_u32s(rates))?;
+        }
+
+        if let Some(ref sequence) = self.sequence {
+            write_chunk(&mut body, *b"seq ", &encode_u32s(sequence))?;
+        }
+
+        let mut frames = b"fram".to_vec();
+        for (index, images) in self.frames.iter().enumerate() {
+            let icon = encode_frame(images)
+                .map_err(|source| EncodeError::InvalidFrame { index, source })?;
+            write_chunk(&mut frames, *b"icon", &icon)?;
+        }
+        write_chunk(&mut body, *b"LIST", &frames)?;
+
+        let mut output = Vec::with_capacity(body.len() + 8);
+        write_chunk(&mut output, *b"RIFF", &body)?;
+        Ok(output)
+    }
+
+    fn encode_header(&self, steps: usize) -> Result<Vec<u8>, EncodeError> {
+        let frames = u32::try_from(self.frames.len()).map_err(|_| EncodeError::TooLarge)?;
+        let steps = u32::try_from(steps).map_err(|_| EncodeError::TooLarge)?;
+
+        let mut flags = Flag::ICON;
+        if self.sequence.is_some() {
+            flags |= Flag::SEQUENCE;
+        }
+
+        let fields = [
+            36, // Header size
+            frames,
+            steps,
+            0, // Width (unused when the frames are icons)
+            0, // Height
+            0, // Bit count
+            0, // Planes
+            self.jif_rate,
+            flags.bits(),
+        ];
+
+        Ok(encode_u32s(&fields))
+    }
+}
+
+/// Encode the images of a single frame as a Windows cursor (CUR) file.
+fn encode_frame(images: &[IconImage]) -> io::Result<Vec<u8>> {
+    let mut icon_dir = IconDir::new(ResourceType::Cursor);
+
+    for image in images {
+        // Images without a hotspot would be encoded as icons, which can't share a
+        // directory with cursors.
+        let mut image = clear_transparent(image);
+        if image.cursor_hotspot().is_none() {
+            image.set_cursor_hotspot(Some((0, 0)));
+        }
+
+        icon_dir.add_entry(IconDirEntry::encode(&image)?);
+    }
+
+    let mut buffer = Vec::new();
+    icon_dir.write(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Copy `image`, clearing the color of its fully transparent pixels.
+///
+/// When every pixel of a bitmap is fully transparent, its alpha channel is ignored (by Windows, and
+/// by [`crate::de`]) in favor of the AND mask, which inverts the colors of masked pixels instead
+/// of hiding them. Cleared pixels are hidden either way.
+fn clear_transparent(image: &IconImage) -> IconImage {
+    let mut rgba = image.rgba_data().to_vec();
+    for pixel in rgba.chunks_exact_mut(4).filter(|pixel| pixel[3] == 0) {
+        pixel.fill(0);
+    }
+
+    let mut cleared = IconImage::from_rgba_data(image.width(), image.height(), rgba);
+    cleared.set_cursor_hotspot(image.cursor_hotspot());
+    cleared
+}
+
+/// Append a RIFF chunk to `output`, including the padding byte required after odd-sized data.
+fn write_chunk(
+    output: &mut Vec<u8>,
+    identifier: Identifier,
+    data: &[u8],
+) -> Result<(), EncodeError> {
+    let size = u32::try_from(data.len()).map_err(|_| EncodeError::TooLarge)?;
+
+    output.extend_from_slice(&identifier);
+    // The ANI file format uses little-endian byte order for multi-byte integers.
+    // <https://en.wikipedia.org/wiki/Resource_Interchange_File_Format#History>
+    output.extend_from_slice(&size.to_le_bytes());
+    output.extend_from_slice(data);
+
+    if !data.len().is_multiple_of(2) {
+        output.push(0);
+    }
+
+    Ok(())
+}
+
+fn encode_u32s(values: &[u32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::de::{Ani, Hotspot};
+
+    fn image(hotspot: (u16, u16)) -> IconImage {
+        let mut image = IconImage::from_rgba_data(32, 32, vec![0xff; 32 * 32 * 4]);
+        image.set_cursor_hotspot(Some(hotspot));
+        image
+    }
+
+    #[test]
+    fn round_trip() {
+        let data = AniBuilder::new()
+            .title("Arrow")
+            .author("Nic")
+            .jif_rate(3)
+            .rates(vec![1, 2, 3])
+            .sequence(vec![0, 1, 0])
+            .frame(vec![image((1, 2))])
+            .frame(vec![image((3, 4))])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let ani = Ani::from_bytes_strict(&data).expect("expected encoded data to be valid");
+
+        let metadata = ani.metadata().unwrap();
+        assert_eq!(metadata.title(), Some("Arrow"));
+        assert_eq!(metadata.author(), Some("Nic"));
+        assert_eq!(ani.header().frames(), 2);
+        assert_eq!(ani.header().steps(), 3);
+        assert_eq!(ani.header().jif_rate(), 3);
+        assert!(ani.header().flags().contains(Flag::ICON | Flag::SEQUENCE));
+        assert_eq!(ani.rates(), Some(&[1, 2, 3][..]));
+        assert_eq!(ani.sequence(), Some(&[0, 1, 0][..]));
+        assert_eq!(ani.frames()[1].hotspot(0), Some(Hotspot::new(3, 4)));
+    }
+
+    #[test]
+    fn transparent_frame() {
+        let image = IconImage::from_rgba_data(2, 1, vec![0, 10, 0, 0, 0xff, 0, 0, 0]);
+        let data = AniBuilder::new()
+            .frame(vec![image])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let ani = Ani::from_bytes(&data).expect("expected encoded data to be valid");
+        assert_eq!(ani.frames()[0].rgba(0), Some(&[0; 2 * 4][..]));
+    }
+
+    #[test]
+    fn invalid_animations() {
+        assert!(matches!(
+            AniBuilder::new().encode(),
+            Err(EncodeError::NoFrames)
+        ));
+
+        let result = AniBuilder::new()
+            .frame(vec![image((0, 0))])
+            .sequence(vec![0, 1])
+            .encode();
+        assert!(matches!(
+            result,
+            Err(EncodeError::SequenceOutOfRange { index: 1, .. })
+        ));
+
+        let result = AniBuilder::new()
+            .frame(vec![image((0, 0))])
+            .rates(vec![1, 2])
+            .encode();
+        assert!(matches!(result, Err(EncodeError::RateCountMismatch { .. })));
+    }
+}
@@ -0,0 +1,190 @@
+//! JavaScript bindings for the decoder, so websites can preview ANI files without a server.
+//!
+//! Build them with [`wasm-pack`](https://rustwasm.github.io/wasm-pack/), e.g.:
+//!
+//! ```sh
+//! wasm-pack build crates/ani --target web -- --features wasm-bindgen
+//! ```
+//!
+//! Then, in JavaScript:
+//!
+//! ```js
+//! const ani = decodeAni(new Uint8Array(await file.arrayBuffer()));
+//! for (const step of ani.steps) {
+//!     const frame = ani.frames[step.frame];
+//!     const image = new ImageData(new Uint8ClampedArray(frame.rgba), frame.width);
+//!     // Draw `image` for `step.duration` milliseconds.
+//! }
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::de::{Ani, Frame};
+
+/// A decoded animated (or static) cursor.
+#[wasm_bindgen]
+pub struct DecodedAni {
+    frames: Vec<DecodedFrame>,
+    steps: Vec<Step>,
+}
+
+#[wasm_bindgen]
+impl DecodedAni {
+    /// The frames of the animation, each at its largest size.
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn frames(&self) -> Vec<DecodedFrame> {
+        self.frames.clone()
+    }
+
+    /// The steps of the animation as they are played back (see [`Ani::timeline`]).
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn steps(&self) -> Vec<Step> {
+        self.steps.clone()
+    }
+}
+
+/// The image of a frame.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct DecodedFrame {
+    width: u32,
+    height: u32,
+    hotspot_x: u16,
+    hotspot_y: u16,
+    rgba: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl DecodedFrame {
+    /// The width of the image, in pixels.
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the image, in pixels.
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The horizontal position of the hotspot (0 if the frame doesn't have one).
+    #[wasm_bindgen(getter, js_name = hotspotX)]
+    #[must_use]
+    pub fn hotspot_x(&self) -> u16 {
+        self.hotspot_x
+    }
+
+    /// The vertical position of the hotspot (0 if the frame doesn't have one).
+    #[wasm_bindgen(getter, js_name = hotspotY)]
+    #[must_use]
+    pub fn hotspot_y(&self) -> u16 {
+        self.hotspot_y
+    }
+
+    /// The pixels of the image as RGBA, in row-major order (4 bytes per pixel).
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn rgba(&self) -> Vec<u8> {
+        self.rgba.clone()
+    }
+}
+
+/// A single step of the animation: which frame to show, and for how long.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub struct Step {
+    /// The index of the frame in [`DecodedAni::frames`].
+    pub frame: usize,
+    /// How long the frame is shown, in milliseconds.
+    pub duration: u32,
+}
+
+/// Decode an ANI file (or a static CUR file) from its contents.
+///
+/// Steps that refer to a frame that doesn't exist are left out.
+///
+/// # Errors
+///
+/// This function returns an error if the data can't be decoded (see [`Ani::from_bytes`]), or a
+/// frame has no images.
+#[wasm_bindgen(js_name = decodeAni)]
+pub fn decode_ani(bytes: &[u8]) -> Result<DecodedAni, JsError> {
+    let ani = if bytes.starts_with(b"RIFF") {
+        Ani::from_bytes(bytes)?
+    } else {
+        Ani::from_cur_bytes(bytes)?
+    };
+
+    let frames = ani
+        .frames()
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            decode_frame(frame).ok_or_else(|| JsError::new(&format!("frame {i} has no images")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let steps = ani
+        .timeline()
+        .filter(|&(frame, _)| frame < frames.len())
+        .map(|(frame, duration)| Step { frame, duration })
+        .collect();
+
+    Ok(DecodedAni { frames, steps })
+}
+
+/// The largest image of `frame`, or `None` if it has no images.
+fn decode_frame(frame: &Frame) -> Option<DecodedFrame> {
+    let (index, image) = frame
+        .images()
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, image)| image.width())?;
+    let hotspot = frame.hotspot(index).unwrap_or_default();
+
+    Some(DecodedFrame {
+        width: image.width(),
+        height: image.height(),
+        hotspot_x: hotspot.x(),
+        hotspot_y: hotspot.y(),
+        rgba: image.rgba_data().to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let small = ico::IconImage::from_rgba_data(1, 1, vec![9; 4]);
+        let large = ico::IconImage::from_rgba_data(2, 1, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        let data = crate::ser::AniBuilder::new()
+            .jif_rate(6)
+            .frame(vec![small, large.clone()])
+            .frame(vec![large])
+            .encode()
+            .expect("expected builder to be valid");
+
+        let Ok(ani) = decode_ani(&data) else {
+            panic!("expected hardcoded animation to be valid");
+        };
+        let frames = ani.frames();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!((frames[0].width(), frames[0].height()), (2, 1));
+        assert_eq!(frames[0].rgba(), [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            ani.steps()
+                .iter()
+                .map(|step| (step.frame, step.duration))
+                .collect::<Vec<_>>(),
+            [(0, 100), (1, 100)]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Decode the files in `fixtures` and compare the result with the `.txt` file next to each.
+//!
+//! The fixtures are small, but each is structured like files found in the wild (e.g., frames
+//! stored as raw bitmaps, or chunks out of order). After an intended change to the decoder,
+//! update the expected output with:
+//!
+//! ```sh
+//! UPDATE_GOLDEN=1 cargo test -p ani --test golden
+//! ```
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use ani::de::Ani;
+
+/// Describe everything the decoder found in `ani`, including the pixels of every frame.
+fn describe(ani: &Ani) -> String {
+    let mut output = String::new();
+    let metadata = ani.metadata();
+    let header = ani.header();
+
+    _ = writeln!(output, "title: {:?}", metadata.and_then(|m| m.title()));
+    _ = writeln!(output, "author: {:?}", metadata.and_then(|m| m.author()));
+    _ = writeln!(output, "frames: {}", header.frames());
+    _ = writeln!(output, "steps: {}", header.steps());
+    _ = writeln!(output, "jif_rate: {}", header.jif_rate());
+    _ = writeln!(output, "flags: {:?}", header.flags());
+    _ = writeln!(output, "rates: {:?}", ani.rates());
+    _ = writeln!(output, "sequence: {:?}", ani.sequence());
+
+    for warning in ani.warnings() {
+        _ = writeln!(output, "warning: {warning}");
+    }
+
+    for (index, frame) in ani.frames().iter().enumerate() {
+        for image in frame.images() {
+            _ = writeln!(
+                output,
+                "frame {index}: {}x{}, hotspot {:?}",
+                image.width(),
+                image.height(),
+                image.cursor_hotspot(),
+            );
+
+            for row in image.rgba_data().chunks(image.width() as usize * 4) {
+                let pixels = row
+                    .chunks(4)
+                    .map(|pixel| pixel.iter().map(|byte| format!("{byte:02x}")).collect())
+                    .collect::<Vec<String>>();
+                _ = writeln!(output, "  {}", pixels.join(" "));
+            }
+        }
+    }
+
+    output
+}
+
+fn check(name: &str) {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let data = fs::read(fixtures.join(format!("{name}.ani"))).unwrap();
+    let expected_path = fixtures.join(format!("{name}.txt"));
+
+    let ani = Ani::from_bytes(&data).expect("expected fixture to be valid");
+    let actual = describe(&ani);
+
+    // The tolerant and streaming decoders must agree with each other.
+    let streamed = Ani::from_reader(&data[..]).expect("expected fixture to be valid");
+    assert_eq!(describe(&streamed), actual);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&expected_path, &actual).unwrap();
+    }
+
+    let expected = fs::read_to_string(&expected_path).unwrap();
+    assert_eq!(actual, expected, "{name}.ani no longer decodes as expected");
+}
+
+#[test]
+fn icon() {
+    check("icon");
+    let data = fs::read(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/icon.ani"
+    ))
+    .unwrap();
+    assert!(Ani::from_bytes_strict(&data).is_ok());
+}
+
+#[test]
+fn bitmap() {
+    check("bitmap");
+}
+
+#[test]
+fn unordered() {
+    check("unordered");
+}
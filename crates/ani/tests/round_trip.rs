@@ -0,0 +1,136 @@
+//! Encode randomly generated animations, and check that decoding them gives back the same thing.
+
+use ani::de::Ani;
+use ani::ser::AniBuilder;
+use ico::IconImage;
+
+/// The number of animations to generate.
+const CASES: u64 = 256;
+
+/// A small pseudorandom number generator (xorshift64*), so failures are reproducible.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number in `0..bound`.
+    fn below(&mut self, bound: u32) -> u32 {
+        u32::try_from(self.next() % u64::from(bound)).unwrap()
+    }
+
+    fn bool(&mut self) -> bool {
+        self.next().is_multiple_of(2)
+    }
+}
+
+struct Animation {
+    title: Option<String>,
+    jif_rate: u32,
+    rates: Option<Vec<u32>>,
+    sequence: Option<Vec<u32>>,
+    frames: Vec<Vec<IconImage>>,
+}
+
+fn image(rng: &mut Rng) -> IconImage {
+    let width = 1 + rng.below(48);
+    let height = 1 + rng.below(48);
+    let rgba = (0..width * height)
+        .flat_map(|_| {
+            // Fully transparent pixels don't keep their color (see `AniBuilder::frame`).
+            let [r, g, b, a] = rng.next().to_le_bytes()[..4].try_into().unwrap();
+            if a == 0 {
+                [0; 4]
+            } else {
+                [r, g, b, a]
+            }
+        })
+        .collect();
+
+    let mut image = IconImage::from_rgba_data(width, height, rgba);
+    let hotspot = (rng.below(width), rng.below(height));
+    image.set_cursor_hotspot(Some((
+        u16::try_from(hotspot.0).unwrap(),
+        u16::try_from(hotspot.1).unwrap(),
+    )));
+    image
+}
+
+fn animation(rng: &mut Rng) -> Animation {
+    let frames = (0..1 + rng.below(6))
+        .map(|_| (0..1 + rng.below(3)).map(|_| image(rng)).collect())
+        .collect::<Vec<Vec<_>>>();
+    let count = u32::try_from(frames.len()).unwrap();
+
+    let sequence = rng.bool().then(|| {
+        (0..1 + rng.below(12))
+            .map(|_| rng.below(count))
+            .collect::<Vec<_>>()
+    });
+    let steps = sequence.as_ref().map_or(frames.len(), Vec::len);
+    let rates = rng
+        .bool()
+        .then(|| (0..steps).map(|_| 1 + rng.below(60)).collect());
+
+    Animation {
+        title: rng.bool().then(|| format!("Cursor {}", rng.below(1000))),
+        jif_rate: 1 + rng.below(60),
+        rates,
+        sequence,
+        frames,
+    }
+}
+
+#[test]
+fn round_trip() {
+    for seed in 1..=CASES {
+        let mut rng = Rng(seed);
+        let animation = animation(&mut rng);
+
+        let mut builder = AniBuilder::new().jif_rate(animation.jif_rate);
+        if let Some(title) = &animation.title {
+            builder = builder.title(title);
+        }
+        if let Some(rates) = &animation.rates {
+            builder = builder.rates(rates.clone());
+        }
+        if let Some(sequence) = &animation.sequence {
+            builder = builder.sequence(sequence.clone());
+        }
+        for images in &animation.frames {
+            builder = builder.frame(images.clone());
+        }
+
+        let data = builder.encode().expect("expected animation to be valid");
+
+        for ani in [Ani::from_bytes(&data), Ani::from_bytes_strict(&data)] {
+            let ani = ani.unwrap_or_else(|err| panic!("seed {seed}: {err}"));
+
+            let title = ani.metadata().and_then(|metadata| metadata.title());
+            assert_eq!(title, animation.title.as_deref(), "seed {seed}");
+            assert_eq!(ani.header().jif_rate(), animation.jif_rate, "seed {seed}");
+            assert_eq!(ani.rates(), animation.rates.as_deref(), "seed {seed}");
+            assert_eq!(ani.sequence(), animation.sequence.as_deref(), "seed {seed}");
+            assert_eq!(ani.frames().len(), animation.frames.len(), "seed {seed}");
+
+            for (frame, images) in ani.frames().iter().zip(&animation.frames) {
+                assert_eq!(frame.images().len(), images.len(), "seed {seed}");
+
+                for (decoded, image) in frame.images().iter().zip(images) {
+                    assert_eq!(decoded.width(), image.width(), "seed {seed}");
+                    assert_eq!(decoded.height(), image.height(), "seed {seed}");
+                    assert_eq!(decoded.cursor_hotspot(), image.cursor_hotspot());
+                    assert_eq!(decoded.rgba_data(), image.rgba_data(), "seed {seed}");
+                }
+            }
+        }
+
+        // Re-encoding what was decoded must give back the same file.
+        let ani = Ani::from_bytes(&data).unwrap();
+        assert_eq!(ani.encode().unwrap(), data, "seed {seed}");
+    }
+}
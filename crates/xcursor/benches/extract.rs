@@ -0,0 +1,103 @@
+//! Time extracting the frames of a large animated cursor (48 frames in 4 sizes) as PNG files, at
+//! each compression level.
+//!
+//! Run with `cargo bench -p xcursor`.
+
+use std::hint::black_box;
+
+use ani::de::DecodeMode;
+use ani::ser::AniBuilder;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ico::IconImage;
+use xcursor::{Compression, PngWriter};
+
+const FRAMES: usize = 48;
+const SIZES: [u32; 4] = [32, 48, 64, 96];
+const COMPRESSIONS: [(&str, Compression); 3] = [
+    ("fast", Compression::Fast),
+    ("default", Compression::Default),
+    ("best", Compression::Best),
+];
+
+/// Encode every image of every frame in memory, with one writer (and buffer) for all of them.
+fn encode(c: &mut Criterion) {
+    let ani = xcursor::decode(&animation(), DecodeMode::default())
+        .expect("expected the animation to be valid");
+    let mut group = c.benchmark_group("encode");
+    group.throughput(Throughput::Elements((FRAMES * SIZES.len()) as u64));
+
+    for (name, compression) in COMPRESSIONS {
+        let mut writer = PngWriter::new(compression);
+
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                for image in ani.frames().iter().flat_map(|frame| frame.images()) {
+                    black_box(writer.encode(image).expect("expected the image to encode"));
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Decode the cursor and write every image of every frame to a PNG file, like a build does.
+fn extract(c: &mut Criterion) {
+    let data = animation();
+    let dir = tempfile::tempdir().expect("expected to create a temporary directory");
+    let mut group = c.benchmark_group("extract");
+    group.sample_size(20);
+    group.throughput(Throughput::Elements((FRAMES * SIZES.len()) as u64));
+
+    for (name, compression) in COMPRESSIONS {
+        let mut writer = PngWriter::new(compression);
+
+        group.bench_function(BenchmarkId::from_parameter(name), |b| {
+            b.iter(|| {
+                let ani = xcursor::decode(&data, DecodeMode::default())
+                    .expect("expected the animation to be valid");
+
+                for (i, frame) in ani.frames().iter().enumerate() {
+                    for image in frame.images() {
+                        let path = dir.path().join(format!("{i:02}-{}.png", image.width()));
+                        writer
+                            .write(&path, image)
+                            .expect("expected the frame to be written");
+                    }
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Build an animation with a different gradient in every frame, so nothing is deduplicated.
+fn animation() -> Vec<u8> {
+    let mut builder = AniBuilder::new();
+
+    for frame in 0..FRAMES {
+        let images = SIZES
+            .iter()
+            .map(|&size| {
+                let rgba = (0..size * size)
+                    .flat_map(|i| {
+                        let value = (i as usize + frame) as u8;
+                        [value, value.wrapping_mul(3), 0x80, value | 0x0f]
+                    })
+                    .collect();
+                let mut image = IconImage::from_rgba_data(size, size, rgba);
+                image.set_cursor_hotspot(Some((0, 0)));
+                image
+            })
+            .collect();
+        builder = builder.frame(images);
+    }
+
+    builder
+        .encode()
+        .expect("expected the animation to be valid")
+}
+
+criterion_group!(benches, encode, extract);
+criterion_main!(benches);
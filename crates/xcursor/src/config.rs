@@ -0,0 +1,231 @@
+//! Write the configuration files read by `xcursorgen`.
+
+use std::fmt::Write as _;
+use std::io::Write;
+
+use tracing::info;
+
+use crate::{ConfigError, Step};
+
+/// An image file making up part of a frame, at one cursor size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    size: u32,
+    hotspot: (u16, u16),
+    file_name: String,
+}
+
+impl Image {
+    /// Describe the image in `file_name` (relative to the directory `xcursorgen` runs in),
+    /// which is `size` pixels wide, with its hotspot at `hotspot` (x, y).
+    pub fn new(size: u32, hotspot: (u16, u16), file_name: impl Into<String>) -> Self {
+        Self {
+            size,
+            hotspot,
+            file_name: file_name.into(),
+        }
+    }
+
+    /// The nominal size of the image, in pixels.
+    #[must_use]
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The position of the hotspot (x, y).
+    #[must_use]
+    pub fn hotspot(&self) -> (u16, u16) {
+        self.hotspot
+    }
+
+    /// Move the hotspot to `hotspot` (x, y).
+    pub fn set_hotspot(&mut self, hotspot: (u16, u16)) {
+        self.hotspot = hotspot;
+    }
+
+    /// The name of the image file.
+    #[must_use]
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+}
+
+/// Write the `xcursorgen` configuration for an animation, from its frames and the order and
+/// duration of its steps.
+///
+/// ```
+/// # use xcursor::{ConfigWriter, Image};
+/// let config = ConfigWriter::new()
+///     .frame(vec![Image::new(32, (1, 2), "00-32.png")])
+///     .frame(vec![Image::new(32, (1, 2), "01-32.png")])
+///     .step(0, 100)
+///     .step(1, 50)
+///     .encode()
+///     .unwrap();
+///
+/// assert_eq!(config, "32 1 2 00-32.png 100\n32 1 2 01-32.png 50\n");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConfigWriter {
+    frames: Vec<Vec<Image>>,
+    steps: Vec<(usize, u32)>,
+}
+
+impl ConfigWriter {
+    /// Create a writer for an animation with no frames.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a frame, made up of one image per cursor size.
+    ///
+    /// If a frame has several images of the same size, only the first one is used.
+    #[must_use]
+    pub fn frame(mut self, images: Vec<Image>) -> Self {
+        self.frames.push(images);
+        self
+    }
+
+    /// Append a step to the animation, showing the frame at `index` for `duration`
+    /// milliseconds.
+    #[must_use]
+    pub fn step(mut self, index: usize, duration: u32) -> Self {
+        self.steps.push((index, duration));
+        self
+    }
+
+    /// Append each of `steps` to the animation (see [`Self::step`]).
+    #[must_use]
+    pub fn steps(mut self, steps: &[Step]) -> Self {
+        self.steps
+            .extend(steps.iter().map(|step| (step.frame, step.duration)));
+        self
+    }
+
+    /// Write the configuration to `writer`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - The configuration is invalid (see [`Self::encode`]).
+    /// - Writing to `writer` fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<(), ConfigError> {
+        let contents = self.encode()?;
+        writer
+            .write_all(contents.as_bytes())
+            .map_err(|err| ConfigError::WriteFailure { source: err })
+    }
+
+    /// Generate the configuration.
+    ///
+    /// The lines are grouped by size, so the animation of each size can be read (or removed) at
+    /// once. Sizes that a frame doesn't have are skipped for the steps showing it.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - There are no steps.
+    /// - A step refers to a frame that does not exist.
+    /// - The file name of an image is empty or contains whitespace.
+    pub fn encode(&self) -> Result<String, ConfigError> {
+        if self.steps.is_empty() {
+            return Err(ConfigError::NoSteps);
+        }
+
+        if let Some(&(index, _)) = self
+            .steps
+            .iter()
+            .find(|&&(index, _)| index >= self.frames.len())
+        {
+            return Err(ConfigError::StepOutOfRange {
+                index,
+                frames: self.frames.len(),
+            });
+        }
+
+        if let Some(image) = self.frames.iter().flatten().find(|image| {
+            image.file_name.is_empty() || image.file_name.contains(char::is_whitespace)
+        }) {
+            return Err(ConfigError::InvalidFileName {
+                file_name: image.file_name.clone(),
+            });
+        }
+
+        let mut sizes = self
+            .frames
+            .iter()
+            .flatten()
+            .map(|image| image.size)
+            .collect::<Vec<_>>();
+        sizes.sort_unstable();
+        sizes.dedup();
+
+        let mut contents = String::new();
+
+        for size in sizes {
+            for &(i, duration) in &self.steps {
+                let Some(image) = self.frames[i].iter().find(|image| image.size == size) else {
+                    info!("frame {i} has no {size}x{size} image");
+                    continue;
+                };
+
+                let ((x, y), file_name) = (image.hotspot, &image.file_name);
+                // Writing to a `String` can't fail.
+                _ = writeln!(contents, "{size} {x} {y} {file_name} {duration}");
+            }
+        }
+
+        Ok(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_by_size() {
+        let config = ConfigWriter::new()
+            .frame(vec![
+                Image::new(48, (3, 3), "a-48.png"),
+                Image::new(32, (2, 2), "a-32.png"),
+            ])
+            .frame(vec![Image::new(32, (2, 2), "b-32.png")])
+            .step(1, 10)
+            .step(0, 20)
+            .encode()
+            .unwrap();
+
+        assert_eq!(
+            config,
+            "32 2 2 b-32.png 10\n32 2 2 a-32.png 20\n48 3 3 a-48.png 20\n"
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let frame = || vec![Image::new(32, (0, 0), "a.png")];
+
+        assert!(matches!(
+            ConfigWriter::new().frame(frame()).encode(),
+            Err(ConfigError::NoSteps)
+        ));
+        assert!(matches!(
+            ConfigWriter::new().frame(frame()).step(1, 0).encode(),
+            Err(ConfigError::StepOutOfRange {
+                index: 1,
+                frames: 1
+            })
+        ));
+        assert!(matches!(
+            ConfigWriter::new()
+                .frame(vec![Image::new(32, (0, 0), "a b.png")])
+                .step(0, 0)
+                .encode(),
+            Err(ConfigError::InvalidFileName { .. })
+        ));
+    }
+}
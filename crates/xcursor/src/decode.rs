@@ -0,0 +1,129 @@
+//! Decode cursors, and resolve the timing of their animations.
+
+use ani::de::{jiffies_to_ms_f64, Ani, DecodeError, DecodeMode, DecodeOptions};
+use tracing::info;
+
+use crate::ThemeError;
+
+/// A single step of an animation: which frame to show, and for how long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// The index of the frame.
+    pub frame: usize,
+    /// How long the frame is shown, in milliseconds.
+    pub duration: u32,
+}
+
+/// Decode an animated (ANI) or static (CUR or PNG) cursor.
+///
+/// Static cursors are treated as an animation with a single frame, so both are built the same
+/// way. The format is detected from the data rather than the file extension; `mode` only
+/// applies to animated cursors.
+///
+/// # Errors
+///
+/// This function returns an error if the data can't be decoded (see [`DecodeOptions::decode`]).
+pub fn decode(data: &[u8], mode: DecodeMode) -> Result<Ani, DecodeError> {
+    if data.starts_with(b"\x89PNG") {
+        return Ani::from_png_bytes(data);
+    }
+
+    if !data.starts_with(b"RIFF") {
+        info!("RIFF signature missing, decoding as a static cursor");
+        return Ani::from_cur_bytes(data);
+    }
+
+    DecodeOptions::new().mode(mode).decode(data)
+}
+
+/// Resolve the order and display time (in milliseconds, at the given playback `speed`) of each
+/// step of the animation, using the defaults from the header for any missing chunks.
+///
+/// Each duration is scaled from the display rate in jiffies and rounded once, so it is never
+/// more than half a millisecond off, whatever the speed.
+///
+/// The durations are not clamped to a minimum; that is up to the caller (see
+/// [`clamp_durations`]).
+///
+/// # Errors
+///
+/// This function returns an error if the sequence refers to a frame that does not exist.
+pub fn steps(ani: &Ani, speed: f64) -> Result<Vec<Step>, ThemeError> {
+    if ani.sequence().is_none() {
+        info!("ANI sequence missing, using default");
+    }
+
+    if ani.rates().is_none() {
+        info!("ANI frame rates missing, using default");
+    }
+
+    ani.timeline_jiffies()
+        .map(|(frame, jiffies)| {
+            if frame >= ani.frames().len() {
+                return Err(ThemeError::InvalidSequence { index: frame });
+            }
+
+            Ok(Step {
+                frame,
+                duration: frame_duration(jiffies, speed),
+            })
+        })
+        .collect()
+}
+
+/// Raise the duration of each of `steps` to at least `min_duration` milliseconds, since some
+/// compositors skip (or spin on) frames with a duration of zero. Returns how many were raised.
+pub fn clamp_durations(steps: &mut [Step], min_duration: u32) -> usize {
+    let mut clamped = 0;
+
+    for step in steps {
+        if step.duration < min_duration {
+            step.duration = min_duration;
+            clamped += 1;
+        }
+    }
+
+    clamped
+}
+
+/// Convert a display rate in jiffies to milliseconds at the given playback `speed`, rounding to
+/// the nearest millisecond. Durations too long to fit in a `u32` saturate at [`u32::MAX`].
+#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn frame_duration(jiffies: u32, speed: f64) -> u32 {
+    let milliseconds = (jiffies_to_ms_f64(jiffies) / speed).round();
+    milliseconds as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use ani::ser::AniBuilder;
+    use ico::IconImage;
+
+    use super::*;
+
+    #[test]
+    fn scaled_durations() {
+        let image = IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let data = AniBuilder::new()
+            .jif_rate(6)
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .rates(vec![4, 5])
+            .encode()
+            .unwrap();
+        let ani = decode(&data, DecodeMode::default()).unwrap();
+
+        let durations = |speed| {
+            steps(&ani, speed)
+                .unwrap()
+                .into_iter()
+                .map(|step| step.duration)
+                .collect::<Vec<_>>()
+        };
+
+        // Rounding to 67ms and 83ms first would give 45ms and 55ms.
+        assert_eq!(durations(1.5), [44, 56]);
+        assert_eq!(durations(1.0), [67, 83]);
+        assert_eq!(frame_duration(u32::MAX, 0.001), u32::MAX);
+    }
+}
@@ -0,0 +1,151 @@
+//! Encode extracted frames as PNG files.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use ico::IconImage;
+
+/// How hard to compress the extracted frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Compression {
+    /// Fastest to write, but the files are larger.
+    Fast,
+
+    /// A balance between speed and size.
+    #[default]
+    Default,
+
+    /// Smallest files, but slowest to write (every PNG filter is tried on each row).
+    Best,
+}
+
+/// Write `image` to `path` as an 8-bit RGBA PNG file.
+///
+/// Unlike [`IconImage::write_png`], the compression is configurable. To write several images,
+/// use a [`PngWriter`], which reuses its buffer for each of them.
+///
+/// # Errors
+///
+/// This function returns an error if the file can't be created or written to.
+pub fn write_png(path: &Path, image: &IconImage, compression: Compression) -> io::Result<()> {
+    PngWriter::new(compression).write(path, image)
+}
+
+/// Encodes images as 8-bit RGBA PNG files, reusing one buffer for all of them (e.g., for every
+/// frame of a cursor).
+///
+/// ```
+/// # use ico::IconImage;
+/// # use xcursor::{Compression, PngWriter};
+/// let mut writer = PngWriter::new(Compression::Fast);
+/// let image = IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+/// let png = writer.encode(&image).unwrap();
+///
+/// assert!(png.starts_with(b"\x89PNG"));
+/// ```
+#[derive(Debug, Default)]
+pub struct PngWriter {
+    compression: Compression,
+    buffer: Vec<u8>,
+}
+
+impl PngWriter {
+    /// Encode images with the given `compression`.
+    #[must_use]
+    pub fn new(compression: Compression) -> Self {
+        Self {
+            compression,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Encode `image`, returning the PNG data (which is only valid until the next image).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the image can't be encoded (e.g., its data doesn't
+    /// match its size).
+    pub fn encode(&mut self, image: &IconImage) -> io::Result<&[u8]> {
+        self.buffer.clear();
+
+        let mut encoder = png::Encoder::new(&mut self.buffer, image.width(), image.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        match self.compression {
+            Compression::Fast => encoder.set_compression(png::Compression::Fast),
+            Compression::Default => encoder.set_compression(png::Compression::Default),
+            Compression::Best => {
+                encoder.set_compression(png::Compression::Best);
+                encoder.set_adaptive_filter(png::AdaptiveFilterType::Adaptive);
+            }
+        }
+
+        let mut writer = encoder.write_header().map_err(into_io)?;
+        writer
+            .write_image_data(image.rgba_data())
+            .map_err(into_io)?;
+        writer.finish().map_err(into_io)?;
+
+        Ok(&self.buffer)
+    }
+
+    /// Write `image` to `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the image can't be encoded, or the file can't be
+    /// written to.
+    pub fn write(&mut self, path: &Path, image: &IconImage) -> io::Result<()> {
+        let png = self.encode(image)?;
+        fs::write(path, png)
+    }
+}
+
+fn into_io(err: png::EncodingError) -> io::Error {
+    match err {
+        png::EncodingError::IoError(err) => err,
+        err => io::Error::other(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let rgba = (0..=u8::MAX).step_by(4).collect::<Vec<_>>();
+        let image = IconImage::from_rgba_data(4, 4, rgba);
+        let dir = crate::test_dir();
+        let path = dir.path().join("encode.png");
+
+        for compression in [Compression::Fast, Compression::Default, Compression::Best] {
+            write_png(&path, &image, compression).unwrap();
+            let decoded = IconImage::read_png(File::open(&path).unwrap()).unwrap();
+            assert_eq!(decoded.rgba_data(), image.rgba_data());
+        }
+    }
+
+    #[test]
+    fn reuse_buffer() {
+        let mut writer = PngWriter::new(Compression::Fast);
+        let large = IconImage::from_rgba_data(8, 8, vec![0x80; 8 * 8 * 4]);
+        let small = IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+
+        writer.encode(&large).unwrap();
+        let png = writer.encode(&small).unwrap().to_vec();
+
+        // Nothing is left over from the larger image.
+        let decoded = IconImage::read_png(png.as_slice()).unwrap();
+        assert_eq!(decoded.rgba_data(), small.rgba_data());
+    }
+}
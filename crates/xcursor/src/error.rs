@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+use std::{error, fmt, io};
+
+use ani::de::DecodeError;
+
+use crate::{XcursorgenSetting, XCURSORGEN_ENV};
+
+/// Represents a failure to write an `xcursorgen` configuration.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ConfigError {
+    /// An error occurred while attempting to write the configuration.
+    WriteFailure {
+        /// The underlying error that caused the failure.
+        source: io::Error,
+    },
+
+    /// The animation does not contain any steps.
+    NoSteps,
+
+    /// A step refers to a frame that does not exist.
+    StepOutOfRange {
+        /// The frame index found in the step.
+        index: usize,
+        /// The number of frames in the animation.
+        frames: usize,
+    },
+
+    /// The name of an image file can't be written to the configuration (e.g., it is empty or
+    /// contains whitespace, which `xcursorgen` uses to separate fields).
+    InvalidFileName {
+        /// The name of the file.
+        file_name: String,
+    },
+}
+
+impl error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Self::WriteFailure { ref source } => Some(source),
+            Self::NoSteps | Self::StepOutOfRange { .. } | Self::InvalidFileName { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::WriteFailure { .. } => "failed to write xcursorgen configuration".fmt(f),
+            Self::NoSteps => "expected at least one step".fmt(f),
+            Self::StepOutOfRange { index, frames } => {
+                write!(
+                    f,
+                    "step refers to frame {index}, but there are only {frames} frames"
+                )
+            }
+            Self::InvalidFileName { ref file_name } => {
+                write!(f, "invalid image file name: {file_name:?}")
+            }
+        }
+    }
+}
+
+/// Represents a failure to build an Xcursor theme.
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ThemeError {
+    /// An error occurred while attempting to read or write a file.
+    Io {
+        /// The file (or directory) that was being accessed.
+        path: PathBuf,
+        /// The underlying error that caused the failure.
+        source: io::Error,
+    },
+
+    /// The cursor could not be decoded.
+    Decode {
+        /// The underlying error that caused the failure.
+        source: DecodeError,
+    },
+
+    /// The sequence of the animation refers to a frame that does not exist.
+    InvalidSequence {
+        /// The frame index found in the sequence.
+        index: usize,
+    },
+
+    /// The `xcursorgen` configuration could not be written.
+    Config {
+        /// The underlying error that caused the failure.
+        source: ConfigError,
+    },
+
+    /// `xcursorgen` could not be found (see [`locate_xcursorgen`](crate::locate_xcursorgen)).
+    XcursorgenNotFound {
+        /// The program that was looked for.
+        program: PathBuf,
+        /// Where `program` was set, or `None` if it was looked up in `PATH` by default.
+        setting: Option<XcursorgenSetting>,
+    },
+
+    /// `xcursorgen` could not be run.
+    XcursorgenFailure {
+        /// The program that was run.
+        program: PathBuf,
+        /// The underlying error that caused the failure.
+        source: io::Error,
+    },
+
+    /// `xcursorgen` exited unsuccessfully.
+    XcursorgenExit {
+        /// The exit code, or `None` if it was terminated by a signal.
+        code: Option<i32>,
+    },
+
+    /// A step replaced with [`BuildHooks`](crate::BuildHooks) failed; shown as the error it
+    /// wraps.
+    Hook {
+        /// The underlying error that caused the failure.
+        source: Box<dyn error::Error + Send + Sync>,
+    },
+
+    /// Building a cursor panicked.
+    Panicked,
+
+    /// A cursor of the theme could not be built.
+    Cursor {
+        /// The name of the cursor.
+        name: String,
+        /// The underlying error that caused the failure.
+        source: Box<ThemeError>,
+    },
+}
+
+impl ThemeError {
+    /// Wrap an error of a step replaced with [`BuildHooks`](crate::BuildHooks).
+    pub fn hook(source: impl Into<Box<dyn error::Error + Send + Sync>>) -> Self {
+        Self::Hook {
+            source: source.into(),
+        }
+    }
+
+    pub(crate) fn io(path: impl Into<PathBuf>) -> impl FnOnce(io::Error) -> Self {
+        move |source| Self::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl error::Error for ThemeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Self::Io { ref source, .. } | Self::XcursorgenFailure { ref source, .. } => {
+                Some(source)
+            }
+            Self::Decode { ref source } => Some(source),
+            Self::Config { ref source } => Some(source),
+            Self::Cursor { ref source, .. } => Some(source),
+            Self::Hook { ref source } => source.source(),
+            Self::InvalidSequence { .. }
+            | Self::Panicked
+            | Self::XcursorgenNotFound { .. }
+            | Self::XcursorgenExit { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Io { ref path, .. } => write!(f, "failed to access {}", path.display()),
+            Self::Decode { .. } => "failed to decode cursor".fmt(f),
+            Self::InvalidSequence { index } => write!(f, "invalid sequence index: {index}"),
+            Self::Config { .. } => "failed to write xcursorgen configuration".fmt(f),
+            Self::XcursorgenNotFound { setting: None, .. } => write!(
+                f,
+                "xcursorgen not found in PATH; install it (e.g., `x11-apps` on Debian and \
+                Ubuntu, `xorg-xcursorgen` on Arch Linux or `xcursorgen` on Fedora), or set \
+                ${XCURSORGEN_ENV} to its path"
+            ),
+            Self::XcursorgenNotFound {
+                ref program,
+                setting: Some(XcursorgenSetting::Env),
+            } => write!(
+                f,
+                "xcursorgen not found: {} (from ${XCURSORGEN_ENV})",
+                program.display()
+            ),
+            Self::XcursorgenNotFound { ref program, .. } => {
+                write!(f, "xcursorgen not found: {}", program.display())
+            }
+            Self::XcursorgenFailure { ref program, .. } => {
+                write!(f, "failed to execute {}", program.display())
+            }
+            Self::XcursorgenExit { code: Some(code) } => {
+                write!(f, "process failed with exit code: {code}")
+            }
+            Self::XcursorgenExit { code: None } => "process terminated due to signal".fmt(f),
+            Self::Hook { ref source } => source.fmt(f),
+            Self::Panicked => "panicked while building cursor".fmt(f),
+            Self::Cursor { ref name, .. } => write!(f, "failed to build cursor: {name}"),
+        }
+    }
+}
+
+impl From<DecodeError> for ThemeError {
+    fn from(source: DecodeError) -> Self {
+        Self::Decode { source }
+    }
+}
+
+impl From<ConfigError> for ThemeError {
+    fn from(source: ConfigError) -> Self {
+        Self::Config { source }
+    }
+}
@@ -0,0 +1,88 @@
+//! Customize the steps of a build (e.g., to resize the frames, or to skip the cursors that are
+//! up to date).
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use ani::de::Ani;
+
+use crate::{CursorBuild, Image, Step, ThemeError};
+
+/// Replaces the steps [`ThemeBuilder`](crate::ThemeBuilder) takes to build a theme.
+///
+/// Every method takes the default step, so implementations only replace the ones they need;
+/// the default steps are also available on [`CursorBuild`], to run before or after their own.
+/// Cursors may be built concurrently (by several threads), so the methods may be called for
+/// different cursors at the same time.
+///
+/// Errors of their own can be returned as [`ThemeError::Hook`].
+pub trait BuildHooks: Sync {
+    /// Prepare the directory `staged` that the theme replacing the one in `dir` is built in
+    /// (e.g., to carry over the cursors that are up to date). It starts out empty.
+    ///
+    /// # Errors
+    ///
+    /// An error stops the build before any cursor is built.
+    fn stage(&self, staged: &Path, dir: &Path) -> Result<(), ThemeError> {
+        _ = (staged, dir);
+        Ok(())
+    }
+
+    /// Read the cursor: by default, the data it was created with (see
+    /// [`Cursor::new`](crate::Cursor::new)).
+    ///
+    /// # Errors
+    ///
+    /// An error fails the cursor.
+    fn read<'a>(&self, cursor: &CursorBuild<'a>) -> Result<Cow<'a, [u8]>, ThemeError> {
+        Ok(Cow::Borrowed(cursor.cursor().data()))
+    }
+
+    /// Whether the cursor in the staged theme (see [`Self::stage`]) is already up to date with
+    /// `data`, which skips the rest of its steps. Never, by default.
+    ///
+    /// # Errors
+    ///
+    /// An error fails the cursor.
+    fn up_to_date(&self, cursor: &CursorBuild<'_>, data: &[u8]) -> Result<bool, ThemeError> {
+        _ = (cursor, data);
+        Ok(false)
+    }
+
+    /// Write the images of each frame of the cursor (decoded from `data`) to its frames
+    /// directory, and return them (see [`CursorBuild::extract_frames`]).
+    ///
+    /// # Errors
+    ///
+    /// An error fails the cursor.
+    fn extract(
+        &self,
+        cursor: &CursorBuild<'_>,
+        data: &[u8],
+        ani: &mut Ani,
+    ) -> Result<Vec<Vec<Image>>, ThemeError> {
+        _ = data;
+        cursor.extract_frames(ani)
+    }
+
+    /// Create the cursor in the staged theme from the images of its frames, shown in the order
+    /// and for the duration of `steps` (see [`CursorBuild::write_xcursor`]).
+    ///
+    /// # Errors
+    ///
+    /// An error fails the cursor.
+    fn write(
+        &self,
+        cursor: &CursorBuild<'_>,
+        frames: &[Vec<Image>],
+        steps: &[Step],
+    ) -> Result<(), ThemeError> {
+        cursor.write_xcursor(frames, steps).map(drop)
+    }
+}
+
+/// Builds every cursor with the default steps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultHooks;
+
+impl BuildHooks for DefaultHooks {}
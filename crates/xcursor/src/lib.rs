@@ -0,0 +1,68 @@
+//! Convert animated (and static) Windows cursors into Xcursor themes.
+//!
+//! [`ThemeBuilder`] runs the whole pipeline: it decodes each cursor, writes its frames as PNG
+//! files, runs `xcursorgen` on them, links the aliases of each cursor, and writes the
+//! `index.theme` file. The theme is built next to its destination, which it only replaces once
+//! every cursor is built.
+//!
+//! Steps of the build can be replaced with [`BuildHooks`] (e.g., to resize the frames, or to
+//! create another kind of cursor than an Xcursor), which is how the `ani-to-xcursor` command
+//! adds its own settings (e.g., shadows), a cache of the extracted frames and hyprcursor output.
+//!
+//! Each step is also available on its own (e.g., [`ConfigWriter`] writes the configuration read
+//! by `xcursorgen`, from images that can come from anywhere), for tools that need more control
+//! over the output.
+//!
+//! To show the progress of a build (e.g., in a window), implement [`ProgressObserver`] and pass
+//! it to [`ThemeBuilder::build_with`].
+//!
+//! Creating the Xcursors requires the `xcursorgen` program, and links are only created on Unix.
+//!
+//! With the `python` feature, decoding and building themes are also exposed to Python as the
+//! `ani_to_xcursor` module.
+
+#![warn(
+    missing_docs,
+    clippy::correctness,
+    clippy::suspicious,
+    clippy::complexity,
+    clippy::perf,
+    clippy::style,
+    clippy::pedantic
+)]
+
+mod config;
+mod decode;
+mod encode;
+mod error;
+mod hooks;
+pub mod pool;
+mod progress;
+#[cfg(feature = "python")]
+mod python;
+mod replace;
+mod theme;
+mod xcursorgen;
+
+pub use config::{ConfigWriter, Image};
+pub use decode::{clamp_durations, decode, steps, Step};
+pub use encode::{write_png, Compression, PngWriter};
+pub use error::{ConfigError, ThemeError};
+pub use hooks::{BuildHooks, DefaultHooks};
+pub use progress::{NoProgress, ProgressObserver};
+pub use replace::replace_dir;
+pub use theme::{
+    claim_aliases, link_cursor, symlink, AliasCollision, Cursor, CursorBuild, CursorResult,
+    IndexTheme, ThemeBuilder,
+};
+pub use xcursorgen::{locate_xcursorgen, run_xcursorgen, XcursorgenSetting, XCURSORGEN_ENV};
+
+/// A directory of its own for a test, which is removed when it is dropped (even if the test
+/// fails).
+#[cfg(test)]
+fn test_dir() -> tempfile::TempDir {
+    tempfile::Builder::new()
+        .prefix("xcursor-test-")
+        .tempdir()
+        .expect("expected to create a temporary directory")
+}
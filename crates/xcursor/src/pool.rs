@@ -0,0 +1,128 @@
+//! Run jobs on a bounded number of threads (e.g., to build several cursors at once).
+
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+
+/// The number of threads to use when not configured: one per available CPU.
+#[must_use]
+pub fn default_jobs() -> NonZeroUsize {
+    thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+/// Call `f` on each of `items` using at most `jobs` threads, returning the results in order.
+///
+/// Threads take the next item as soon as they finish one, so a few slow items don't hold up the
+/// rest. A panic is caught and returned as the result of the item that caused it.
+pub fn map<T, R, F>(jobs: NonZeroUsize, items: Vec<T>, f: F) -> Vec<thread::Result<R>>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    // Nothing is left out, since nothing stops the threads.
+    run(jobs, items, f, |_| false)
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Like [`map`], but stop taking new items once `stop` returns `true` for a result (or an item
+/// panics). Items that are already being processed are finished; the others are left out
+/// (`None`).
+pub fn map_until<T, R, F, S>(
+    jobs: NonZeroUsize,
+    items: Vec<T>,
+    f: F,
+    stop: S,
+) -> Vec<Option<thread::Result<R>>>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+    S: Fn(&R) -> bool + Sync,
+{
+    run(jobs, items, f, |result| result.as_ref().map_or(true, &stop))
+}
+
+fn run<T, R, F, S>(
+    jobs: NonZeroUsize,
+    items: Vec<T>,
+    f: F,
+    stop: S,
+) -> Vec<Option<thread::Result<R>>>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+    S: Fn(&thread::Result<R>) -> bool + Sync,
+{
+    let len = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new((0..len).map(|_| None).collect::<Vec<_>>());
+    let stopped = AtomicBool::new(false);
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.get().min(len) {
+            scope.spawn(|| {
+                while !stopped.load(Ordering::Relaxed) {
+                    // Release the lock before running the job, so other threads can continue.
+                    let next = queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+                    let Some((index, item)) = next else {
+                        break;
+                    };
+
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| f(item)));
+                    if stop(&result) {
+                        stopped.store(true, Ordering::Relaxed);
+                    }
+
+                    results.lock().unwrap_or_else(PoisonError::into_inner)[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    results.into_inner().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preserves_order() {
+        let jobs = NonZeroUsize::new(3).unwrap();
+        let results = map(jobs, (0..20).collect(), |i| i * 2);
+        let results = results.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+
+        assert_eq!(results, (0..20).map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn catches_panics() {
+        let jobs = NonZeroUsize::new(2).unwrap();
+        let results = map(jobs, vec![1, 0, 2], |i| {
+            assert!(i != 0, "zero");
+            i
+        });
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+    }
+
+    #[test]
+    fn stops_early() {
+        let jobs = NonZeroUsize::new(1).unwrap();
+        let results = map_until(jobs, (0..5).collect(), |i| i, |&i| i == 2);
+        let results = results
+            .into_iter()
+            .map(|result| result.map(Result::unwrap))
+            .collect::<Vec<_>>();
+
+        assert_eq!(results, [Some(0), Some(1), Some(2), None, None]);
+    }
+}
@@ -0,0 +1,45 @@
+//! Follow the progress of a build (e.g., to show it in a terminal or a window).
+
+use std::error::Error;
+
+/// Receives the progress of a build as it happens.
+///
+/// Every method does nothing by default, so implementations only handle the events they show.
+/// Cursors may be built concurrently (by several threads), so events of different cursors can
+/// be interleaved.
+pub trait ProgressObserver: Sync {
+    /// The build of the `total` cursors of `theme` started.
+    fn theme_started(&self, theme: &str, total: usize) {
+        _ = (theme, total);
+    }
+
+    /// The cursor `name` started building.
+    fn cursor_started(&self, name: &str) {
+        _ = name;
+    }
+
+    /// `written` of the `total` frame images of the cursor `name` were written.
+    fn frame_extracted(&self, name: &str, written: usize, total: usize) {
+        _ = (name, written, total);
+    }
+
+    /// Something about the cursor `name` may not turn out as expected (e.g., its animation
+    /// doesn't follow the ANI file format specification), but the build goes on.
+    fn warning(&self, name: &str, message: &str) {
+        _ = (name, message);
+    }
+
+    /// The cursor `name` is done, and failed with `error` if it isn't `None`.
+    fn cursor_finished(&self, name: &str, error: Option<&(dyn Error + 'static)>) {
+        _ = (name, error);
+    }
+
+    /// Every cursor of the theme is done.
+    fn theme_finished(&self) {}
+}
+
+/// Ignores the progress of a build.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressObserver for NoProgress {}
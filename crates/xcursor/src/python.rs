@@ -0,0 +1,207 @@
+//! Python bindings, so theme build scripts can decode cursors and build themes without running
+//! the `ani-to-xcursor` command.
+//!
+//! The module is named `ani_to_xcursor`. Build it with [maturin](https://www.maturin.rs/), or by
+//! hand:
+//!
+//! ```sh
+//! PYO3_BUILD_EXTENSION_MODULE=1 cargo rustc -p xcursor --release --features python \
+//!     --crate-type cdylib
+//! cp target/release/libxcursor.so ani_to_xcursor.so
+//! ```
+//!
+//! Then, in Python:
+//!
+//! ```python
+//! import ani_to_xcursor
+//!
+//! data = open("Busy.ani", "rb").read()
+//! animation = ani_to_xcursor.decode(data)
+//! for frame, duration in animation.steps:
+//!     print(animation.frames[frame].width, duration)
+//!
+//! ani_to_xcursor.build_theme("Busy", "Busy", [("wait", data, ["watch"])])
+//! ```
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use ani::de::DecodeMode;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::{Cursor, ThemeBuilder};
+
+/// The image of a frame, at its largest size.
+#[pyclass(module = "ani_to_xcursor", name = "Frame", frozen, skip_from_py_object)]
+#[derive(Clone)]
+struct Frame {
+    /// The width of the image, in pixels.
+    #[pyo3(get)]
+    width: u32,
+    /// The height of the image, in pixels.
+    #[pyo3(get)]
+    height: u32,
+    /// The position of the hotspot, as `(x, y)` (`(0, 0)` if the frame doesn't have one).
+    #[pyo3(get)]
+    hotspot: (u16, u16),
+    rgba: Vec<u8>,
+}
+
+#[pymethods]
+impl Frame {
+    /// The pixels of the image as RGBA, in row-major order (4 bytes per pixel).
+    #[getter]
+    fn rgba<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.rgba)
+    }
+}
+
+/// A decoded animated (or static) cursor.
+#[pyclass(module = "ani_to_xcursor", name = "Animation", frozen)]
+struct Animation {
+    frames: Vec<Frame>,
+    /// The steps of the animation, as `(frame, duration)` pairs (in milliseconds).
+    #[pyo3(get)]
+    steps: Vec<(usize, u32)>,
+}
+
+#[pymethods]
+impl Animation {
+    /// The frames of the animation.
+    #[getter]
+    fn frames(&self) -> Vec<Frame> {
+        self.frames.clone()
+    }
+}
+
+/// Decode an ANI, CUR or PNG file from its contents (see [`crate::decode`]).
+///
+/// Raises `ValueError` if the data can't be decoded, or its sequence refers to a frame that
+/// doesn't exist.
+#[pyfunction]
+fn decode(data: &[u8]) -> PyResult<Animation> {
+    let ani = crate::decode(data, DecodeMode::default())
+        .map_err(|err| PyValueError::new_err(message(&err)))?;
+    let steps = crate::steps(&ani, 1.0).map_err(|err| PyValueError::new_err(message(&err)))?;
+
+    let frames = ani
+        .frames()
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let (index, image) = frame
+                .images()
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, image)| image.width())
+                .ok_or_else(|| PyValueError::new_err(format!("frame {i} has no images")))?;
+            let hotspot = frame.hotspot(index).unwrap_or_default();
+
+            Ok(Frame {
+                width: image.width(),
+                height: image.height(),
+                hotspot: (hotspot.x(), hotspot.y()),
+                rgba: image.rgba_data().to_vec(),
+            })
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    Ok(Animation {
+        frames,
+        steps: steps
+            .into_iter()
+            .map(|step| (step.frame, step.duration))
+            .collect(),
+    })
+}
+
+/// Build the theme `name` into `output` (see [`ThemeBuilder::build`]), from `cursors` given as
+/// `(name, data, aliases)` tuples.
+///
+/// Raises `RuntimeError` if the theme can't be built.
+#[pyfunction]
+#[expect(clippy::needless_pass_by_value)] // Arguments are extracted from Python as owned values.
+#[pyo3(signature = (
+    name, output, cursors, *, xcursorgen = PathBuf::from("xcursorgen"), speed = 1.0
+))]
+fn build_theme(
+    py: Python<'_>,
+    name: String,
+    output: PathBuf,
+    cursors: Vec<(String, Vec<u8>, Vec<String>)>,
+    xcursorgen: PathBuf,
+    speed: f64,
+) -> PyResult<()> {
+    if !(speed.is_finite() && speed > 0.0) {
+        return Err(PyValueError::new_err("speed must be greater than zero"));
+    }
+
+    let builder = cursors.into_iter().fold(
+        ThemeBuilder::new(name).xcursorgen(xcursorgen).speed(speed),
+        |builder, (name, data, aliases)| {
+            let cursor = aliases
+                .into_iter()
+                .fold(Cursor::new(name, data), Cursor::alias);
+            builder.cursor(cursor)
+        },
+    );
+
+    // Building runs `xcursorgen` for each cursor, so other Python threads may run meanwhile.
+    py.detach(|| builder.build(&output))
+        .map_err(|err| PyRuntimeError::new_err(message(&err)))
+}
+
+/// The message of `err`, followed by those of its sources.
+fn message(err: &dyn Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
+    }
+
+    message
+}
+
+/// Convert animated (and static) Windows cursors into Xcursor themes.
+#[pymodule]
+fn ani_to_xcursor(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<Animation>()?;
+    module.add_class::<Frame>()?;
+    module.add_function(wrap_pyfunction!(decode, module)?)?;
+    module.add_function(wrap_pyfunction!(build_theme, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ani::ser::AniBuilder;
+    use ico::IconImage;
+
+    use super::*;
+
+    #[test]
+    fn decode_animation() {
+        let mut image = IconImage::from_rgba_data(2, 1, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        image.set_cursor_hotspot(Some((1, 0)));
+        let data = AniBuilder::new()
+            .jif_rate(6)
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .unwrap();
+
+        let Ok(animation) = decode(&data) else {
+            panic!("expected hardcoded animation to be valid");
+        };
+
+        assert_eq!(animation.steps, [(0, 100), (1, 100)]);
+        assert_eq!(animation.frames.len(), 2);
+        assert_eq!(animation.frames[1].hotspot, (1, 0));
+        assert_eq!(animation.frames[1].rgba, [1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+}
@@ -0,0 +1,112 @@
+//! Replace a theme with a new one that was built next to it.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use tracing::info;
+
+/// Move the directory at `staged` to `dir`, removing the directory that was there before.
+///
+/// On Linux, the two are exchanged atomically, so `dir` always holds a complete theme.
+/// Elsewhere (or on file systems that don't support it), the last one is moved out of the way
+/// (to `old`) first, since a directory can't be renamed over another one that isn't empty.
+///
+/// # Errors
+///
+/// This function returns an error if a directory can't be moved or removed.
+pub fn replace_dir(staged: &Path, dir: &Path, old: &Path) -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    match exchange(staged, dir) {
+        Ok(()) => {
+            fs::remove_dir_all(staged)?;
+            info!("replaced directory: {:#}", dir.display());
+            return Ok(());
+        }
+        Err(err)
+            if matches!(
+                err.raw_os_error(),
+                Some(libc::ENOENT | libc::EINVAL | libc::ENOSYS)
+            ) => {}
+        Err(err) => return Err(err),
+    }
+
+    let replaced = match fs::rename(dir, old) {
+        Ok(()) => true,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => false,
+        Err(err) => return Err(err),
+    };
+
+    if let Err(err) = fs::rename(staged, dir) {
+        if replaced {
+            _ = fs::rename(old, dir);
+        }
+
+        return Err(err);
+    }
+
+    if replaced {
+        fs::remove_dir_all(old)?;
+    }
+
+    info!("replaced directory: {:#}", dir.display());
+    Ok(())
+}
+
+/// Exchange the directories at `a` and `b` atomically, with `renameat2(RENAME_EXCHANGE)`.
+///
+/// Fails with `ENOENT` if either doesn't exist, and with `EINVAL` or `ENOSYS` if the file
+/// system or kernel doesn't support it.
+#[cfg(target_os = "linux")]
+fn exchange(a: &Path, b: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt as _;
+
+    let a = CString::new(a.as_os_str().as_bytes())?;
+    let b = CString::new(b.as_os_str().as_bytes())?;
+
+    // SAFETY: Both paths are NUL-terminated strings that outlive the call.
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_renameat2,
+            libc::AT_FDCWD,
+            a.as_ptr(),
+            libc::AT_FDCWD,
+            b.as_ptr(),
+            libc::RENAME_EXCHANGE,
+        )
+    };
+
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace() {
+        let temp = crate::test_dir();
+        let dir = temp.path();
+        let (staged, theme, old) = (dir.join(".tmp-1"), dir.join("theme"), dir.join(".old-1"));
+
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(staged.join("index.theme"), "first").unwrap();
+        replace_dir(&staged, &theme, &old).unwrap();
+        let first = fs::read_to_string(theme.join("index.theme"));
+
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(staged.join("index.theme"), "second").unwrap();
+        replace_dir(&staged, &theme, &old).unwrap();
+        let second = fs::read_to_string(theme.join("index.theme"));
+        let leftovers = staged.exists() || old.exists();
+
+        assert_eq!(first.unwrap(), "first");
+        assert_eq!(second.unwrap(), "second");
+        assert!(!leftovers);
+    }
+}
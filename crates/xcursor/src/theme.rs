@@ -0,0 +1,990 @@
+//! Assemble Xcursors into a theme.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, ErrorKind};
+use std::num::NonZeroUsize;
+use std::path::{self, Path, PathBuf};
+use std::{env, fs, process};
+
+use ani::de::{Ani, DecodeMode};
+use tracing::{error_span, info};
+
+use crate::{
+    clamp_durations, decode, pool, replace_dir, run_xcursorgen, steps, BuildHooks, Compression,
+    ConfigWriter, DefaultHooks, Image, NoProgress, PngWriter, ProgressObserver, Step, ThemeError,
+};
+
+/// The `index.theme` file, which names the theme and the themes it inherits from.
+///
+/// ```
+/// # use xcursor::IndexTheme;
+/// let index_theme = IndexTheme::new("Busy").inherits("Adwaita");
+/// assert_eq!(index_theme.to_string(), "[Icon Theme]\nName = Busy\nInherits = Adwaita\n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexTheme {
+    name: String,
+    comment: Option<String>,
+    inherits: Option<String>,
+    example: Option<String>,
+}
+
+impl IndexTheme {
+    /// Describe the theme named `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the description of the theme.
+    #[must_use]
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Set the themes (comma-separated) to take the cursors missing from this one from.
+    #[must_use]
+    pub fn inherits(mut self, inherits: impl Into<String>) -> Self {
+        self.inherits = Some(inherits.into());
+        self
+    }
+
+    /// Set the cursor shown as a preview of the theme.
+    #[must_use]
+    pub fn example(mut self, example: impl Into<String>) -> Self {
+        self.example = Some(example.into());
+        self
+    }
+}
+
+impl fmt::Display for IndexTheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "[Icon Theme]\nName = {}", self.name)?;
+
+        let fields = [
+            ("Comment", &self.comment),
+            ("Inherits", &self.inherits),
+            ("Example", &self.example),
+        ];
+
+        for (key, value) in fields {
+            if let Some(value) = value {
+                writeln!(f, "{key} = {value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Create a symbolic link at `link` pointing to `source`, replacing any file already there.
+///
+/// # Errors
+///
+/// This function returns an error if the existing file can't be removed, or the link can't be
+/// created (always, on platforms other than Unix).
+pub fn symlink(source: &Path, link: &Path) -> io::Result<()> {
+    match fs::remove_file(link) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(source, link)
+    }
+
+    #[cfg(not(unix))]
+    {
+        _ = source;
+        Err(io::Error::new(
+            ErrorKind::Unsupported,
+            "symbolic links are only supported on Unix",
+        ))
+    }
+}
+
+/// Link the Xcursor `target` into `cursors_dir` as `name`, then link each of `aliases` to it.
+///
+/// Aliases are relative links (e.g., `watch -> wait`), so the theme can be moved or copied
+/// without breaking them. Existing links are replaced, so an alias that moved to another cursor
+/// follows it; resolve aliases claimed by several cursors first (see [`claim_aliases`]).
+///
+/// # Errors
+///
+/// This function returns an error if a link can't be created (see [`symlink`]).
+pub fn link_cursor(
+    cursors_dir: &Path,
+    name: &str,
+    aliases: &[String],
+    target: &Path,
+) -> Result<(), ThemeError> {
+    let target_link = cursors_dir.join(name);
+
+    if target_link != target {
+        symlink(target, &target_link).map_err(ThemeError::io(&target_link))?;
+    }
+
+    for alias in aliases {
+        let alias_link = cursors_dir.join(alias);
+        symlink(Path::new(name), &alias_link).map_err(ThemeError::io(&alias_link))?;
+        info!("created alias: {alias}");
+    }
+
+    Ok(())
+}
+
+/// An alias left out by [`claim_aliases`], because another cursor already uses it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AliasCollision {
+    /// The cursor that lists the alias.
+    pub cursor: String,
+
+    /// The alias.
+    pub alias: String,
+
+    /// The cursor that keeps the alias (as its name, or one of its aliases).
+    pub owner: String,
+}
+
+impl fmt::Display for AliasCollision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "alias {} of {} is already used by {}; leaving it out",
+            self.alias, self.cursor, self.owner
+        )
+    }
+}
+
+/// Remove the aliases of `cursors` (pairs of a name and its aliases) that another cursor already
+/// uses, and return them.
+///
+/// Otherwise, the links of both cursors would replace each other, and which one wins would
+/// depend on the order they are built in. Instead, the name of a cursor always wins over the
+/// aliases of the others, and an alias listed by several cursors goes to the first of them.
+/// Aliases a cursor lists more than once (or that are its own name) are removed quietly.
+pub fn claim_aliases<'a>(
+    cursors: impl IntoIterator<Item = (&'a str, &'a mut Vec<String>)>,
+) -> Vec<AliasCollision> {
+    let cursors = cursors.into_iter().collect::<Vec<_>>();
+    let mut owners = cursors
+        .iter()
+        .map(|&(name, _)| (name.to_owned(), name))
+        .collect::<HashMap<_, _>>();
+    let mut collisions = Vec::new();
+
+    for (name, aliases) in cursors {
+        aliases.retain(|alias| match owners.get(alias) {
+            None => {
+                owners.insert(alias.clone(), name);
+                true
+            }
+            Some(&owner) if owner == name => false,
+            Some(&owner) => {
+                collisions.push(AliasCollision {
+                    cursor: name.to_owned(),
+                    alias: alias.clone(),
+                    owner: owner.to_owned(),
+                });
+                false
+            }
+        });
+    }
+
+    collisions
+}
+
+/// A cursor to add to a theme.
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    name: String,
+    data: Vec<u8>,
+    aliases: Vec<String>,
+    speed: f64,
+    frames_dir: Option<PathBuf>,
+}
+
+impl Cursor {
+    /// Describe the cursor named `name` (e.g., `wait`), from the contents of an ANI, CUR or PNG
+    /// file (see [`decode`]).
+    pub fn new(name: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: name.into(),
+            data: data.into(),
+            aliases: Vec::new(),
+            speed: 1.0,
+            frames_dir: None,
+        }
+    }
+
+    /// Add another name for the cursor (e.g., `watch`), linked to it in the theme.
+    #[must_use]
+    pub fn alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into());
+        self
+    }
+
+    /// Set the playback speed of the animation, relative to the speed of the theme (see
+    /// [`ThemeBuilder::speed`]).
+    #[must_use]
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Keep the extracted frames (and the `xcursorgen` configuration) of the cursor in
+    /// `frames_dir`, rather than in a subdirectory of the one of the theme (see
+    /// [`ThemeBuilder::frames_dir`]).
+    #[must_use]
+    pub fn frames_dir(mut self, frames_dir: impl Into<PathBuf>) -> Self {
+        self.frames_dir = Some(frames_dir.into());
+        self
+    }
+
+    /// The name of the cursor.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The contents of the cursor file.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// What became of a cursor of a theme (see [`ThemeBuilder::build_with_hooks`]).
+#[derive(Debug)]
+pub enum CursorResult {
+    /// The cursor was built.
+    Built,
+
+    /// The cursor was already up to date (see [`BuildHooks::up_to_date`]).
+    UpToDate,
+
+    /// The cursor failed to build.
+    Failed(ThemeError),
+
+    /// The cursor was left out, because another one failed first (see
+    /// [`ThemeBuilder::fail_fast`]).
+    Canceled,
+}
+
+impl CursorResult {
+    /// Whether the cursor is in the theme (either built or up to date).
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        matches!(self, Self::Built | Self::UpToDate)
+    }
+}
+
+/// Build an Xcursor theme from animated (or static) Windows cursors.
+///
+/// ```no_run
+/// # use std::path::Path;
+/// # use xcursor::{Cursor, ThemeBuilder};
+/// let data = std::fs::read("Busy.ani").unwrap();
+///
+/// ThemeBuilder::new("Busy")
+///     .cursor(Cursor::new("wait", data).alias("watch"))
+///     .build(Path::new("Busy"))
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThemeBuilder {
+    index_theme: IndexTheme,
+    cursors: Vec<Cursor>,
+    xcursorgen: PathBuf,
+    mode: DecodeMode,
+    speed: f64,
+    min_frame_duration: u32,
+    compression: Compression,
+    frames_dir: Option<PathBuf>,
+    jobs: NonZeroUsize,
+    fail_fast: bool,
+}
+
+impl ThemeBuilder {
+    /// Create a builder for the theme named `name`, with no cursors.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            index_theme: IndexTheme::new(name),
+            cursors: Vec::new(),
+            xcursorgen: PathBuf::from("xcursorgen"),
+            mode: DecodeMode::default(),
+            speed: 1.0,
+            min_frame_duration: 10,
+            compression: Compression::default(),
+            frames_dir: None,
+            jobs: NonZeroUsize::MIN,
+            fail_fast: true,
+        }
+    }
+
+    /// Replace the contents of the `index.theme` file.
+    #[must_use]
+    pub fn index_theme(mut self, index_theme: IndexTheme) -> Self {
+        self.index_theme = index_theme;
+        self
+    }
+
+    /// Add a cursor to the theme.
+    #[must_use]
+    pub fn cursor(mut self, cursor: Cursor) -> Self {
+        self.cursors.push(cursor);
+        self
+    }
+
+    /// Set the `xcursorgen` binary to run: a path, or a name to look up in `PATH` (the
+    /// default is `xcursorgen`; see [`locate_xcursorgen`](crate::locate_xcursorgen)).
+    #[must_use]
+    pub fn xcursorgen(mut self, xcursorgen: impl Into<PathBuf>) -> Self {
+        self.xcursorgen = xcursorgen.into();
+        self
+    }
+
+    /// Set how forgiving to be of animations that don't follow the ANI file format
+    /// specification.
+    #[must_use]
+    pub fn mode(mut self, mode: DecodeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the playback speed of the animations (e.g., `2.0` plays them twice as fast).
+    #[must_use]
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Set the shortest time a frame is shown, in milliseconds (the default is 10); some
+    /// compositors skip (or spin on) frames with a duration of zero.
+    #[must_use]
+    pub fn min_frame_duration(mut self, min_frame_duration: u32) -> Self {
+        self.min_frame_duration = min_frame_duration;
+        self
+    }
+
+    /// Set how hard to compress the extracted frames.
+    #[must_use]
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Keep the extracted frames (and the `xcursorgen` configurations) in a subdirectory of
+    /// `frames_dir` for each cursor, rather than in a temporary directory that is removed after
+    /// the build.
+    #[must_use]
+    pub fn frames_dir(mut self, frames_dir: impl Into<PathBuf>) -> Self {
+        self.frames_dir = Some(frames_dir.into());
+        self
+    }
+
+    /// Set how many cursors to build at once (the default is one).
+    #[must_use]
+    pub fn jobs(mut self, jobs: NonZeroUsize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// Set whether to stop at the first cursor that fails to build (the default), leaving out
+    /// the cursors that haven't been started yet, rather than attempting every cursor.
+    #[must_use]
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Build the theme into `dir`: the Xcursors (and the links of their aliases) go in
+    /// `dir/cursors`, next to the `index.theme` file.
+    ///
+    /// The theme is built next to `dir` (in `.<name>.tmp-<id>-<suffix>`, where `<id>` is the ID
+    /// of the process and `<suffix>` is random), and only replaces the theme in `dir` once every
+    /// cursor is built (see [`replace_dir`]), so a failed build leaves the last one as it was.
+    ///
+    /// Aliases already used by another cursor are left out, with a warning (see
+    /// [`claim_aliases`]).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if:
+    ///
+    /// - A file can't be written.
+    /// - A cursor can't be decoded, or its animation is invalid.
+    /// - `xcursorgen` can't be run, or fails.
+    pub fn build(&self, dir: &Path) -> Result<(), ThemeError> {
+        self.build_with(dir, &NoProgress)
+    }
+
+    /// Like [`Self::build`], but passes the progress of the build on to `observer`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::build`].
+    pub fn build_with(
+        &self,
+        dir: &Path,
+        observer: &dyn ProgressObserver,
+    ) -> Result<(), ThemeError> {
+        let results = self.build_with_hooks(dir, &DefaultHooks, observer)?;
+
+        for (cursor, result) in self.cursors.iter().zip(results) {
+            if let CursorResult::Failed(err) = result {
+                return Err(ThemeError::Cursor {
+                    name: cursor.name.clone(),
+                    source: Box::new(err),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Like [`Self::build_with`], but takes the steps of the build from `hooks`, and returns
+    /// what became of each cursor (in the order they were added) rather than the first error.
+    ///
+    /// The theme in `dir` is only replaced if every cursor [succeeded](CursorResult::succeeded).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the theme can't be staged (see
+    /// [`BuildHooks::stage`]) or replaced.
+    pub fn build_with_hooks(
+        &self,
+        dir: &Path,
+        hooks: &dyn BuildHooks,
+        observer: &dyn ProgressObserver,
+    ) -> Result<Vec<CursorResult>, ThemeError> {
+        let dir = path::absolute(dir).map_err(ThemeError::io(dir))?;
+        let Some(name) = dir.file_name().and_then(|name| name.to_str()) else {
+            let err = io::Error::new(ErrorKind::InvalidInput, "expected a directory name");
+            return Err(ThemeError::io(&dir)(err));
+        };
+        let parent = dir.parent().unwrap_or(&dir);
+        fs::create_dir_all(parent).map_err(ThemeError::io(parent))?;
+
+        // Every build stages its theme in a directory of its own (even several at once, in one
+        // process), which is only ever created here, so it can't be planted beforehand.
+        let staged = tempfile::Builder::new()
+            .prefix(&format!(".{name}.tmp-{}-", process::id()))
+            .tempdir_in(parent)
+            .map_err(ThemeError::io(parent))?
+            .keep();
+        let suffix = staged
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(|file_name| file_name.rsplit_once('-'))
+            .map_or("", |(_, suffix)| suffix);
+        let old = dir.with_file_name(format!(".{name}.old-{}-{suffix}", process::id()));
+
+        let result = self
+            .build_staged(&staged, &dir, hooks, observer)
+            .and_then(|results| {
+                if results.iter().all(CursorResult::succeeded) {
+                    replace_dir(&staged, &dir, &old).map_err(ThemeError::io(&dir))?;
+                }
+
+                Ok(results)
+            });
+
+        // Unless it replaced the theme, what is left of the staged theme is incomplete.
+        _ = fs::remove_dir_all(&staged);
+        result
+    }
+
+    fn build_staged(
+        &self,
+        staged: &Path,
+        dir: &Path,
+        hooks: &dyn BuildHooks,
+        observer: &dyn ProgressObserver,
+    ) -> Result<Vec<CursorResult>, ThemeError> {
+        hooks.stage(staged, dir)?;
+
+        let cursors_dir = staged.join("cursors");
+        fs::create_dir_all(&cursors_dir).map_err(ThemeError::io(&cursors_dir))?;
+
+        let index_theme = staged.join("index.theme");
+        fs::write(&index_theme, self.index_theme.to_string())
+            .map_err(ThemeError::io(&index_theme))?;
+
+        // Unless they are kept, the frames go in a directory of the build's own, which is
+        // removed when it is dropped.
+        let temp_frames;
+        let frames_root = if let Some(ref frames_dir) = self.frames_dir {
+            path::absolute(frames_dir).map_err(ThemeError::io(frames_dir))?
+        } else {
+            temp_frames = tempfile::Builder::new()
+                .prefix("xcursor-theme-")
+                .tempdir()
+                .map_err(ThemeError::io(env::temp_dir()))?;
+            temp_frames.path().to_owned()
+        };
+
+        observer.theme_started(&self.index_theme.name, self.cursors.len());
+
+        let mut aliases = self
+            .cursors
+            .iter()
+            .map(|cursor| cursor.aliases.clone())
+            .collect::<Vec<_>>();
+        let names = self.cursors.iter().map(|cursor| cursor.name.as_str());
+
+        for collision in claim_aliases(names.zip(&mut aliases)) {
+            observer.warning(&collision.cursor, &collision.to_string());
+        }
+
+        let builds = self
+            .cursors
+            .iter()
+            .zip(&aliases)
+            .map(|(cursor, aliases)| CursorBuild {
+                builder: self,
+                cursor,
+                aliases,
+                frames_dir: match cursor.frames_dir {
+                    Some(ref frames_dir) => frames_dir.clone(),
+                    None => frames_root.join(&cursor.name),
+                },
+                theme_dir: staged,
+                observer,
+            })
+            .collect::<Vec<_>>();
+
+        let results = pool::map_until(
+            self.jobs,
+            builds,
+            |build| {
+                // Attach context so we know which thread is emitting the events.
+                let span = error_span!("", cursor = ?build.cursor.name);
+
+                span.in_scope(|| {
+                    observer.cursor_started(&build.cursor.name);
+                    let result = self.build_cursor(&build, hooks);
+                    let error = result.as_ref().err().map(|err| err as _);
+                    observer.cursor_finished(&build.cursor.name, error);
+                    result
+                })
+            },
+            |result| self.fail_fast && result.is_err(),
+        );
+
+        observer.theme_finished();
+
+        Ok(results
+            .into_iter()
+            .map(|result| match result {
+                Some(Ok(Ok(result))) => result,
+                Some(Ok(Err(err))) => CursorResult::Failed(err),
+                Some(Err(_)) => CursorResult::Failed(ThemeError::Panicked),
+                None => CursorResult::Canceled,
+            })
+            .collect())
+    }
+
+    fn build_cursor(
+        &self,
+        build: &CursorBuild<'_>,
+        hooks: &dyn BuildHooks,
+    ) -> Result<CursorResult, ThemeError> {
+        let data = hooks.read(build)?;
+
+        if hooks.up_to_date(build, &data)? {
+            info!("cursor is up to date");
+            return Ok(CursorResult::UpToDate);
+        }
+
+        let frames_dir = &build.frames_dir;
+        fs::create_dir_all(frames_dir).map_err(ThemeError::io(frames_dir))?;
+        let mut ani = decode(&data, self.mode)?;
+
+        for warning in ani.warnings() {
+            build.warning(&warning.to_string());
+        }
+
+        for issue in ani.validate() {
+            build.warning(&issue.to_string());
+        }
+
+        let frames = hooks.extract(build, &data, &mut ani)?;
+        let mut steps = steps(&ani, self.speed * build.cursor.speed)?;
+        let clamped = clamp_durations(&mut steps, self.min_frame_duration);
+
+        // The duration of a single frame doesn't matter, since it's never replaced.
+        if clamped > 0 && steps.len() > 1 {
+            build.warning(&format!(
+                "raised the duration of {clamped} of {} steps to {}ms",
+                steps.len(),
+                self.min_frame_duration
+            ));
+        }
+
+        hooks.write(build, &frames, &steps)?;
+        Ok(CursorResult::Built)
+    }
+}
+
+/// A cursor being built, passed to each of the [`BuildHooks`].
+pub struct CursorBuild<'a> {
+    builder: &'a ThemeBuilder,
+    cursor: &'a Cursor,
+    aliases: &'a [String],
+    frames_dir: PathBuf,
+    theme_dir: &'a Path,
+    observer: &'a dyn ProgressObserver,
+}
+
+impl<'a> CursorBuild<'a> {
+    /// The cursor, as it was added to the theme.
+    #[must_use]
+    pub fn cursor(&self) -> &'a Cursor {
+        self.cursor
+    }
+
+    /// The name of the cursor.
+    #[must_use]
+    pub fn name(&self) -> &'a str {
+        &self.cursor.name
+    }
+
+    /// The aliases of the cursor, leaving out the ones another cursor already uses (see
+    /// [`claim_aliases`]).
+    #[must_use]
+    pub fn aliases(&self) -> &'a [String] {
+        self.aliases
+    }
+
+    /// The directory the frames of the cursor are extracted to.
+    #[must_use]
+    pub fn frames_dir(&self) -> &Path {
+        &self.frames_dir
+    }
+
+    /// The directory the theme is staged in (see [`BuildHooks::stage`]).
+    #[must_use]
+    pub fn theme_dir(&self) -> &'a Path {
+        self.theme_dir
+    }
+
+    /// The directory of the staged theme the Xcursors (and their links) go in.
+    #[must_use]
+    pub fn cursors_dir(&self) -> PathBuf {
+        self.theme_dir.join("cursors")
+    }
+
+    /// Report something about the cursor that may not turn out as expected (see
+    /// [`ProgressObserver::warning`]).
+    pub fn warning(&self, message: &str) {
+        self.observer.warning(&self.cursor.name, message);
+    }
+
+    /// Report that `written` of the `total` frame images of the cursor were written (see
+    /// [`ProgressObserver::frame_extracted`]).
+    pub fn frame_extracted(&self, written: usize, total: usize) {
+        self.observer
+            .frame_extracted(&self.cursor.name, written, total);
+    }
+
+    /// Write every image of every frame of `ani` to the frames directory as a PNG file (named
+    /// after the index of the frame and the size of the image, e.g., `00-32.png`).
+    ///
+    /// Only the first image of each size is kept, since an Xcursor can only hold one per size.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a file can't be written.
+    pub fn extract_frames(&self, ani: &Ani) -> Result<Vec<Vec<Image>>, ThemeError> {
+        let mut frames = Vec::with_capacity(ani.frames().len());
+        let mut pending = Vec::new();
+
+        for (i, frame) in ani.frames().iter().enumerate() {
+            let mut images = Vec::<Image>::with_capacity(frame.images().len());
+
+            for (j, image) in frame.images().iter().enumerate() {
+                let size = image.width();
+
+                // An Xcursor can only hold one image per size.
+                if images.iter().any(|image| image.size() == size) {
+                    continue;
+                }
+
+                let file_name = format!("{i:02}-{size}.png");
+                pending.push((self.frames_dir.join(&file_name), image));
+
+                let hotspot = frame.hotspot(j).unwrap_or_default();
+                images.push(Image::new(size, (hotspot.x(), hotspot.y()), file_name));
+            }
+
+            frames.push(images);
+        }
+
+        let mut writer = PngWriter::new(self.builder.compression);
+        for (written, (path, image)) in pending.iter().enumerate() {
+            writer.write(path, image).map_err(ThemeError::io(path))?;
+            self.frame_extracted(written + 1, pending.len());
+        }
+
+        Ok(frames)
+    }
+
+    /// Write the `xcursorgen` configuration of the cursor (`<name>.cursor`, in the frames
+    /// directory), run `xcursorgen` on it and link the aliases to the Xcursor it creates, which
+    /// is returned.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if the configuration is invalid or can't be written, if
+    /// `xcursorgen` fails, or if a link can't be created.
+    pub fn write_xcursor(
+        &self,
+        frames: &[Vec<Image>],
+        steps: &[Step],
+    ) -> Result<PathBuf, ThemeError> {
+        let writer = frames
+            .iter()
+            .cloned()
+            .fold(ConfigWriter::new(), ConfigWriter::frame);
+
+        let config = self.frames_dir.join(format!("{}.cursor", self.cursor.name));
+        fs::write(&config, writer.steps(steps).encode()?).map_err(ThemeError::io(&config))?;
+
+        let output = self.cursors_dir().join(&self.cursor.name);
+        run_xcursorgen(&self.builder.xcursorgen, &self.frames_dir, &config, &output)?;
+        info!("created Xcursor: {:#}", output.display());
+
+        self.link(&output)?;
+        Ok(output)
+    }
+
+    /// Link the Xcursor `target` into the staged theme as the cursor, then link its aliases to
+    /// it (see [`link_cursor`]).
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error if a link can't be created.
+    pub fn link(&self, target: &Path) -> Result<(), ThemeError> {
+        link_cursor(&self.cursors_dir(), &self.cursor.name, self.aliases, target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::sync::Mutex;
+    use std::thread;
+
+    use ani::ser::AniBuilder;
+    use ico::IconImage;
+
+    use super::*;
+
+    /// Records the events of a build.
+    #[derive(Default)]
+    struct Events(Mutex<Vec<String>>);
+
+    impl ProgressObserver for Events {
+        fn theme_started(&self, theme: &str, total: usize) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("start {theme} {total}"));
+        }
+
+        fn frame_extracted(&self, name: &str, written: usize, total: usize) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("frame {name} {written}/{total}"));
+        }
+
+        fn cursor_finished(&self, name: &str, error: Option<&(dyn Error + 'static)>) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("done {name} {}", error.is_none()));
+        }
+    }
+
+    #[test]
+    fn claim() {
+        let mut cursors = [
+            ("default", vec!["left_ptr".to_owned(), "hand2".to_owned()]),
+            ("pointer", vec!["hand2".to_owned(), "default".to_owned()]),
+            (
+                "text",
+                vec!["xterm".to_owned(), "xterm".to_owned(), "text".to_owned()],
+            ),
+        ];
+
+        let collisions = claim_aliases(cursors.iter_mut().map(|(name, aliases)| (*name, aliases)));
+
+        assert_eq!(
+            collisions
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "alias hand2 of pointer is already used by default; leaving it out",
+                "alias default of pointer is already used by default; leaving it out",
+            ]
+        );
+        assert_eq!(cursors[0].1, ["left_ptr", "hand2"]);
+        assert!(cursors[1].1.is_empty());
+        assert_eq!(cursors[2].1, ["xterm"]);
+    }
+
+    #[test]
+    fn build() {
+        let temp = crate::test_dir();
+        let dir = temp.path();
+        let image = |hotspot| {
+            let mut image = IconImage::from_rgba_data(32, 32, vec![0xff; 32 * 32 * 4]);
+            image.set_cursor_hotspot(Some(hotspot));
+            image
+        };
+        let data = AniBuilder::new()
+            .jif_rate(6)
+            .frame(vec![image((1, 2))])
+            .frame(vec![image((3, 4))])
+            .encode()
+            .unwrap();
+
+        // `true` accepts any arguments, standing in for xcursorgen.
+        let events = Events::default();
+        let result = ThemeBuilder::new("Busy")
+            .index_theme(IndexTheme::new("Busy").comment("Test"))
+            .cursor(Cursor::new("wait", data).alias("watch"))
+            .xcursorgen("true")
+            .frames_dir(dir.join("frames"))
+            .build_with(&dir.join("theme"), &events);
+
+        let config = fs::read_to_string(dir.join("frames/wait/wait.cursor"));
+        let index_theme = fs::read_to_string(dir.join("theme/index.theme"));
+        let alias = fs::read_link(dir.join("theme/cursors/watch"));
+        let mut entries = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        result.unwrap();
+        assert_eq!(
+            config.unwrap(),
+            "32 1 2 00-32.png 100\n32 3 4 01-32.png 100\n"
+        );
+        assert_eq!(
+            index_theme.unwrap(),
+            "[Icon Theme]\nName = Busy\nComment = Test\n"
+        );
+        assert_eq!(alias.unwrap(), Path::new("wait"));
+        assert_eq!(entries, ["frames", "theme"]);
+        assert_eq!(
+            events.0.into_inner().unwrap(),
+            [
+                "start Busy 1",
+                "frame wait 1/2",
+                "frame wait 2/2",
+                "done wait true"
+            ]
+        );
+    }
+
+    #[test]
+    fn concurrent_builds() {
+        let dir = crate::test_dir();
+        let image = IconImage::from_rgba_data(32, 32, vec![0xff; 32 * 32 * 4]);
+        let data = AniBuilder::new()
+            .frame(vec![image.clone()])
+            .frame(vec![image])
+            .encode()
+            .unwrap();
+
+        // Both extract the frames of `wait` to a temporary directory, and stage their theme next
+        // to the other's, without getting in each other's way.
+        let results = thread::scope(|scope| {
+            let builds = ["first", "second"].map(|name| {
+                let (data, dir) = (&data, dir.path());
+                scope.spawn(move || {
+                    ThemeBuilder::new(name)
+                        .cursor(Cursor::new("wait", data.clone()))
+                        .xcursorgen("true")
+                        .build(&dir.join(name))
+                })
+            });
+
+            builds.map(|build| build.join().unwrap())
+        });
+
+        let mut entries = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        for result in results {
+            result.unwrap();
+        }
+        assert_eq!(entries, ["first", "second"]);
+    }
+
+    /// Skips `default` as up to date, and fails to write any other cursor.
+    struct Hooks;
+
+    impl BuildHooks for Hooks {
+        fn up_to_date(&self, cursor: &CursorBuild<'_>, _data: &[u8]) -> Result<bool, ThemeError> {
+            Ok(cursor.name() == "default")
+        }
+
+        fn write(
+            &self,
+            _cursor: &CursorBuild<'_>,
+            _frames: &[Vec<Image>],
+            _steps: &[Step],
+        ) -> Result<(), ThemeError> {
+            Err(ThemeError::hook("disk full"))
+        }
+    }
+
+    #[test]
+    fn hooks() {
+        let temp = crate::test_dir();
+        let dir = temp.path();
+        let theme = dir.join("theme");
+        fs::create_dir_all(&theme).unwrap();
+        fs::write(theme.join("index.theme"), "last").unwrap();
+
+        let image = IconImage::from_rgba_data(32, 32, vec![0xff; 32 * 32 * 4]);
+        let data = AniBuilder::new().frame(vec![image]).encode().unwrap();
+
+        let results = ThemeBuilder::new("Busy")
+            .cursor(Cursor::new("default", Vec::new()))
+            .cursor(Cursor::new("wait", data))
+            .frames_dir(dir.join("frames"))
+            .fail_fast(false)
+            .build_with_hooks(&theme, &Hooks, &NoProgress);
+
+        let index_theme = fs::read_to_string(theme.join("index.theme"));
+        let mut entries = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect::<Vec<_>>();
+        entries.sort();
+
+        let results = results.unwrap();
+        assert!(matches!(results[0], CursorResult::UpToDate));
+        assert!(
+            matches!(results[1], CursorResult::Failed(ref err) if err.to_string() == "disk full")
+        );
+        assert_eq!(index_theme.unwrap(), "last");
+        assert_eq!(entries, ["frames", "theme"]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Find and run `xcursorgen`, which assembles the extracted frames into an Xcursor.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use tracing::debug;
+
+use crate::ThemeError;
+
+/// The environment variable pointing at the `xcursorgen` binary to use.
+pub const XCURSORGEN_ENV: &str = "XCURSORGEN";
+
+/// Find the `xcursorgen` binary: the one in `$XCURSORGEN`, else `configured`, else
+/// `xcursorgen` from `PATH`.
+///
+/// Either setting can be a path, or a name to look up in `PATH`.
+///
+/// # Errors
+///
+/// This function returns [`ThemeError::XcursorgenNotFound`] if the binary doesn't exist or
+/// isn't executable.
+pub fn locate_xcursorgen(configured: Option<&Path>) -> Result<PathBuf, ThemeError> {
+    let (program, setting) = match env::var_os(XCURSORGEN_ENV).filter(|value| !value.is_empty()) {
+        Some(value) => (PathBuf::from(value), Some(XcursorgenSetting::Env)),
+        None => match configured {
+            Some(path) => (path.to_owned(), Some(XcursorgenSetting::Configured)),
+            None => (PathBuf::from("xcursorgen"), None),
+        },
+    };
+
+    let found = if program.components().count() > 1 {
+        Some(program.clone()).filter(|path| is_executable(path))
+    } else {
+        find_in_path(&program)
+    };
+
+    let path = found.ok_or(ThemeError::XcursorgenNotFound { program, setting })?;
+    debug!("using xcursorgen: {:#}", path.display());
+    Ok(path)
+}
+
+/// Where the `xcursorgen` binary to use was set (see [`locate_xcursorgen`]).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XcursorgenSetting {
+    /// The `XCURSORGEN` environment variable.
+    Env,
+    /// The path passed to [`locate_xcursorgen`].
+    Configured,
+}
+
+/// Run `program` (the `xcursorgen` binary, either a path or a name to look up in `PATH`) to
+/// create the Xcursor `output` from the `config` in `frames_dir`.
+///
+/// The file names in the configuration are relative to `frames_dir`.
+///
+/// # Errors
+///
+/// This function returns an error if `program` can't be run, or if it fails.
+pub fn run_xcursorgen(
+    program: &Path,
+    frames_dir: &Path,
+    config: &Path,
+    output: &Path,
+) -> Result<(), ThemeError> {
+    let status = Command::new(program)
+        .args([config.as_os_str(), output.as_os_str()])
+        .current_dir(frames_dir)
+        .status()
+        .map_err(|err| ThemeError::XcursorgenFailure {
+            program: program.to_owned(),
+            source: err,
+        })?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ThemeError::XcursorgenExit {
+            code: status.code(),
+        })
+    }
+}
+
+/// Look up the executable `name` in the directories of `PATH`.
+fn find_in_path(name: &Path) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+
+    env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        path.metadata()
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing() {
+        // Only meaningful when the variable isn't set by the environment running the tests.
+        if env::var_os(XCURSORGEN_ENV).is_some() {
+            return;
+        }
+
+        let err = locate_xcursorgen(Some(Path::new("/nonexistent/xcursorgen"))).unwrap_err();
+        assert!(matches!(
+            err,
+            ThemeError::XcursorgenNotFound {
+                setting: Some(XcursorgenSetting::Configured),
+                ..
+            }
+        ));
+
+        let sh = locate_xcursorgen(Some(Path::new("sh"))).unwrap();
+        assert!(sh.is_absolute() || sh.components().count() > 1);
+    }
+}
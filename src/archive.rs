@@ -0,0 +1,187 @@
+//! Read cursor files directly out of zip archives, and extract downloaded cursor packs.
+
+use std::fs::{self, File};
+use std::io::{self, Read as _};
+use std::path::{Component, Path};
+use std::process::{self, Command};
+
+use anyhow::{anyhow, bail, Context as _};
+use tracing::info;
+use zip::ZipArchive;
+
+/// The largest file [`read_entry`] reads out of an archive, which is as large as a chunk of an
+/// ANI file may be (see [`ani::de::Limits`]).
+const MAX_ENTRY_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Read the contents of the file at `name` inside of `archive`.
+///
+/// Entry names always use forward slashes; a leading `./` in `name` is ignored. Files larger
+/// than [`MAX_ENTRY_SIZE`] are refused, whatever size the archive claims they are.
+pub fn read_entry(archive: &Path, name: &Path) -> anyhow::Result<Vec<u8>> {
+    let mut zip = open(archive)?;
+    let entry_name = entry_name(name);
+
+    let mut entry = zip
+        .by_name(&entry_name)
+        .with_context(|| format!("file not found in archive: {entry_name}"))?;
+
+    let too_large =
+        || anyhow!("file in archive is larger than {MAX_ENTRY_SIZE} bytes: {entry_name}");
+    if entry.size() > MAX_ENTRY_SIZE {
+        return Err(too_large());
+    }
+
+    let mut buffer = Vec::with_capacity(usize::try_from(entry.size()).unwrap_or_default());
+    (&mut entry)
+        .take(MAX_ENTRY_SIZE + 1)
+        .read_to_end(&mut buffer)
+        .with_context(|| format!("failed to read {entry_name} from archive"))?;
+
+    if u64::try_from(buffer.len()).is_ok_and(|len| len > MAX_ENTRY_SIZE) {
+        return Err(too_large());
+    }
+
+    Ok(buffer)
+}
+
+/// Check whether `archive` contains a file at `name`, using the same rules as [`read_entry`].
+pub fn contains(archive: &Path, name: &Path) -> anyhow::Result<bool> {
+    let zip = open(archive)?;
+    Ok(zip.index_for_name(&entry_name(name)).is_some())
+}
+
+/// Extract the cursor files (`.ani`, `.cur` and `.inf`) of a cursor pack into `destination`,
+/// keeping the layout of the archive.
+///
+/// Zip archives are read directly. Cabinet (`.cab`) and 7-Zip (`.7z`) archives are extracted
+/// with `cabextract` and `7z` respectively, which must be installed. The format is detected
+/// from the data rather than the file extension.
+pub fn extract_pack(archive: &Path, destination: &Path) -> anyhow::Result<()> {
+    let mut signature = Vec::with_capacity(6);
+    File::open(archive)
+        .and_then(|file| file.take(6).read_to_end(&mut signature))
+        .with_context(|| format!("failed to read archive: {}", archive.display()))?;
+
+    let path = &archive.display().to_string();
+
+    match signature.as_slice() {
+        [b'P', b'K', ..] => extract(archive, destination),
+        [b'M', b'S', b'C', b'F', ..] => extract_with(destination, |output| {
+            Command::new("cabextract")
+                .args(["--quiet", "--directory", output, path])
+                .status()
+                .context("failed to execute cabextract (is it installed?)")
+        }),
+        [b'7', b'z', 0xBC, 0xAF, 0x27, 0x1C] => extract_with(destination, |output| {
+            Command::new("7z")
+                .args(["x", "-y", &format!("-o{output}"), path])
+                .status()
+                .context("failed to execute 7z (is it installed?)")
+        }),
+        _ => bail!("unsupported archive format (expected .zip, .cab or .7z)"),
+    }
+}
+
+/// Extract the cursor files inside of the zip `archive` into `destination`.
+///
+/// Entries that would be written outside of `destination` (e.g., `../evil`) are skipped.
+fn extract(archive: &Path, destination: &Path) -> anyhow::Result<()> {
+    let mut zip = open(archive)?;
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).context("failed to read archive entry")?;
+
+        let Some(relative) = entry.enclosed_name() else {
+            let name = String::from_utf8_lossy(entry.name_raw());
+            info!("skipping unsafe archive entry: {name}");
+            continue;
+        };
+
+        if entry.is_dir() || !is_pack_file(&relative) {
+            continue;
+        }
+
+        let output = destination.join(relative);
+
+        if let Some(parent) = output.parent() {
+            fs::create_dir_all(parent).context("failed to create directory")?;
+        }
+
+        let mut file = File::create(&output)
+            .with_context(|| format!("failed to create file: {}", output.display()))?;
+        io::copy(&mut entry, &mut file)
+            .with_context(|| format!("failed to extract file: {}", output.display()))?;
+        info!("extracted file: {:#}", output.display());
+    }
+
+    Ok(())
+}
+
+/// Extract an archive into a temporary directory using an external tool, then move its cursor
+/// files into `destination`.
+fn extract_with<F>(destination: &Path, run: F) -> anyhow::Result<()>
+where
+    F: FnOnce(&str) -> anyhow::Result<process::ExitStatus>,
+{
+    // A directory of its own, which is removed when it is dropped.
+    let temp = tempfile::Builder::new()
+        .prefix("ani-to-xcursor-extract-")
+        .tempdir()
+        .context("failed to create temporary directory")?;
+
+    let status = run(&temp.path().display().to_string())?;
+    if !status.success() {
+        return Err(anyhow!("failed to extract archive: {status}"));
+    }
+
+    copy_pack_files(temp.path(), destination)
+}
+
+/// Recursively copy the cursor files in `input` into `output`.
+fn copy_pack_files(input: &Path, output: &Path) -> anyhow::Result<()> {
+    for entry in fs::read_dir(input).context("failed to read directory")? {
+        let entry = entry.context("failed to read directory entry")?;
+        let source = entry.path();
+        let destination = output.join(entry.file_name());
+
+        if source.is_dir() {
+            copy_pack_files(&source, &destination)?;
+        } else if is_pack_file(&source) {
+            fs::create_dir_all(output).context("failed to create directory")?;
+            fs::copy(&source, &destination)
+                .with_context(|| format!("failed to copy file: {}", source.display()))?;
+            info!("extracted file: {:#}", destination.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Check whether a file is needed to build a theme: a cursor, or the `Install.inf` describing
+/// the cursors.
+fn is_pack_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            ["ani", "cur", "inf"]
+                .iter()
+                .any(|expected| extension.eq_ignore_ascii_case(expected))
+        })
+}
+
+fn open(archive: &Path) -> anyhow::Result<ZipArchive<File>> {
+    let file = File::open(archive)
+        .with_context(|| format!("failed to open archive: {}", archive.display()))?;
+
+    ZipArchive::new(file).context("failed to read zip archive")
+}
+
+fn entry_name(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
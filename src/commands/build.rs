@@ -1,112 +1,909 @@
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt::Write as _;
-use std::fs::File;
-use std::io::{self, ErrorKind, Write as _};
-use std::path::Path;
-use std::process::Command;
-use std::{env, fs, iter, path, thread};
-
-use ani::de::{Ani, JIFFY};
-use anyhow::{anyhow, Context as _};
+use std::io::{self, Write as _};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use std::{env, fmt, fs, iter, mem, path};
+
+use ani::de::{Ani, DecodeMode, Hotspot};
+use anyhow::{anyhow, bail, Context as _};
 use colored::Colorize as _;
-use tracing::{error, error_span, info};
+use ico::IconImage;
+use serde::Serialize;
+use tracing::{error, info, warn};
+use xcursor::pool;
+use xcursor::{
+    BuildHooks, CursorBuild, CursorResult, Image, IndexTheme, PngWriter, ProgressObserver, Step,
+    ThemeBuilder, ThemeError,
+};
 
+use crate::archive;
 use crate::commands::Run;
-use crate::config::{Config, Cursor};
+use crate::config::{Animation, Config, Cursor, Frames, Layout, Shadow, Transform};
 use crate::context::Context;
-use crate::package::{Build as BuildDir, Package};
+use crate::exit::{self, Failure};
+use crate::fingerprint::{self, Fingerprints, Hasher};
+use crate::frame_cache::FrameCache;
+use crate::hyprcursor;
+use crate::names;
+use crate::package::{Build as BuildDir, Package, Theme};
+use crate::progress::Terminal;
+use crate::scale;
+use crate::staging;
+use crate::svg;
+use crate::transform;
+use crate::trim;
 use crate::verbosity::VerbosityLevel;
+use crate::watch;
 
 #[derive(Debug, Clone, Default, clap::Args)]
+#[expect(clippy::struct_excessive_bools)]
 pub struct Build {
+    /// How forgiving to be of input that doesn't follow the ANI file format specification.
+    #[clap(long, value_enum, default_value_t)]
+    mode: Mode,
+
+    #[clap(flatten)]
+    selection: Selection,
+
+    /// Build only the cursors with the given names (separated by commas).
+    #[clap(
+        long,
+        value_name = "NAMES",
+        value_delimiter = ',',
+        conflicts_with = "skip"
+    )]
+    only: Vec<String>,
+
+    /// Build every cursor except the ones with the given names (separated by commas).
+    #[clap(long, value_name = "NAMES", value_delimiter = ',')]
+    skip: Vec<String>,
+
+    /// Stop at the first cursor that fails to build, leaving out the cursors that haven't been
+    /// started yet [default: `fail_fast` in `Cursor.toml`, or false].
+    #[clap(long, overrides_with = "keep_going")]
+    fail_fast: bool,
+
+    /// Attempt every cursor, even after one fails to build (overrides `fail_fast` in
+    /// `Cursor.toml`).
+    #[clap(long, overrides_with = "fail_fast")]
+    keep_going: bool,
+
+    /// Rebuild every cursor, even if its inputs haven't changed since the last build.
     #[clap(long)]
-    strict: bool,
+    force: bool,
+
+    /// Replace extracted frames even if they were edited since they were generated.
+    #[clap(long)]
+    overwrite: bool,
+
+    /// The maximum number of threads to use [default: the number of CPUs]
+    #[clap(long, short)]
+    jobs: Option<NonZeroUsize>,
+
+    /// How to report the result of the build.
+    #[clap(long, value_enum, default_value_t)]
+    format: Format,
+
+    /// The cursor format to build.
+    #[clap(long, value_enum, default_value_t)]
+    target: Target,
+
+    /// Build in this directory instead of `build` (or the `build_dir` set in `Cursor.toml`).
+    #[clap(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+
+    /// Keep running, and rebuild whenever `Cursor.toml`, an input or an extracted frame
+    /// changes.
+    #[clap(long, conflicts_with = "format")]
+    watch: bool,
+
+    /// Also export each frame as an SVG file (embedding the largest image) to `build/svg`, for
+    /// SVG-based cursor theme pipelines.
+    #[clap(long)]
+    export_svg: bool,
+}
+
+/// Which themes of the package to build (and install).
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Selection {
+    /// Build only the theme with the given name, of a package that defines several with
+    /// `[[theme]]` [default: every theme].
+    #[clap(long)]
+    theme: Option<String>,
+
+    /// Build the theme profile with the given name instead of the base theme.
+    #[clap(long, conflicts_with = "all_profiles")]
+    profile: Option<String>,
+
+    /// Build the base theme (and its variants) and every profile defined in `Cursor.toml`.
+    #[clap(long)]
+    all_profiles: bool,
+}
+
+/// How a command reports its result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Human-readable messages.
+    #[default]
+    Text,
+
+    /// A machine-readable summary, printed to stdout.
+    Json,
+}
+
+/// How forgiving to be of input that doesn't follow the ANI file format specification.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Mode {
+    /// Reject anything that doesn't follow the specification exactly.
+    Strict,
+
+    /// Work around the mistakes commonly found in the wild, such as chunks out of order.
+    #[default]
+    Tolerant,
+
+    /// Like `tolerant`, but also salvage the complete frames of a truncated file.
+    Recover,
+}
+
+impl From<Mode> for DecodeMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Strict => Self::Strict,
+            Mode::Tolerant => Self::Tolerant,
+            Mode::Recover => Self::Recover,
+        }
+    }
+}
+
+/// The format to build the cursors in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Target {
+    /// Xcursor files, which every X11 and Wayland desktop supports.
+    #[default]
+    Xcursor,
+
+    /// Hyprcursor shapes, for Hyprland (added to the theme alongside any Xcursors).
+    Hyprcursor,
+}
+
+/// Settings that apply to every cursor in a build.
+#[derive(Clone, Copy)]
+#[expect(clippy::struct_excessive_bools)]
+struct Options<'a> {
+    mode: Mode,
+    target: Target,
+    force: bool,
+    overwrite: bool,
+    export_svg: bool,
+
+    /// Whether some of the cursors are left out of the build (with `--only` or `--skip`).
+    partial: bool,
+
+    /// Stop at the first cursor that fails to build.
+    fail_fast: bool,
+
+    /// The maximum number of threads to use.
+    jobs: NonZeroUsize,
+
+    /// The `xcursorgen` binary (see [`xcursor::locate_xcursorgen`]), unless only hyprcursors
+    /// are built.
+    xcursorgen: Option<&'a Path>,
+
+    /// The frames extracted by previous builds, shared by every theme of the package.
+    cache: &'a FrameCache,
+
+    /// Receives the progress of the build (and the warnings of each cursor).
+    observer: &'a dyn ProgressObserver,
+}
+
+/// A summary of a build, for `--format json`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Report {
+    pub themes: Vec<ThemeReport>,
+}
+
+impl Report {
+    /// The number of cursors that failed to build, across every theme.
+    pub fn failures(&self) -> usize {
+        self.themes.iter().map(ThemeReport::failures).sum()
+    }
+
+    /// The error to exit with if any cursor failed to build (see [`ThemeReport::error`]).
+    pub fn error(&self) -> Option<anyhow::Error> {
+        self.themes.iter().find_map(ThemeReport::error)
+    }
+
+    pub fn print(&self) -> anyhow::Result<()> {
+        let mut stdout = io::stdout().lock();
+        serde_json::to_writer_pretty(&mut stdout, self).context("failed to write report")?;
+        writeln!(stdout)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeReport {
+    pub theme: String,
+
+    /// The directory the theme was built in.
+    pub output: PathBuf,
+
+    /// Where the theme was installed to, if it was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed: Option<PathBuf>,
+    pub duration_ms: u128,
+    pub cursors: Vec<CursorReport>,
+}
+
+impl ThemeReport {
+    /// The number of cursors that failed to build.
+    pub fn failures(&self) -> usize {
+        self.cursors
+            .iter()
+            .filter(|cursor| cursor.status == Status::Failed)
+            .count()
+    }
+
+    /// The error to exit with if any cursor failed to build, classified by the first failure.
+    pub fn error(&self) -> Option<anyhow::Error> {
+        let failure = self.cursors.iter().find_map(|cursor| cursor.failure)?;
+
+        Some(anyhow!("failed to create ({}) cursors", self.failures()).context(failure))
+    }
+
+    /// Print a table of the cursors, with how long each took to build and what came out of it.
+    fn print_table(&self) -> io::Result<()> {
+        let width = self
+            .cursors
+            .iter()
+            .map(|cursor| cursor.name.len())
+            .chain(iter::once("Cursor".len()))
+            .max()
+            .unwrap_or_default();
+
+        let mut stderr = io::stderr().lock();
+        let heading = format!(
+            "{:<width$}  {:<10}  {:>6}  {:>10}  {:>8}",
+            "Cursor", "Status", "Frames", "Size", "Time"
+        );
+        writeln!(stderr, "{}", heading.bold())?;
+
+        for cursor in &self.cursors {
+            let status = format!("{:<10}", cursor.status.as_str());
+            let status = match cursor.status {
+                Status::Built => status.green(),
+                Status::UpToDate => status.normal(),
+                Status::Failed => status.red(),
+                Status::Canceled => status.yellow(),
+            };
+            let frames = cursor
+                .frames
+                .map_or_else(|| "-".to_owned(), |frames| frames.to_string());
+            let size = cursor
+                .output_bytes
+                .map_or_else(|| "-".to_owned(), format_bytes);
+
+            writeln!(
+                stderr,
+                "{:<width$}  {status}  {frames:>6}  {size:>10}  {:>5} ms",
+                cursor.name, cursor.duration_ms
+            )?;
+        }
+
+        let count = |status| {
+            self.cursors
+                .iter()
+                .filter(|cursor| cursor.status == status)
+                .count()
+        };
+        write!(
+            stderr,
+            "{} cursors in {} ms: {} built; {} up to date; {} failed",
+            self.cursors.len(),
+            self.duration_ms,
+            count(Status::Built),
+            count(Status::UpToDate),
+            count(Status::Failed)
+        )?;
+
+        match count(Status::Canceled) {
+            0 => writeln!(stderr),
+            canceled => writeln!(stderr, "; {canceled} canceled"),
+        }
+    }
+
+    /// Summarize the warnings of every cursor, so they aren't lost among the build logs.
+    fn print_warnings(&self) -> io::Result<()> {
+        let warnings = self
+            .cursors
+            .iter()
+            .flat_map(|cursor| iter::repeat(&cursor.name).zip(&cursor.warnings))
+            .collect::<Vec<_>>();
+
+        if warnings.is_empty() {
+            return Ok(());
+        }
+
+        let mut stderr = io::stderr().lock();
+        let heading = format!("Warnings ({}):", warnings.len());
+        writeln!(stderr, "{}", heading.bold().yellow())?;
+
+        for (name, warning) in warnings {
+            writeln!(stderr, "  {}: {warning}", name.bold())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CursorReport {
+    pub name: String,
+    pub status: Status,
+
+    /// The Xcursor file, if the cursor was built.
+    pub output: Option<PathBuf>,
+
+    /// The size of the output file, in bytes.
+    pub output_bytes: Option<u64>,
+
+    /// The number of frames of the animation.
+    pub frames: Option<usize>,
+    pub duration_ms: u128,
+    pub warnings: Vec<String>,
+    pub error: Option<String>,
+
+    /// The class of the error, which decides the exit code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<Failure>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    Built,
+    UpToDate,
+    Failed,
+
+    /// Not attempted, because another cursor failed first (see `--fail-fast`).
+    Canceled,
+}
+
+impl CursorReport {
+    /// Log the error the cursor failed to build with, and record it in the report.
+    fn fail(&mut self, err: &anyhow::Error, level: VerbosityLevel) {
+        let mut error_message = err.to_string();
+
+        if level >= VerbosityLevel::Verbose {
+            error_message.push('\n');
+
+            for cause in err.chain() {
+                _ = writeln!(error_message, "  Cause: {cause}");
+            }
+        }
+
+        error!("failed to process cursor: {}: {error_message}", self.name);
+        self.error = Some(format!("{err:#}"));
+        self.failure = Some(Failure::of(err));
+    }
+}
+
+impl Status {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Built => "built",
+            Self::UpToDate => "up-to-date",
+            Self::Failed => "failed",
+            Self::Canceled => "canceled",
+        }
+    }
+}
+
+/// Format a number of bytes for people (e.g., `12.3 KiB`).
+#[expect(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
 }
 
 impl Build {
-    pub fn new(strict: bool) -> Self {
-        Self { strict }
+    pub fn new(mode: Mode, format: Format) -> Self {
+        Self {
+            mode,
+            format,
+            ..Default::default()
+        }
+    }
+
+    /// Build the themes in `selection` rather than the default ones.
+    #[must_use]
+    pub fn selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Build every target theme, without printing the summary.
+    ///
+    /// Cursors that fail to build are recorded in the report rather than returned as an
+    /// error, so the caller can report them before giving up.
+    pub fn build(&self, ctx: &mut Context) -> anyhow::Result<Report> {
+        let targets = self.targets(ctx)?;
+        let cache = FrameCache::new(self.package(ctx)?.build().cache());
+        let terminal = Terminal::new(ctx.level);
+
+        let mut report = Report::default();
+
+        for (config, build) in &targets {
+            // Fail before extracting anything if the Xcursors can't be created.
+            let xcursorgen = match self.target {
+                Target::Xcursor => Some(xcursor::locate_xcursorgen(config.xcursorgen())?),
+                Target::Hyprcursor => None,
+            };
+            let options = Options {
+                mode: self.mode,
+                target: self.target,
+                export_svg: self.export_svg,
+                force: self.force,
+                overwrite: self.overwrite,
+                partial: !self.only.is_empty() || !self.skip.is_empty(),
+                fail_fast: self.fail_fast || (!self.keep_going && config.fail_fast()),
+                jobs: self.jobs.unwrap_or_else(pool::default_jobs),
+                xcursorgen: xcursorgen.as_deref(),
+                cache: &cache,
+                observer: &terminal,
+            };
+
+            let theme = build_theme(config, build, options, ctx.level)
+                .with_context(|| format!("failed to build theme: {}", config.theme()))?;
+            let error = theme.error();
+
+            if self.format == Format::Text && ctx.level >= VerbosityLevel::Default {
+                theme.print_table()?;
+                theme.print_warnings()?;
+            }
+
+            report.themes.push(theme);
+
+            if let Some(err) = error {
+                if self.format == Format::Text {
+                    return Err(err)
+                        .with_context(|| format!("failed to build theme: {}", config.theme()));
+                }
+
+                // Report every theme that was attempted, then stop.
+                break;
+            }
+
+            if self.format == Format::Text {
+                let mut stderr = io::stderr();
+                writeln!(
+                    stderr,
+                    "{}",
+                    format!("Successfully built theme: {}", config.theme())
+                        .bold()
+                        .green()
+                )?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// The package to build, in the directory given with `--out-dir` (if any).
+    fn package(&self, ctx: &mut Context) -> anyhow::Result<Package> {
+        let (package, _) = ctx.load()?;
+
+        Ok(match self.out_dir {
+            Some(ref dir) => package.clone().with_build_dir(dir),
+            None => package.clone(),
+        })
+    }
+
+    /// The themes to build, along with the directory to build each of them in.
+    fn targets(&self, ctx: &mut Context) -> anyhow::Result<Vec<(Config, BuildDir)>> {
+        let package = &self.package(ctx)?;
+        let (_, config) = ctx.load()?;
+
+        let themes = match self.selection.theme {
+            Some(ref name) => vec![name.as_str()],
+            None => iter::once(config.theme()).chain(config.themes()).collect(),
+        };
+
+        let mut targets = Vec::new();
+
+        for name in themes {
+            // The first theme is built in the build directory itself, like a package with a
+            // single theme.
+            let theme = &config.with_theme(name)?;
+            let package = &if name == config.theme() {
+                package.clone()
+            } else {
+                package.clone().with_theme(name)
+            };
+
+            if let Some(ref profile) = self.selection.profile {
+                targets.push((theme.with_profile(profile)?, package.profile_build(profile)));
+                continue;
+            }
+
+            targets.push((theme.clone(), package.build().clone()));
+
+            for variant in theme.variants() {
+                targets.push((theme.with_variant(variant)?, package.variant_build(variant)));
+            }
+
+            if self.selection.all_profiles {
+                for profile in theme.profiles() {
+                    targets.push((theme.with_profile(profile)?, package.profile_build(profile)));
+                }
+            }
+        }
+
+        // Collisions are resolved before leaving cursors out, so building only some cursors
+        // doesn't hand their aliases to them. Variants and profiles share the same cursors, so
+        // each collision is only reported once.
+        let mut reported = HashSet::new();
+        for (config, _) in &mut targets {
+            for collision in config.claim_aliases() {
+                if reported.insert(collision.clone()) {
+                    warn!("{collision}");
+                }
+            }
+        }
+
+        self.filter_cursors(&mut targets)?;
+        Ok(targets)
+    }
+
+    /// Leave out the cursors not selected with `--only`, or excluded with `--skip`.
+    fn filter_cursors(&self, targets: &mut [(Config, BuildDir)]) -> anyhow::Result<()> {
+        if self.only.is_empty() && self.skip.is_empty() {
+            return Ok(());
+        }
+
+        for name in self.only.iter().chain(&self.skip) {
+            let found = targets
+                .iter()
+                .any(|(config, _)| config.cursors().iter().any(|cursor| cursor.name() == name));
+
+            if !found {
+                bail!("cursor not found (or disabled with `enabled = false`): {name}");
+            }
+        }
+
+        for (config, _) in targets {
+            *config = config.with_cursors(|cursor| {
+                let listed = |names: &[String]| names.iter().any(|name| name == cursor.name());
+                (self.only.is_empty() || listed(&self.only)) && !listed(&self.skip)
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Build, then rebuild whenever one of the files the build depends on changes.
+    ///
+    /// Failed builds are logged rather than returned, so a mistake (e.g., a typo in
+    /// `Cursor.toml`) can be fixed without restarting.
+    fn watch(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        loop {
+            match self.build(ctx) {
+                Ok(report) if report.failures() > 0 => {
+                    error!("failed to create ({}) cursors", report.failures());
+                }
+                Ok(_) => {}
+                Err(err) => error!("{err:#}"),
+            }
+
+            let paths = self.watched_paths(ctx)?;
+            let mut stderr = io::stderr();
+            writeln!(
+                stderr,
+                "{}",
+                "Watching for changes (press Ctrl+C to stop)...".cyan()
+            )?;
+
+            let changed = watch::wait_for_change(&paths);
+            writeln!(stderr, "Changed: {}", changed.display())?;
+            ctx.reload();
+        }
+    }
+
+    /// The files a rebuild depends on: `Cursor.toml` (and the files it includes), the input of
+    /// every cursor and the extracted frames (which can be edited by hand).
+    fn watched_paths(&self, ctx: &mut Context) -> anyhow::Result<Vec<PathBuf>> {
+        let mut paths = vec![ctx.load_package()?.config()];
+
+        // Without a valid configuration, only a fix to it can make the build succeed.
+        let Ok(targets) = self.targets(ctx) else {
+            return Ok(paths);
+        };
+
+        for (config, build) in targets {
+            for include in config.includes() {
+                paths.push(path::absolute(include).context("failed to resolve include path")?);
+            }
+
+            for cursor in config.cursors() {
+                let input = cursor.archive().unwrap_or(cursor.input());
+                paths.push(path::absolute(input).context("failed to resolve cursor input path")?);
+            }
+
+            paths.push(build.frames());
+        }
+
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
     }
 }
 
 impl Run for Build {
     fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
-        let package = if let Some(ref package) = ctx.package {
-            package
-        } else {
-            let current_dir = env::current_dir().context("failed to get current directory")?;
-            ctx.package = Some(Package::new(current_dir));
-            ctx.package.as_ref().unwrap()
-        };
+        if self.watch {
+            return self.watch(ctx);
+        }
 
-        let config = if let Some(ref config) = ctx.config {
-            config
-        } else {
-            let path = package.config();
-            ctx.config = Some(Config::from_file(&path)?);
-            ctx.config.as_ref().unwrap()
+        let report = match self.build(ctx) {
+            Ok(report) => report,
+            Err(err) if self.format == Format::Json => {
+                exit::print_json(&err)?;
+                return Err(err);
+            }
+            Err(err) => return Err(err),
         };
 
-        setup_build_directory(package.build(), config.theme())?;
+        if self.format == Format::Json {
+            report.print()?;
+        }
 
-        let handles = config
-            .cursors()
-            .to_owned()
-            .into_iter()
-            .map(|cursor| {
-                // Attach context so we know which thread is emitting the events.
-                let span = error_span!("", cursor = ?cursor.name());
+        report.error().map_or(Ok(()), Err)
+    }
+}
 
-                let build = package.build().clone();
-                let name = cursor.name().to_owned();
-                let strict = self.strict;
+/// Build the theme of `config` with [`ThemeBuilder`], which stages it next to the last theme and
+/// only replaces it once every cursor is built.
+///
+/// This way, a failed build never leaves a half-written theme behind (which `install` would
+/// pick up anyway), and the last good theme stays in place.
+fn build_theme(
+    config: &Config,
+    build: &BuildDir,
+    options: Options,
+    level: VerbosityLevel,
+) -> anyhow::Result<ThemeReport> {
+    let start = Instant::now();
+    staging::remove_stale(build.as_path()).context("failed to remove stale staging directories")?;
+    setup_build_directory(build)?;
+    warn_about_names(config, options.partial);
 
-                let handle = thread::spawn(move || {
-                    span.in_scope(move || process_cursor(&cursor, &build, strict))
-                });
+    let previous = if options.force {
+        Fingerprints::default()
+    } else {
+        Fingerprints::load(&build.fingerprints())?
+    };
 
-                (name, handle)
-            })
-            .collect::<Vec<_>>();
+    // Frame hashes are kept even when forcing a rebuild, so edited frames are still detected.
+    let recorded_frames = Fingerprints::load(&build.frame_hashes())?;
+    let (mut fingerprints, mut frame_hashes) =
+        skipped_records(config, build, options, &recorded_frames)?;
 
-        let mut error_count = 0;
-        for (name, handle) in handles {
-            match handle.join() {
-                Ok(result) => {
-                    if let Err(err) = result {
-                        let mut error_message = err.to_string();
+    let hooks = Hooks {
+        config,
+        build,
+        options,
+        cursors: config
+            .cursors()
+            .iter()
+            .map(|cursor| (cursor.name(), cursor))
+            .collect(),
+        previous,
+        recorded_frames,
+        // Cursors are processed concurrently; any threads left over are shared between the
+        // frames of each cursor, which helps when there are only a few (large) cursors.
+        frame_jobs: NonZeroUsize::new(options.jobs.get() / config.cursors().len().max(1))
+            .unwrap_or(NonZeroUsize::MIN),
+        processed: Mutex::default(),
+    };
+    let observer = Recorder::new(options.observer);
 
-                        if ctx.level >= VerbosityLevel::Verbose {
-                            error_message.push('\n');
+    let results = theme_builder(config, build, options)?
+        .build_with_hooks(build.theme().as_path(), &hooks, &observer)
+        .map_err(step_error)?;
 
-                            for cause in err.chain() {
-                                _ = writeln!(error_message, "  Cause: {cause}");
-                            }
-                        }
+    let mut processed = hooks
+        .processed
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner);
+    let mut recorded = observer.into_cursors();
+    let mut reports = Vec::with_capacity(results.len());
 
-                        error!("failed to process cursor: {name}: {error_message}");
-                        error_count += 1;
-                    }
-                }
-                Err(err) => {
-                    // The thread most likely panicked.
-                    error!("failed to join on the associated thread: {err:#?}");
-                    error_count += 1;
+    for (cursor, result) in config.cursors().iter().zip(results) {
+        let name = cursor.name();
+        let processed = processed.remove(name).unwrap_or_default();
+        let recorded = recorded.remove(name).unwrap_or_default();
+        let mut report = CursorReport {
+            name: name.to_owned(),
+            status: Status::Failed,
+            output: None,
+            output_bytes: None,
+            frames: processed.frame_count,
+            duration_ms: recorded.duration.as_millis(),
+            warnings: recorded.warnings,
+            error: None,
+            failure: None,
+        };
+
+        match result {
+            CursorResult::Built | CursorResult::UpToDate => {
+                for (key, hash) in processed.frames {
+                    frame_hashes.insert(key, hash);
                 }
+
+                fingerprints.insert(name.to_owned(), processed.fingerprint);
+
+                report.status = if matches!(result, CursorResult::UpToDate) {
+                    Status::UpToDate
+                } else {
+                    Status::Built
+                };
+                report.output = Some(output_path(build.theme(), cursor, options.target));
+                report.output_bytes = processed.output_bytes;
             }
+            CursorResult::Failed(err) => report.fail(&step_error(err), level),
+            // Left out with `--fail-fast`, after another cursor failed.
+            CursorResult::Canceled => report.status = Status::Canceled,
         }
 
-        if error_count > 0 {
-            Err(anyhow!("failed to create ({error_count}) cursors"))
-        } else {
-            let mut stderr = io::stderr();
-            writeln!(stderr, "{}", "Successfully built theme!".bold().green())?;
+        reports.push(report);
+    }
+
+    // The frames are replaced in place, unlike the theme, so their hashes are saved either way.
+    frame_hashes.save(&build.frame_hashes())?;
+
+    let report = ThemeReport {
+        theme: config.theme().to_owned(),
+        output: build.theme().as_path().to_owned(),
+        installed: None,
+        duration_ms: start.elapsed().as_millis(),
+        cursors: reports,
+    };
+
+    if report.error().is_some() {
+        warn!(
+            "not every cursor was built; kept the last theme: {:#}",
+            build.theme().as_path().display()
+        );
+    } else {
+        // Only now do the fingerprints describe the theme in place; until then, the last ones
+        // still do, so the cursors of a failed build are rebuilt by the next one.
+        fingerprints
+            .save(&build.fingerprints())
+            .context("failed to save fingerprints")?;
+    }
+
+    Ok(report)
+}
+
+/// Describe the theme of `config` to [`ThemeBuilder`]. The cursors are read (and their frames
+/// extracted) by [`Hooks`], which also adds the settings the builder doesn't know about.
+fn theme_builder(
+    config: &Config,
+    build: &BuildDir,
+    options: Options,
+) -> anyhow::Result<ThemeBuilder> {
+    let animation = config.animation();
+    let mut builder = ThemeBuilder::new(config.theme())
+        .index_theme(index_theme(config))
+        .mode(options.mode.into())
+        .speed(animation.speed())
+        .min_frame_duration(animation.min_frame_duration())
+        .jobs(options.jobs)
+        .fail_fast(options.fail_fast);
+
+    if let Some(xcursorgen) = options.xcursorgen {
+        builder = builder.xcursorgen(xcursorgen);
+    }
 
-            Ok(())
+    for cursor in config.cursors() {
+        let mut frames_dir = build.frames();
+        if config.frames().layout() == Layout::Nested {
+            frames_dir.push(file_stem(cursor)?);
         }
+
+        let theme_cursor = xcursor::Cursor::new(cursor.name(), Vec::new())
+            .speed(cursor.speed())
+            .frames_dir(frames_dir);
+
+        builder = builder.cursor(
+            cursor
+                .aliases()
+                .iter()
+                .fold(theme_cursor, xcursor::Cursor::alias),
+        );
     }
+
+    Ok(builder)
 }
 
-fn setup_build_directory(build: &BuildDir, theme_name: &str) -> anyhow::Result<()> {
+/// The fingerprints and frame hashes to keep from the last build: those of the cursors left out
+/// of a partial build, so the next full build doesn't rebuild them (or replace their frames)
+/// needlessly.
+fn skipped_records(
+    config: &Config,
+    build: &BuildDir,
+    options: Options,
+    recorded_frames: &Fingerprints,
+) -> anyhow::Result<(Fingerprints, Fingerprints)> {
+    let mut fingerprints = Fingerprints::default();
+    let mut frame_hashes = Fingerprints::default();
+
+    if !options.partial {
+        return Ok((fingerprints, frame_hashes));
+    }
+
+    let skipped = |name: &str| !config.cursors().iter().any(|cursor| cursor.name() == name);
+
+    for (name, hash) in Fingerprints::load(&build.fingerprints())?.iter() {
+        if skipped(name) {
+            fingerprints.insert(name.to_owned(), hash);
+        }
+    }
+
+    for (key, hash) in recorded_frames.iter() {
+        if key.split_once('/').is_some_and(|(name, _)| skipped(name)) {
+            frame_hashes.insert(key.to_owned(), hash);
+        }
+    }
+
+    Ok((fingerprints, frame_hashes))
+}
+
+/// Warn about cursor names X applications don't look up, and about commonly used cursors the
+/// theme doesn't provide (see `ani-to-xcursor check`), unless only some cursors are built.
+fn warn_about_names(config: &Config, partial: bool) {
+    let names = config
+        .cursors()
+        .iter()
+        .flat_map(|cursor| {
+            iter::once(cursor.name()).chain(cursor.aliases().iter().map(String::as_str))
+        })
+        .collect::<Vec<_>>();
+
+    for name in &names {
+        if names::canonical(name).is_none() {
+            warn!("unknown X cursor name: {name}");
+        }
+    }
+
+    if partial {
+        return;
+    }
+
+    for name in names::missing_critical(names) {
+        warn!("theme has no {name} cursor");
+    }
+}
+
+fn setup_build_directory(build: &BuildDir) -> anyhow::Result<()> {
     fs::create_dir_all(build.as_path()).context("failed to create build directory")?;
     info!("created directory: {:#}", build.as_path().display());
 
@@ -114,213 +911,788 @@ fn setup_build_directory(build: &BuildDir, theme_name: &str) -> anyhow::Result<(
     fs::create_dir_all(&frames).context("failed to create frames directory")?;
     info!("created directory: {:#}", frames.display());
 
-    let theme = build.theme();
-    fs::create_dir_all(theme.as_path()).context("failed to create theme directory")?;
-    info!("created directory: {:#}", theme.as_path().display());
+    Ok(())
+}
 
-    let cursors = theme.cursors();
-    fs::create_dir_all(&cursors).context("failed to create theme directory")?;
-    info!("created directory: {:#}", cursors.display());
+/// The `index.theme` file, which names the theme and the themes it inherits from.
+fn index_theme(config: &Config) -> IndexTheme {
+    let fields = config.index_theme();
+    let mut index_theme = IndexTheme::new(config.theme());
 
-    let index_theme = theme.index_theme();
-    let contents = format!(
-        "[Icon Theme]\n\
-        Name = {theme_name}\n\
-        Inherits = Adwaita"
-    );
-    fs::write(&index_theme, &contents).context("failed to create index.theme file")?;
-    info!("created file: {:#}", index_theme.display());
+    if let Some(comment) = fields.comment() {
+        index_theme = index_theme.comment(comment);
+    }
 
-    Ok(())
+    if let Some(inherits) = fields.inherits() {
+        index_theme = index_theme.inherits(inherits);
+    }
+
+    if let Some(example) = fields.example() {
+        index_theme = index_theme.example(example);
+    }
+
+    index_theme
 }
 
-fn process_cursor(cursor: &Cursor, build: &BuildDir, strict: bool) -> anyhow::Result<()> {
-    let path = path::absolute(cursor.input()).context("failed to resolve cursor input path")?;
-    let ani = Ani::open(&path, strict).context("failed to decode ANI file")?;
+/// The steps `build` adds to [`ThemeBuilder`]: carrying over the last theme, skipping the
+/// cursors that are up to date, reading archives, the frame cache, transforms and shadows,
+/// resizing, hotspots and trimming, and the hyprcursor and SVG output.
+struct Hooks<'a> {
+    config: &'a Config,
+    build: &'a BuildDir,
+    options: Options<'a>,
 
-    let file_stem = path
-        .file_stem()
-        .and_then(|stem| stem.to_str())
-        .context("expected path to be valid unicode")?;
+    /// The cursors of `config`, by name.
+    cursors: HashMap<&'a str, &'a Cursor>,
 
-    let mut frames_dir = build.frames();
-    frames_dir.push(file_stem);
-    let frames_dir = frames_dir;
-    fs::create_dir_all(&frames_dir).context("failed to create frame output directory")?;
+    /// The fingerprint of each cursor when it was last built (none with `--force`).
+    previous: Fingerprints,
 
-    let frame_names = extract_frames(&ani, &frames_dir)?;
+    /// The hash of each extracted frame when it was last generated, keyed by [`frame_key`].
+    recorded_frames: Fingerprints,
 
-    let cursor_config_path = frames_dir.join(format!("{file_stem}.cursor"));
-    build_xcursor_config(&ani, &frame_names, &cursor_config_path)?;
+    /// The number of threads each cursor writes its frames with.
+    frame_jobs: NonZeroUsize,
 
-    let xcursor_output = frames_dir.join(file_stem);
-    create_xcursor(&frames_dir, &cursor_config_path, &xcursor_output)
-        .context("failed to create Xcursor")?;
+    /// What each cursor was built from, by name.
+    processed: Mutex<HashMap<String, Processed>>,
+}
 
-    link_to_theme(
-        &build.theme().cursors(),
-        cursor.name(),
-        cursor.aliases(),
-        &xcursor_output,
-    )?;
+/// What a cursor was built from, for the next build to tell whether it is up to date.
+#[derive(Debug, Default)]
+struct Processed {
+    /// The fingerprint of the inputs the cursor was built from.
+    fingerprint: u64,
 
-    Ok(())
+    /// The hash of each extracted frame at the time it was generated, keyed by [`frame_key`].
+    frames: Vec<(String, u64)>,
+
+    /// The hashes the last build recorded for the frames of the cursor, by file name.
+    recorded: Vec<(String, u64)>,
+
+    /// The frames that must not be replaced (see [`frames_to_keep`]).
+    keep: HashSet<String>,
+
+    /// The number of frames of the animation (unknown if it couldn't be decoded).
+    frame_count: Option<usize>,
+
+    /// The size of the output file, in bytes.
+    output_bytes: Option<u64>,
 }
 
-fn extract_frames(ani: &Ani, output_dir: &Path) -> anyhow::Result<Vec<Vec<String>>> {
-    let mut names = Vec::with_capacity(ani.frames().len());
+impl BuildHooks for Hooks<'_> {
+    fn stage(&self, staged: &Path, _dir: &Path) -> Result<(), ThemeError> {
+        step(self.stage_theme(&Theme::new(staged.to_owned())))
+    }
 
-    // TODO: (See also todo in `build_xcursor_config`):
-    // Maybe sort PNGs by size to make it easier to bulk delete undesired cursors?
+    fn read<'a>(&self, cursor: &CursorBuild<'a>) -> Result<Cow<'a, [u8]>, ThemeError> {
+        step(read_input(self.cursors[cursor.name()]).map(Cow::Owned))
+    }
 
-    for (i, frame) in ani.frames().iter().enumerate() {
-        let mut size_names = Vec::with_capacity(frame.len());
-        for image in frame {
-            let width = image.width();
-            let name = format!("{i:0>2}-{width}.png");
-            let path = output_dir.join(&name);
+    fn up_to_date(&self, cursor: &CursorBuild<'_>, data: &[u8]) -> Result<bool, ThemeError> {
+        step(self.check_up_to_date(cursor, data))
+    }
+
+    fn extract(
+        &self,
+        cursor: &CursorBuild<'_>,
+        data: &[u8],
+        ani: &mut Ani,
+    ) -> Result<Vec<Vec<Image>>, ThemeError> {
+        step(self.extract_frames(cursor, data, ani))
+    }
+
+    fn write(
+        &self,
+        cursor: &CursorBuild<'_>,
+        frames: &[Vec<Image>],
+        steps: &[Step],
+    ) -> Result<(), ThemeError> {
+        step(self.write_outputs(cursor, frames, steps))
+    }
+}
+
+impl Hooks<'_> {
+    /// Update what is recorded about the cursor `name`.
+    fn processed<R>(&self, name: &str, f: impl FnOnce(&mut Processed) -> R) -> R {
+        let mut processed = self
+            .processed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        f(processed.entry(name.to_owned()).or_default())
+    }
+
+    /// Carry over what is still up to date from the last theme (see [`staging::stage`]), and
+    /// add the hyprcursor manifest.
+    fn stage_theme(&self, staged: &Theme) -> anyhow::Result<()> {
+        let names = self.config.cursors().iter().map(Cursor::name).collect();
+        staging::stage(self.build.theme(), staged, &names, self.options.partial)
+            .context("failed to stage theme")?;
 
-            let file = File::create(&path)?;
+        if self.options.target == Target::Hyprcursor {
+            let shapes = staged.hyprcursors();
+            fs::create_dir_all(&shapes).context("failed to create hyprcursors directory")?;
+            info!("created directory: {:#}", shapes.display());
 
-            image.write_png(&file)?;
-            size_names.push(name);
+            let manifest = staged.manifest();
+            fs::write(&manifest, hyprcursor::render_manifest(self.config))
+                .context("failed to create manifest.hl file")?;
+            info!("created file: {:#}", manifest.display());
         }
-        names.push(size_names);
+
+        Ok(())
     }
 
-    Ok(names)
+    /// Whether the fingerprint of the cursor matches the last build, its output (carried over
+    /// to the staged theme) still exists and none of its frames were edited, in which case only
+    /// its links are recreated.
+    fn check_up_to_date(&self, build: &CursorBuild<'_>, data: &[u8]) -> anyhow::Result<bool> {
+        let cursor = self.cursors[build.name()];
+        let animation = &self.config.animation().with_speed(cursor.speed());
+        let fingerprint = fingerprint(
+            cursor,
+            data,
+            self.config.frames(),
+            animation,
+            self.config.transform(cursor),
+            self.config.shadow(cursor),
+            self.options,
+        )?;
+        let recorded = self
+            .recorded_frames
+            .iter()
+            .filter_map(|(key, hash)| {
+                let file_name = key.strip_prefix(cursor.name())?.strip_prefix('/')?;
+                Some((file_name.to_owned(), hash))
+            })
+            .collect::<Vec<_>>();
+
+        // Frames that were edited by hand since they were generated.
+        let modified = recorded
+            .iter()
+            .filter(|(file_name, hash)| {
+                fs::read(build.frames_dir().join(file_name))
+                    .is_ok_and(|data| fingerprint::hash(&data) != *hash)
+            })
+            .map(|(file_name, _)| file_name.clone())
+            .collect::<HashSet<_>>();
+
+        let output = output_path(
+            &Theme::new(build.theme_dir().to_owned()),
+            cursor,
+            self.options.target,
+        );
+        let previous = self.previous.get(cursor.name());
+
+        if previous == Some(fingerprint) && output.is_file() && modified.is_empty() {
+            if self.options.target == Target::Xcursor {
+                build.link(&output)?;
+            }
+
+            let frames = recorded
+                .into_iter()
+                .map(|(file_name, hash)| (frame_key(cursor, &file_name), hash))
+                .collect();
+            let frame_count = decode_quietly(data, self.options.mode)
+                .ok()
+                .map(|ani| ani.frames().len());
+
+            self.processed(cursor.name(), |processed| {
+                *processed = Processed {
+                    fingerprint,
+                    frames,
+                    frame_count,
+                    output_bytes: fs::metadata(&output).ok().map(|m| m.len()),
+                    ..Processed::default()
+                };
+            });
+
+            return Ok(true);
+        }
+
+        let keep = frames_to_keep(modified, self.options.overwrite, build);
+        self.processed(cursor.name(), |processed| {
+            *processed = Processed {
+                fingerprint,
+                recorded,
+                keep,
+                ..Processed::default()
+            };
+        });
+
+        Ok(false)
+    }
+
+    /// Extract the frames of the cursor (or restore them from the frame cache), then apply the
+    /// configured hotspots and trim them.
+    fn extract_frames(
+        &self,
+        build: &CursorBuild<'_>,
+        data: &[u8],
+        ani: &mut Ani,
+    ) -> anyhow::Result<Vec<Vec<Image>>> {
+        let cursor = self.cursors[build.name()];
+        let keep = self.processed(cursor.name(), |processed| processed.keep.clone());
+        let mut extracted = extract_cached(ani, data, cursor, self.config, build, &keep, self)?;
+
+        apply_hotspots(cursor, &mut extracted, build);
+
+        // Configured hotspots refer to the untrimmed images, so they're moved along with the rest.
+        if cursor.trim() {
+            trim::trim(
+                build.frames_dir(),
+                &mut extracted,
+                self.config.frames().compression(),
+            )
+            .context("failed to trim frames")?;
+        }
+
+        let frame_count = ani.frames().len();
+        self.processed(cursor.name(), |processed| {
+            processed.frame_count = Some(frame_count);
+        });
+
+        Ok(extracted)
+    }
+
+    /// Assemble the extracted frames of the cursor in the format of the build target (and
+    /// export them as SVG files, if requested).
+    fn write_outputs(
+        &self,
+        build: &CursorBuild<'_>,
+        extracted: &[Vec<Image>],
+        steps: &[Step],
+    ) -> anyhow::Result<()> {
+        let cursor = self.cursors[build.name()];
+        let frames = self.config.frames();
+        let output = output_path(
+            &Theme::new(build.theme_dir().to_owned()),
+            cursor,
+            self.options.target,
+        );
+
+        // The output may be a hard link to the one in the last theme (see `staging::stage`),
+        // which must be replaced rather than written to.
+        match fs::remove_file(&output) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err).context("failed to remove the last output"),
+        }
+
+        match self.options.target {
+            Target::Xcursor => {
+                build
+                    .write_xcursor(extracted, steps)
+                    .context("failed to create Xcursor")?;
+            }
+            Target::Hyprcursor => {
+                let shape_dir = self.build.hyprcursor_shapes().join(cursor.name());
+                hyprcursor::write_shape(
+                    cursor,
+                    build.frames_dir(),
+                    extracted,
+                    steps,
+                    frames.filter(),
+                    &shape_dir,
+                    &output,
+                )
+                .context("failed to create hyprcursor shape")?;
+            }
+        }
+
+        if self.options.export_svg {
+            svg::export(
+                cursor.name(),
+                build.frames_dir(),
+                extracted,
+                steps,
+                frames.filter(),
+                &self.build.svg(),
+            )
+            .context("failed to export SVG files")?;
+        }
+
+        let (keep, recorded) = self.processed(cursor.name(), |processed| {
+            (
+                mem::take(&mut processed.keep),
+                mem::take(&mut processed.recorded),
+            )
+        });
+        let hashes = hash_frames(cursor, build.frames_dir(), extracted, &keep, &recorded)?;
+
+        self.processed(cursor.name(), |processed| {
+            processed.frames = hashes;
+            processed.output_bytes = fs::metadata(&output).ok().map(|m| m.len());
+        });
+
+        Ok(())
+    }
 }
 
-#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-fn build_xcursor_config(
-    ani: &Ani,
-    frame_names: &[Vec<String>],
-    output: &Path,
-) -> anyhow::Result<()> {
-    let sequence = ani.sequence().map_or_else(
-        || {
-            info!("ANI sequence missing, using default");
-            (0..ani.header().steps())
-                .map(|i| i % ani.header().frames())
-                .collect()
-        },
-        ToOwned::to_owned,
-    );
-    let rates = ani.rates().map_or_else(
-        || {
-            info!("ANI frame rates missing, using default");
-            iter::repeat_n(ani.header().jif_rate(), ani.frames().len()).collect()
+/// Passes the progress of a build on to another observer, keeping the warnings and the duration
+/// of each cursor for the build report.
+struct Recorder<'a> {
+    observer: &'a dyn ProgressObserver,
+    cursors: Mutex<HashMap<String, Recorded>>,
+}
+
+#[derive(Debug, Default)]
+struct Recorded {
+    start: Option<Instant>,
+    duration: Duration,
+    warnings: Vec<String>,
+}
+
+impl<'a> Recorder<'a> {
+    fn new(observer: &'a dyn ProgressObserver) -> Self {
+        Self {
+            observer,
+            cursors: Mutex::default(),
+        }
+    }
+
+    fn record(&self, name: &str, f: impl FnOnce(&mut Recorded)) {
+        let mut cursors = self.cursors.lock().unwrap_or_else(PoisonError::into_inner);
+        f(cursors.entry(name.to_owned()).or_default());
+    }
+
+    fn into_cursors(self) -> HashMap<String, Recorded> {
+        self.cursors
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl ProgressObserver for Recorder<'_> {
+    fn theme_started(&self, theme: &str, total: usize) {
+        self.observer.theme_started(theme, total);
+    }
+
+    fn cursor_started(&self, name: &str) {
+        self.record(name, |recorded| recorded.start = Some(Instant::now()));
+        self.observer.cursor_started(name);
+    }
+
+    fn frame_extracted(&self, name: &str, written: usize, total: usize) {
+        self.observer.frame_extracted(name, written, total);
+    }
+
+    fn warning(&self, name: &str, message: &str) {
+        self.record(name, |recorded| recorded.warnings.push(message.to_owned()));
+        self.observer.warning(name, message);
+    }
+
+    fn cursor_finished(&self, name: &str, error: Option<&(dyn std::error::Error + 'static)>) {
+        self.record(name, |recorded| {
+            if let Some(start) = recorded.start {
+                recorded.duration = start.elapsed();
+            }
+        });
+        self.observer.cursor_finished(name, error);
+    }
+
+    fn theme_finished(&self) {
+        self.observer.theme_finished();
+    }
+}
+
+/// An error of one of the [`Hooks`], passed through [`ThemeBuilder`] as a [`ThemeError::Hook`]
+/// so it can be taken back whole (see [`step_error`]).
+#[derive(Debug)]
+struct StepError(anyhow::Error);
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for StepError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+fn step<T>(result: anyhow::Result<T>) -> Result<T, ThemeError> {
+    result.map_err(|err| ThemeError::hook(StepError(err)))
+}
+
+/// The error a cursor (or the theme) failed with, as returned by [`Hooks`] if it came from
+/// there.
+fn step_error(err: ThemeError) -> anyhow::Error {
+    match err {
+        ThemeError::Hook { source } => match source.downcast::<StepError>() {
+            Ok(err) => err.0,
+            Err(source) => ThemeError::Hook { source }.into(),
         },
-        ToOwned::to_owned,
-    );
-
-    // TODO: Calculate the required capacity and pre-allocate.
-    let mut contents = String::new();
-
-    // TODO: Sort the entries by size.
-    // Right now, the `.cursor` file looks like:
-    //
-    // 00-32.png
-    // 00-48.png
-    // 00-64.png
-    // 01-32.png
-    // 01-48.png
-    // 01-64.png
-    //
-    // Instead, I want:
-    //
-    // 00-32.png
-    // 01-32.png
-    //
-    // 00-48.png
-    // 01-48.png
-    //
-    // 00-64.png
-    // 01-64.png
-    //
-    // This way, you can delete a whole group of cursors very easily if you wanted to.
-    //
-    // (Maybe also group them when saving them as well...)
-
-    for i in sequence {
-        let i = usize::try_from(i).context("invalid sequence index")?;
-        let frame = &ani.frames()[i];
-
-        for (j, entry) in frame.iter().enumerate() {
-            let size = entry.width();
-            let (x, y) = entry.cursor_hotspot().unwrap_or((0, 0));
-            let file_name = &frame_names[i][j];
-            let duration = rates[i] * (JIFFY.round() as u32);
-
-            writeln!(contents, "{size} {x} {y} {file_name} {duration}",)?;
-        }
-    }
-
-    fs::write(output, contents).context("failed to create Xcursor configuration file")?;
-    Ok(())
+        err => err.into(),
+    }
 }
 
-fn create_xcursor(frames_dir: &Path, config: &Path, output: &Path) -> anyhow::Result<()> {
-    let status = Command::new("xcursorgen")
-        .args([config.display().to_string(), output.display().to_string()])
-        .current_dir(frames_dir)
-        .status()
-        .context("failed to execute xcursorgen")?;
+/// The file `cursor` is built into, in the theme `theme`.
+fn output_path(theme: &Theme, cursor: &Cursor, target: Target) -> PathBuf {
+    match target {
+        Target::Xcursor => theme.cursors().join(cursor.name()),
+        Target::Hyprcursor => theme.hyprcursors().join(format!("{}.hlc", cursor.name())),
+    }
+}
+
+/// The file stem of the input of `cursor`, which its frames are named after.
+fn file_stem(cursor: &Cursor) -> anyhow::Result<&str> {
+    cursor
+        .input()
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .context("expected path to be valid unicode")
+}
 
-    match status.code() {
-        Some(0) => {
-            info!("created Xcursor: {:#}", output.display());
-            Ok(())
+/// Extract the frames of `cursor`, or copy them from the frame cache if the same input was
+/// extracted before with the same settings (see [`FrameCache`]).
+fn extract_cached(
+    ani: &mut Ani,
+    data: &[u8],
+    cursor: &Cursor,
+    config: &Config,
+    build: &CursorBuild<'_>,
+    keep: &HashSet<String>,
+    hooks: &Hooks<'_>,
+) -> anyhow::Result<Vec<Vec<Image>>> {
+    let frames = config.frames();
+    let transform = config.transform(cursor);
+    let shadow = config.shadow(cursor);
+    let file_stem = file_stem(cursor)?;
+    let file_name = |index, size| frames.file_name(cursor.name(), file_stem, index, size);
+    let cache = hooks.options.cache;
+    let key = FrameCache::key(data, cursor, frames, transform, shadow, hooks.options.mode)?;
+
+    if let Some(extracted) = cache.restore(key, build.frames_dir(), file_name, keep) {
+        return Ok(extracted);
+    }
+
+    adjust_frames(ani, cursor, transform, shadow);
+    let extracted = extract_frames(
+        ani,
+        build.frames_dir(),
+        frames,
+        file_name,
+        keep,
+        hooks.frame_jobs,
+        |written, total| build.frame_extracted(written, total),
+    )?;
+
+    // Frames edited by hand aren't what the input produces, so they aren't cached.
+    if keep.is_empty() {
+        cache.store(key, build.frames_dir(), &extracted);
+    }
+
+    Ok(extracted)
+}
+
+/// Replace the hotspots of the extracted images with the ones configured for `cursor`.
+///
+/// Hotspots outside of an image (e.g., `hotspot_x` for a size of the input smaller than the
+/// ones it was meant for) are moved to its edge, with a warning.
+fn apply_hotspots(cursor: &Cursor, extracted: &mut [Vec<Image>], build: &CursorBuild<'_>) {
+    let mut clamped = BTreeSet::new();
+
+    for image in extracted.iter_mut().flatten() {
+        let size = image.size();
+
+        if let Some(configured) = cursor.hotspot(size) {
+            let max = u16::try_from(size.saturating_sub(1)).unwrap_or(u16::MAX);
+            let hotspot = Hotspot::new(configured.x().min(max), configured.y().min(max));
+
+            if hotspot != configured {
+                clamped.insert(size);
+            }
+
+            // Configured hotspots refer to the cursor before it was mirrored.
+            let hotspot = if cursor.mirror() {
+                transform::mirror_hotspot(hotspot, size)
+            } else {
+                hotspot
+            };
+            image.set_hotspot((hotspot.x(), hotspot.y()));
         }
-        Some(code) => Err(anyhow!("process failed with exit code: {code}")),
-        None => Err(anyhow!("process terminated due to signal")),
+    }
+
+    for size in clamped {
+        build.warning(&format!(
+            "hotspot is outside the {size}x{size} images; moved it to their edge"
+        ));
     }
 }
 
-fn link_to_theme(
-    theme_cursors_dir: &Path,
-    cursor_name: &str,
-    aliases: &[String],
-    target: &Path,
-) -> anyhow::Result<()> {
-    let target_link = theme_cursors_dir.join(cursor_name);
-    symlink(target, &target_link)?;
+/// Read the input file of `cursor`, from its archive if it has one.
+pub fn read_input(cursor: &Cursor) -> anyhow::Result<Vec<u8>> {
+    if let Some(archive) = cursor.archive() {
+        archive::read_entry(archive, cursor.input())
+    } else {
+        let path = path::absolute(cursor.input()).context("failed to resolve cursor input path")?;
+        fs::read(&path).with_context(|| format!("failed to read file: {}", path.display()))
+    }
+}
 
-    for alias in aliases {
-        let alias_link = theme_cursors_dir.join(alias);
+/// The frames that must not be replaced: the ones that were edited by hand, unless `overwrite`
+/// is set.
+fn frames_to_keep(
+    modified: HashSet<String>,
+    overwrite: bool,
+    build: &CursorBuild<'_>,
+) -> HashSet<String> {
+    if overwrite {
+        return HashSet::new();
+    }
 
-        if alias_link.exists() {
+    for file_name in &modified {
+        build.warning(&format!(
+            "keeping frame that was modified since it was generated: {file_name} \
+                (use --overwrite to replace it)"
+        ));
+    }
+
+    modified
+}
+
+/// Hash the extracted frames, so edits made to them can be detected by the next build.
+///
+/// Kept frames retain the hash of the generated frame, so they are still recognized as
+/// modified (and not overwritten) by the next build.
+fn hash_frames(
+    cursor: &Cursor,
+    frames_dir: &Path,
+    extracted: &[Vec<Image>],
+    keep: &HashSet<String>,
+    recorded: &[(String, u64)],
+) -> anyhow::Result<Vec<(String, u64)>> {
+    let mut hashes = Vec::new();
+    let mut seen = HashSet::new();
+
+    for image in extracted.iter().flatten() {
+        let file_name = image.file_name();
+
+        // Deduplicated images are shared between frames.
+        if !seen.insert(file_name) {
             continue;
         }
 
-        symlink(&target_link, &alias_link)?;
-        info!("created alias: {alias}");
+        let hash = if keep.contains(file_name) {
+            recorded
+                .iter()
+                .find(|(name, _)| name == file_name)
+                .map(|&(_, hash)| hash)
+        } else {
+            let data = fs::read(frames_dir.join(file_name)).context("failed to read frame")?;
+            Some(fingerprint::hash(&data))
+        };
+
+        if let Some(hash) = hash {
+            hashes.push((frame_key(cursor, file_name), hash));
+        }
+    }
+
+    Ok(hashes)
+}
+
+/// Identify a frame by the cursor it belongs to, since frames of different cursors may share a
+/// directory (and file names) depending on the layout.
+fn frame_key(cursor: &Cursor, file_name: &str) -> String {
+    format!("{}/{file_name}", cursor.name())
+}
+
+/// Hash everything that affects the output of a cursor: its input data, its configuration,
+/// the build settings and the version of this tool.
+fn fingerprint(
+    cursor: &Cursor,
+    data: &[u8],
+    frames: &Frames,
+    animation: &Animation,
+    transform: &Transform,
+    shadow: Option<&Shadow>,
+    options: Options,
+) -> anyhow::Result<u64> {
+    let mut hasher = Hasher::default();
+    hasher.field(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.field(data);
+    hasher.field(toml::to_string(cursor)?.as_bytes());
+    hasher.field(toml::to_string(frames)?.as_bytes());
+    hasher.field(toml::to_string(animation)?.as_bytes());
+    hasher.field(toml::to_string(transform)?.as_bytes());
+    hasher.field(format!("{shadow:?}").as_bytes());
+    hasher.field(format!("{:?}", options.mode).as_bytes());
+    hasher.field(format!("{:?}", options.target).as_bytes());
+    hasher.field(&[u8::from(options.export_svg)]);
+
+    Ok(hasher.finish())
+}
+
+/// Recolor, mirror and shadow the decoded frames of `cursor`, as configured.
+fn adjust_frames(ani: &mut Ani, cursor: &Cursor, transform: &Transform, shadow: Option<&Shadow>) {
+    if !transform.is_identity() {
+        transform::apply(ani, transform);
+    }
+    if cursor.mirror() {
+        transform::mirror(ani);
+    }
+    if let Some(shadow) = shadow {
+        transform::shadow(ani, shadow);
     }
+}
 
-    Ok(())
+/// Decode an animated (`.ani`) or static (`.cur`, `.ico`, `.png`) cursor.
+///
+/// Static cursors are treated as an animation with a single frame, so both are built the same
+/// way. The format is detected from the data rather than the file extension.
+pub fn decode(data: &[u8], mode: Mode) -> anyhow::Result<Ani> {
+    let ani = decode_quietly(data, mode)?;
+
+    for warning in ani.warnings() {
+        warn!("{warning}");
+    }
+
+    for issue in ani.validate() {
+        warn!("{issue}");
+    }
+
+    Ok(ani)
 }
 
-pub fn symlink(source: &Path, target: &Path) -> anyhow::Result<()> {
-    match fs::remove_file(target) {
-        Ok(()) => {}
-        Err(err) => match err.kind() {
-            ErrorKind::NotFound => {}
-            _ => return Err(err).context("failed to remove existing file")?,
-        },
+/// Like [`decode`], but leaves reporting the warnings and issues found in the data to the caller.
+pub fn decode_quietly(data: &[u8], mode: Mode) -> anyhow::Result<Ani> {
+    xcursor::decode(data, mode.into()).context("failed to decode cursor")
+}
+
+/// Write every image of every frame to `output_dir` as a PNG, using up to `jobs` threads.
+///
+/// Files named in `keep` are left as they are, but are still included in the result. After
+/// each file is written, `on_written` is called with the number of files written so far and
+/// the number of files to write.
+///
+/// Frames may contain several images of the same size (e.g., at different color depths);
+/// only the first image of each size is kept, since an Xcursor can only hold one per size.
+/// If `frames` lists the sizes to generate, only those are written: sizes a frame doesn't have
+/// are resampled from its largest image, with the hotspot scaled to match.
+pub fn extract_frames<F, P>(
+    ani: &Ani,
+    output_dir: &Path,
+    frames: &Frames,
+    file_name: F,
+    keep: &HashSet<String>,
+    jobs: NonZeroUsize,
+    on_written: P,
+) -> anyhow::Result<Vec<Vec<Image>>>
+where
+    F: Fn(usize, u32) -> anyhow::Result<String>,
+    P: Fn(usize, usize) + Sync,
+{
+    let mut extracted = Vec::with_capacity(ani.frames().len());
+    let mut pending = Vec::new();
+    let mut seen = HashSet::new();
+
+    // The file written for each unique image (by size), so repeated frames can share it.
+    let mut unique = HashMap::<(u32, u32, u32, &[u8]), String>::new();
+
+    // Name every image up front, so duplicates are caught before anything is written.
+    for (i, frame) in ani.frames().iter().enumerate() {
+        let mut originals = Vec::<(usize, &IconImage)>::with_capacity(frame.images().len());
+
+        for (j, image) in frame.images().iter().enumerate() {
+            let size = image.width();
+
+            if originals
+                .iter()
+                .any(|&(_, original)| size == original.width())
+            {
+                info!("skipping duplicate {size}x{size} image in frame {i}");
+                continue;
+            }
+
+            originals.push((j, image));
+        }
+
+        let targets = if frames.sizes().is_empty() {
+            originals.iter().map(|&(_, image)| image.width()).collect()
+        } else {
+            frames.sizes().to_vec()
+        };
+
+        let mut images = Vec::<Image>::with_capacity(targets.len());
+
+        for size in targets {
+            let Some(&(j, image)) = originals
+                .iter()
+                .find(|&&(_, image)| image.width() == size)
+                .or_else(|| originals.iter().max_by_key(|&&(_, image)| image.width()))
+            else {
+                continue;
+            };
+
+            let key = (size, image.width(), image.height(), image.rgba_data());
+
+            let name = if let Some(name) = unique.get(&key).filter(|_| frames.dedup()) {
+                info!("frame {i} reuses {name}");
+                name.clone()
+            } else {
+                let name = file_name(i, size)?;
+
+                if !seen.insert(name.clone()) {
+                    bail!("frame name template produced a duplicate file name: {name}");
+                }
+
+                if !keep.contains(&name) {
+                    pending.push((output_dir.join(&name), image, size));
+                }
+
+                unique.insert(key, name.clone());
+                name
+            };
+
+            let hotspot = frame.hotspot(j).unwrap_or_default();
+            let hotspot = scale::hotspot(hotspot, image.width(), size);
+            images.push(Image::new(size, (hotspot.x(), hotspot.y()), name));
+        }
+
+        extracted.push(images);
     }
 
-    let status = Command::new("ln")
-        .args([
-            "--symbolic",
-            &source.display().to_string(),
-            &target.display().to_string(),
-        ])
-        .status()
-        .context("failed to execute ln")?;
+    let filter = frames.filter();
+    let compression = frames.compression();
+    let total = pending.len();
+    let written = AtomicUsize::new(0);
+    // Each thread takes a writer (and its buffer) for a frame and puts it back afterwards, so
+    // there are never more of them than threads.
+    let writers = Mutex::new(Vec::new());
+    let results = xcursor::pool::map(jobs, pending, |(path, image, size)| -> anyhow::Result<()> {
+        let mut writer = writers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop()
+            .unwrap_or_else(|| PngWriter::new(compression));
+
+        if image.width() == size {
+            writer.write(&path, image)
+        } else {
+            info!(
+                "resampling {}x{} image to {size}",
+                image.width(),
+                image.height()
+            );
+            let scaled = scale::resize(image, size, filter)?;
+            writer.write(&path, &scaled)
+        }
+        .with_context(|| format!("failed to create file: {}", path.display()))?;
+
+        writers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(writer);
+        on_written(written.fetch_add(1, Ordering::Relaxed) + 1, total);
+        Ok(())
+    });
 
-    match status.code() {
-        Some(0) => Ok(()),
-        Some(code) => Err(anyhow!("process failed with exit code: {code}")),
-        None => Err(anyhow!("process terminated due to signal")),
+    for result in results {
+        result.map_err(|_| anyhow!("panicked while writing frame"))??;
     }
+
+    Ok(extracted)
 }
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::io::{self, Write as _};
+
+use anyhow::{anyhow, Context as _};
+use colored::Colorize as _;
+
+use crate::archive;
+use crate::commands::Run;
+use crate::config::{Config, Cursor};
+use crate::context::Context;
+use crate::exit::Failure;
+use crate::names;
+
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Check;
+
+impl Run for Check {
+    fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let (_, config) = ctx.load()?;
+
+        let mut findings = Findings::default();
+        check_inputs(config, &mut findings);
+
+        for profile in config.profiles() {
+            let config = config.with_profile(profile)?;
+            check_inputs(&config, &mut findings);
+        }
+
+        check_names(config.cursors(), &mut findings);
+        findings.print()?;
+
+        if !findings.problems.is_empty() {
+            return Err(anyhow!(
+                "configuration check failed with ({}) problems",
+                findings.problems.len()
+            ))
+            .context(Failure::Config);
+        }
+
+        Ok(())
+    }
+}
+
+/// Everything wrong with a configuration.
+#[derive(Debug, Default)]
+struct Findings {
+    /// Mistakes that would break (or silently change) the build.
+    problems: Vec<String>,
+
+    /// Things that are probably mistakes, but don't affect the build.
+    warnings: Vec<String>,
+}
+
+impl Findings {
+    /// Record a problem, unless it was already found (e.g., in a profile sharing the input).
+    fn problem(&mut self, description: String) {
+        if !self.problems.contains(&description) {
+            self.problems.push(description);
+        }
+    }
+
+    fn print(&self) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+
+        for problem in &self.problems {
+            writeln!(stdout, "{} {problem}", "FAIL".bold().red())?;
+        }
+
+        for warning in &self.warnings {
+            writeln!(stdout, "{} {warning}", "WARN".bold().yellow())?;
+        }
+
+        writeln!(
+            stdout,
+            "\n{} problems; {} warnings",
+            self.problems.len(),
+            self.warnings.len()
+        )
+    }
+}
+
+/// Check that the input of every cursor exists (inside of its archive, if it has one).
+fn check_inputs(config: &Config, findings: &mut Findings) {
+    for cursor in config.cursors() {
+        let input = cursor.input();
+
+        let Some(archive) = cursor.archive() else {
+            if !input.is_file() {
+                findings.problem(format!(
+                    "{}: input file not found: {}",
+                    cursor.name(),
+                    input.display()
+                ));
+            }
+
+            continue;
+        };
+
+        match archive::contains(archive, input) {
+            Ok(true) => {}
+            Ok(false) => {
+                findings.problem(format!(
+                    "{}: input not found in archive {}: {}",
+                    cursor.name(),
+                    archive.display(),
+                    input.display()
+                ));
+            }
+            Err(err) => findings.problem(format!("{}: {err:#}", cursor.name())),
+        }
+    }
+}
+
+/// Check that every cursor name and alias is unique and is a name X applications look up, and
+/// that the theme provides the cursors applications use the most.
+fn check_names(cursors: &[Cursor], findings: &mut Findings) {
+    // Which cursor each name (or alias) belongs to.
+    let mut owners = HashMap::<&str, &str>::new();
+    let mut unknown = Vec::new();
+
+    for cursor in cursors {
+        let name = cursor.name();
+
+        if owners.insert(name, name).is_some() {
+            findings.problem(format!("duplicate cursor name: {name}"));
+        }
+
+        if names::canonical(name).is_none() {
+            unknown.push(name);
+        }
+    }
+
+    for cursor in cursors {
+        let name = cursor.name();
+
+        for alias in cursor.aliases() {
+            match owners.get(alias.as_str()) {
+                Some(&owner) if owner == name => {
+                    findings.problem(format!("{name}: duplicate alias: {alias}"));
+                }
+                Some(&owner) => {
+                    findings.problem(format!(
+                        "{name}: alias {alias} collides with cursor {owner}"
+                    ));
+                }
+                None => {
+                    owners.insert(alias, name);
+
+                    if names::canonical(alias).is_none() {
+                        unknown.push(alias);
+                    }
+                }
+            }
+        }
+    }
+
+    for name in unknown {
+        findings
+            .warnings
+            .push(format!("unknown X cursor name: {name}"));
+    }
+
+    for name in names::missing_critical(owners.into_keys()) {
+        findings
+            .warnings
+            .push(format!("theme has no {name} cursor"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "default"
+            aliases = ["left_ptr", "left_ptr"]
+            input = "./Arrow.ani"
+
+            [[cursor]]
+            name = "text"
+            aliases = ["xterm", "left_ptr", "default"]
+            input = "./IBeam.ani"
+
+            [[cursor]]
+            name = "text"
+            aliases = ["sparkles"]
+            input = "./IBeam.ani"
+        "#
+        .parse()
+        .unwrap();
+
+        let mut findings = Findings::default();
+        check_names(config.cursors(), &mut findings);
+
+        assert_eq!(
+            findings.problems,
+            [
+                "duplicate cursor name: text",
+                "default: duplicate alias: left_ptr",
+                "text: alias left_ptr collides with cursor default",
+                "text: alias default collides with cursor default",
+            ]
+        );
+        assert_eq!(
+            findings.warnings[..2],
+            [
+                "unknown X cursor name: sparkles",
+                "theme has no pointer cursor"
+            ]
+        );
+    }
+}
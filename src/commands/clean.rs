@@ -0,0 +1,286 @@
+use std::io::{self, ErrorKind, Write as _};
+use std::path::{Component, Path};
+use std::{fs, iter};
+
+use anyhow::{bail, Context as _};
+use colored::Colorize as _;
+use tracing::info;
+
+use crate::commands::Run;
+use crate::config::{Cursor, Layout};
+use crate::context::Context;
+use crate::fingerprint::Fingerprints;
+use crate::package::Build;
+use crate::staging;
+
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Clean {
+    /// Only remove the extracted frames.
+    #[clap(long, conflicts_with = "cursors")]
+    frames: bool,
+
+    /// Only remove the generated theme directories.
+    #[clap(long, conflicts_with = "cursors")]
+    theme: bool,
+
+    /// Only remove what was built for the cursor with the given name (can be repeated).
+    #[clap(long = "cursor", value_name = "NAME")]
+    cursors: Vec<String>,
+}
+
+/// The entries `build` (and `package`) create in a build directory, besides the staging
+/// directories of builds that were interrupted (see [`staging::PREFIXES`]). Anything else means
+/// the directory holds files of its own, and isn't removed.
+const BUILD_ENTRIES: &[&str] = &[
+    "frames",
+    "theme",
+    "themes",
+    "profiles",
+    "variants",
+    "hyprcursors",
+    "cache",
+    "svg",
+    "dist",
+    ".fingerprints",
+    ".frame-hashes",
+];
+
+/// The directories holding the build directories of other themes, profiles and variants.
+const NESTED_BUILDS: &[&str] = &["themes", "profiles", "variants"];
+
+impl Run for Clean {
+    fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let (package, config) = ctx.load()?;
+        let root = package.build().as_path();
+
+        if !root.exists() {
+            info!("nothing to clean: {:#}", root.display());
+            return Ok(());
+        }
+
+        check_build_dir(package.as_path(), root)?;
+        let builds = build_dirs(root)?;
+
+        for build in &builds {
+            staging::remove_stale(build.as_path())
+                .context("failed to remove stale staging directories")?;
+        }
+
+        if !self.cursors.is_empty() {
+            for name in &self.cursors {
+                let cursor = config
+                    .cursors()
+                    .iter()
+                    .find(|cursor| cursor.name() == name)
+                    .with_context(|| format!("cursor not found: {name}"))?;
+
+                for build in &builds {
+                    clean_cursor(build, cursor, config.frames().layout())?;
+                }
+            }
+        } else if self.frames || self.theme {
+            for build in &builds {
+                if self.frames {
+                    remove(root, &build.frames())?;
+                    remove(root, &build.frame_hashes())?;
+                    remove(root, &build.cache())?;
+                }
+
+                if self.theme {
+                    remove(root, build.theme().as_path())?;
+                }
+            }
+        } else {
+            remove(root, root)?;
+        }
+
+        let mut stderr = io::stderr();
+        writeln!(
+            stderr,
+            "{}",
+            format!("Successfully cleaned: {}", root.display())
+                .bold()
+                .green()
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Make sure `build` is a build directory that is safe to remove: inside of the package (but
+/// not the package itself), and holding nothing but what the `build` command creates.
+fn check_build_dir(package: &Path, build: &Path) -> anyhow::Result<()> {
+    let package = fs::canonicalize(package).context("failed to resolve package directory")?;
+    let canonical = fs::canonicalize(build).context("failed to resolve build directory")?;
+
+    if canonical == package || !canonical.starts_with(&package) {
+        bail!(
+            "refusing to clean {} because it isn't a directory inside of the package",
+            build.display()
+        );
+    }
+
+    for entry in fs::read_dir(build).context("failed to read build directory")? {
+        let name = entry.context("failed to read build directory")?.file_name();
+
+        if !BUILD_ENTRIES.iter().any(|expected| name == *expected) && !staging::is_staging(&name) {
+            bail!(
+                "refusing to clean {} because it contains files that weren't created by \
+                ani-to-xcursor: {}",
+                build.display(),
+                name.to_string_lossy()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// The build directory at `root`, and those of the themes, profiles and variants built inside
+/// of it.
+fn build_dirs(root: &Path) -> anyhow::Result<Vec<Build>> {
+    let mut builds = vec![Build::new(root.to_owned())];
+
+    for nested in NESTED_BUILDS {
+        let entries = match fs::read_dir(root.join(nested)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(err).context("failed to read build directory"),
+        };
+
+        for entry in entries {
+            let entry = entry.context("failed to read build directory")?;
+
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                builds.extend(build_dirs(&entry.path())?);
+            }
+        }
+    }
+
+    Ok(builds)
+}
+
+/// Remove what was built for `cursor` in `build`, and forget its fingerprints so it is rebuilt
+/// by the next build.
+fn clean_cursor(build: &Build, cursor: &Cursor, layout: Layout) -> anyhow::Result<()> {
+    let root = build.as_path();
+    let Some(stem) = cursor.input().file_stem() else {
+        return Ok(());
+    };
+
+    let mut frames_dir = build.frames();
+    if layout == Layout::Nested {
+        frames_dir.push(stem);
+    }
+
+    let mut fingerprints = Fingerprints::load(&build.fingerprints())?;
+    fingerprints.retain(|name| name != cursor.name());
+    if build.fingerprints().exists() {
+        fingerprints.save(&build.fingerprints())?;
+    }
+
+    let prefix = format!("{}/", cursor.name());
+    let mut frame_hashes = Fingerprints::load(&build.frame_hashes())?;
+    for (key, _) in frame_hashes.iter() {
+        if let Some(file_name) = key.strip_prefix(&prefix) {
+            remove(root, &frames_dir.join(file_name))?;
+        }
+    }
+    frame_hashes.retain(|key| !key.starts_with(&prefix));
+    if build.frame_hashes().exists() {
+        frame_hashes.save(&build.frame_hashes())?;
+    }
+
+    remove(root, &frames_dir.join(stem))?;
+    remove(root, &frames_dir.join(format!("{}.cursor", cursor.name())))?;
+
+    if layout == Layout::Nested {
+        // Fails (harmlessly) if another cursor shares the directory.
+        _ = fs::remove_dir(&frames_dir);
+    }
+
+    let theme = build.theme();
+    for name in iter::once(cursor.name()).chain(cursor.aliases().iter().map(String::as_str)) {
+        remove(root, &theme.cursors().join(name))?;
+    }
+    remove(
+        root,
+        &theme.hyprcursors().join(format!("{}.hlc", cursor.name())),
+    )?;
+    remove(root, &build.hyprcursor_shapes().join(cursor.name()))?;
+
+    Ok(())
+}
+
+/// Remove the file, symlink or directory at `path`, which must be inside of `root`.
+///
+/// Symlinks are removed rather than followed, so nothing outside of `root` is ever touched.
+/// Missing paths are skipped.
+fn remove(root: &Path, path: &Path) -> anyhow::Result<()> {
+    let Ok(relative) = path.strip_prefix(root) else {
+        bail!(
+            "refusing to remove a path outside of {}: {}",
+            root.display(),
+            path.display()
+        );
+    };
+
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        bail!(
+            "refusing to remove a path outside of {}: {}",
+            root.display(),
+            path.display()
+        );
+    }
+
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", path.display()));
+        }
+    };
+
+    // A symlinked directory along the way could lead outside of `root`.
+    if relative.parent().is_some()
+        && let Some(parent) = path.parent()
+        && !fs::canonicalize(parent)?.starts_with(fs::canonicalize(root)?)
+    {
+        bail!(
+            "refusing to remove a path outside of {}: {}",
+            root.display(),
+            path.display()
+        );
+    }
+
+    if metadata.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+    .with_context(|| format!("failed to remove {}", path.display()))?;
+
+    info!("removed: {:#}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_inside_root() {
+        let root = Path::new("/tmp/package/build");
+
+        assert!(remove(root, Path::new("/tmp/package/Cursor.toml")).is_err());
+        assert!(remove(
+            root,
+            Path::new("/tmp/package/build/frames/../../Cursor.toml")
+        )
+        .is_err());
+        assert!(remove(root, Path::new("/tmp/package/build/frames/missing")).is_ok());
+    }
+}
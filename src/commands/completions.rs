@@ -0,0 +1,39 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use anyhow::Context as _;
+use clap::CommandFactory as _;
+use clap_complete::Shell;
+use tracing::info;
+
+use crate::commands::Run;
+use crate::context::Context;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct Completions {
+    /// The shell to generate the completion script for.
+    #[clap(value_enum)]
+    shell: Shell,
+}
+
+impl Run for Completions {
+    fn run(&self, _ctx: &mut Context) -> anyhow::Result<()> {
+        let mut command = crate::Parser::command();
+        let name = command.get_name().to_owned();
+        clap_complete::generate(self.shell, &mut command, name, &mut io::stdout());
+
+        Ok(())
+    }
+}
+
+/// Write the man pages of the command and each of its subcommands to `dir` (for
+/// `--generate-man`).
+pub fn generate_man(dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    clap_mangen::generate_to(crate::Parser::command(), dir)
+        .with_context(|| format!("failed to write man pages to {}", dir.display()))?;
+
+    info!("generated man pages: {:#}", dir.display());
+    Ok(())
+}
@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::{fs, path};
+
+use ani::de::Ani;
+use anyhow::Context as _;
+use colored::Colorize as _;
+use tracing::{info, warn};
+use xcursor::{pool, ConfigWriter, Image, Step};
+
+use crate::commands::build::{decode, extract_frames, Mode};
+use crate::commands::Run;
+use crate::config::{Animation, Frames};
+use crate::context::Context;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct Convert {
+    /// The ANI (or static CUR) file to convert.
+    input: PathBuf,
+
+    /// Where to write the Xcursor [default: the input's file stem, in the current directory]
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+
+    /// Keep the extracted PNG frames (and the xcursorgen configuration) in this directory.
+    #[clap(long)]
+    frames: Option<PathBuf>,
+
+    /// How forgiving to be of input that doesn't follow the ANI file format specification.
+    #[clap(long, value_enum, default_value_t)]
+    mode: Mode,
+}
+
+impl Run for Convert {
+    fn run(&self, _ctx: &mut Context) -> anyhow::Result<()> {
+        let input = path::absolute(&self.input).context("failed to resolve input path")?;
+        let data = fs::read(&input)
+            .with_context(|| format!("failed to read file: {}", input.display()))?;
+        let ani = decode(&data, self.mode)?;
+
+        let file_stem = input
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .context("expected path to be valid unicode")?;
+
+        let output = match self.output {
+            Some(ref output) => output.clone(),
+            None => PathBuf::from(file_stem),
+        };
+        // xcursorgen runs from inside of the frames directory.
+        let output = path::absolute(&output).context("failed to resolve output path")?;
+
+        // Unless they are kept, the frames go in a directory of their own (that no one else
+        // can have created beforehand).
+        let (frames_dir, temp) = if let Some(ref frames) = self.frames {
+            (frames.clone(), None)
+        } else {
+            let temp = tempfile::Builder::new()
+                .prefix("ani-to-xcursor-convert-")
+                .tempdir()
+                .context("failed to create temporary directory")?;
+            (temp.path().to_owned(), Some(temp))
+        };
+        let frames_dir = path::absolute(&frames_dir).context("failed to resolve frames path")?;
+        fs::create_dir_all(&frames_dir).context("failed to create frames directory")?;
+
+        let result = convert(&ani, file_stem, &frames_dir, &output);
+
+        // Failing to clean up shouldn't hide whether the conversion itself succeeded.
+        if let Some(temp) = temp {
+            match temp.close() {
+                Ok(()) => info!("removed directory: {:#}", frames_dir.display()),
+                Err(err) => warn!(
+                    "failed to remove temporary directory: {}: {err}",
+                    frames_dir.display()
+                ),
+            }
+        }
+        result?;
+
+        let mut stderr = io::stderr();
+        writeln!(
+            stderr,
+            "{}",
+            format!("Successfully created Xcursor: {}", output.display())
+                .bold()
+                .green()
+        )?;
+
+        Ok(())
+    }
+}
+
+fn convert(ani: &Ani, file_stem: &str, frames_dir: &Path, output: &Path) -> anyhow::Result<()> {
+    let frames = Frames::default();
+    let extracted = extract_frames(
+        ani,
+        frames_dir,
+        &frames,
+        |index, size| frames.file_name(file_stem, file_stem, index, size),
+        &HashSet::new(),
+        pool::default_jobs(),
+        |_, _| {},
+    )?;
+
+    let config = frames_dir.join(format!("{file_stem}.cursor"));
+    let steps = clamped_steps(ani, &Animation::default())?;
+    write_xcursor_config(&extracted, &steps, &config)?;
+    let xcursorgen = xcursor::locate_xcursorgen(None)?;
+    xcursor::run_xcursorgen(&xcursorgen, frames_dir, &config, output)
+        .context("failed to create Xcursor")?;
+    info!("created Xcursor: {:#}", output.display());
+    Ok(())
+}
+
+/// Resolve the steps of the animation (see [`xcursor::steps`]), raising every duration to at
+/// least `animation.min_frame_duration` (see [`xcursor::clamp_durations`]).
+fn clamped_steps(ani: &Ani, animation: &Animation) -> anyhow::Result<Vec<Step>> {
+    let mut steps = xcursor::steps(ani, animation.speed())?;
+    let min_duration = animation.min_frame_duration();
+    let clamped = xcursor::clamp_durations(&mut steps, min_duration);
+
+    // The duration of a single frame doesn't matter, since it's never replaced.
+    if clamped > 0 && steps.len() > 1 {
+        warn!(
+            "raised the duration of {clamped} of {} steps to {min_duration}ms",
+            steps.len(),
+        );
+    }
+
+    Ok(steps)
+}
+
+/// Write the `xcursorgen` configuration for the extracted frames.
+fn write_xcursor_config(
+    frames: &[Vec<Image>],
+    steps: &[Step],
+    output: &Path,
+) -> anyhow::Result<()> {
+    let writer = frames
+        .iter()
+        .cloned()
+        .fold(ConfigWriter::new(), ConfigWriter::frame);
+
+    let contents = writer.steps(steps).encode()?;
+    fs::write(output, contents).context("failed to create Xcursor configuration file")?;
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use std::env;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use colored::Colorize as _;
+
+use crate::commands::install::{check_writable, Location};
+use crate::commands::Run;
+use crate::context::Context;
+use crate::desktop::Desktop;
+use crate::exit;
+
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Doctor {
+    /// Where themes would be installed, to check that it can be written to.
+    #[clap(flatten)]
+    location: Location,
+}
+
+impl Run for Doctor {
+    fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let mut report = Report::default();
+
+        let configured = check_config(ctx, &mut report)?;
+        check_xcursorgen(configured.as_deref(), &mut report);
+        self.check_icons_dir(&mut report)?;
+        check_session(&mut report);
+        check_desktop(&mut report);
+
+        report.print()?;
+
+        if report.failures() > 0 {
+            return Err(anyhow!("found ({}) problems", report.failures()));
+        }
+
+        Ok(())
+    }
+}
+
+impl Doctor {
+    fn check_icons_dir(&self, report: &mut Report) -> anyhow::Result<()> {
+        let dir = self.location.icons_dir()?;
+        // `install` creates the directory if it is missing, so it's enough to be able to write
+        // to the closest existing parent.
+        let existing = dir.ancestors().find(|path| path.exists()).unwrap_or(&dir);
+
+        match check_writable(existing) {
+            Ok(()) => report.pass(format!("can install themes to {}", dir.display())),
+            Err(err) => report.fail(
+                format!("can't install themes to {}: {err:#}", dir.display()),
+                "install for your user only (without `--system` or `--prefix`), or fix the \
+                permissions of the directory",
+            ),
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that `Cursor.toml` is valid, returning the `xcursorgen` it configures (if any).
+fn check_config(ctx: &mut Context, report: &mut Report) -> anyhow::Result<Option<PathBuf>> {
+    let path = ctx.load_package()?.config();
+
+    if !path.exists() {
+        report.warn(
+            format!(
+                "no Cursor.toml in {}",
+                ctx.load_package()?.as_path().display()
+            ),
+            "run `ani-to-xcursor init` in the directory with your cursors to generate one",
+        );
+        return Ok(None);
+    }
+
+    match ctx.load() {
+        Ok((_, config)) => {
+            report.pass(format!(
+                "Cursor.toml is valid (theme {}, {} cursors)",
+                config.theme(),
+                config.cursors().len()
+            ));
+            Ok(config.xcursorgen().map(ToOwned::to_owned))
+        }
+        Err(err) => {
+            report.fail(
+                format!(
+                    "Cursor.toml is invalid: {}",
+                    exit::causes(&err).collect::<Vec<_>>().join(": ")
+                ),
+                "fix the error above, then run `ani-to-xcursor check` to look for other mistakes",
+            );
+            Ok(None)
+        }
+    }
+}
+
+fn check_xcursorgen(configured: Option<&Path>, report: &mut Report) {
+    match xcursor::locate_xcursorgen(configured) {
+        Ok(path) => report.pass(format!("xcursorgen found: {}", path.display())),
+        Err(err) => report.fail(
+            err.to_string(),
+            "`build` needs xcursorgen to create the Xcursors",
+        ),
+    }
+}
+
+/// Check whether the graphical session shows animated Xcursors.
+fn check_session(report: &mut Report) {
+    let session = env::var("XDG_SESSION_TYPE")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let current = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    let desktops = current
+        .split(':')
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>();
+    let is = |names: &[&str]| desktops.iter().any(|name| names.contains(&name.as_str()));
+
+    let wayland = session == "wayland" || env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = session == "x11" || env::var_os("DISPLAY").is_some();
+
+    if wayland {
+        if is(&["gnome", "kde", "hyprland"]) {
+            report.pass(format!("Wayland session ({current}) animates Xcursors"));
+
+            if is(&["hyprland"]) {
+                report.warn(
+                    "Hyprland prefers hyprcursor themes",
+                    "build with `--target hyprcursor` to add one alongside the Xcursors",
+                );
+            }
+        } else {
+            let compositor = if current.is_empty() {
+                "an unknown compositor"
+            } else {
+                current.as_str()
+            };
+            report.warn(
+                format!("Wayland session ({compositor}) may not animate Xcursors"),
+                "some compositors only show the first frame of animated cursors; check the \
+                documentation of yours if the cursors don't animate",
+            );
+        }
+    } else if x11 {
+        report.pass("X11 session animates Xcursors");
+    } else {
+        report.warn(
+            "no graphical session found",
+            "run `ani-to-xcursor doctor` from your desktop to check whether it animates Xcursors",
+        );
+    }
+}
+
+/// Check whether `install --apply` can make the theme the active one.
+fn check_desktop(report: &mut Report) {
+    match Desktop::detect() {
+        Some(desktop) => report.pass(format!(
+            "`install --apply` can set the cursor theme ({desktop:?})"
+        )),
+        None => report.warn(
+            "`install --apply` can't set the cursor theme of this desktop",
+            "after installing, select the theme in the settings of your desktop",
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    checks: Vec<(Status, String, Option<String>)>,
+}
+
+impl Report {
+    fn pass(&mut self, description: impl Into<String>) {
+        self.checks.push((Status::Pass, description.into(), None));
+    }
+
+    fn warn(&mut self, description: impl Into<String>, hint: impl Into<String>) {
+        self.checks
+            .push((Status::Warn, description.into(), Some(hint.into())));
+    }
+
+    fn fail(&mut self, description: impl Into<String>, hint: impl Into<String>) {
+        self.checks
+            .push((Status::Fail, description.into(), Some(hint.into())));
+    }
+
+    fn count(&self, status: Status) -> usize {
+        self.checks.iter().filter(|check| check.0 == status).count()
+    }
+
+    fn failures(&self) -> usize {
+        self.count(Status::Fail)
+    }
+
+    fn print(&self) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+
+        for (status, description, hint) in &self.checks {
+            let status = match status {
+                Status::Pass => "PASS".bold().green(),
+                Status::Warn => "WARN".bold().yellow(),
+                Status::Fail => "FAIL".bold().red(),
+            };
+
+            writeln!(stdout, "{status} {description}")?;
+
+            if let Some(hint) = hint {
+                writeln!(stdout, "     {} {hint}", "hint:".bold())?;
+            }
+        }
+
+        writeln!(
+            stdout,
+            "\n{} passed; {} warnings; {} failed",
+            self.count(Status::Pass),
+            self.count(Status::Warn),
+            self.failures()
+        )
+    }
+}
@@ -1,59 +1,124 @@
-use std::io::Write as _;
-use std::process::{Command, Stdio};
+use std::fmt::Write as _;
+use std::io::{BufRead, Write};
+use std::path::{Component, Path, PathBuf};
 use std::{env, fs, io};
 
 use anyhow::{bail, Context as _};
 use colored::Colorize as _;
-use tracing::error;
+use tracing::{info, warn};
 
+use crate::archive;
 use crate::commands::Run;
 use crate::context::Context;
+use crate::inf::Inf;
+use crate::names;
+use crate::roles::Preset;
+use crate::windows::ROLES;
 
-#[derive(Debug, Clone, clap::Args)]
-pub struct Init;
+/// Words that commonly appear in the names of cursor files (or of their `[Strings]` keys), and
+/// the X cursor name they suggest.
+///
+/// Checked in order, so the more specific words come first (e.g., `Link Select` is a pointer,
+/// and `Help Select` is not the default cursor).
+const HINTS: &[(&str, &[&str])] = &[
+    ("pencil", &["handwriting", "handwrite", "pen", "nwpen"]),
+    ("pointer", &["link", "hand", "linkselect"]),
+    (
+        "progress",
+        &["working", "work", "appstarting", "background"],
+    ),
+    ("wait", &["busy", "wait"]),
+    ("help", &["help", "helpsel", "helpselect"]),
+    ("crosshair", &["precision", "cross", "crosshair"]),
+    ("text", &["text", "ibeam", "beam"]),
+    (
+        "not-allowed",
+        &["unavailable", "unavailiable", "unavail", "no"],
+    ),
+    ("ns-resize", &["vertical", "vert", "ns", "sizens"]),
+    ("ew-resize", &["horizontal", "horz", "ew", "sizewe"]),
+    ("nwse-resize", &["diagonal1", "dgn1", "nwse", "sizenwse"]),
+    ("nesw-resize", &["diagonal2", "dgn2", "nesw", "sizenesw"]),
+    ("move", &["move", "sizeall"]),
+    ("up-arrow", &["alternate", "up", "uparrow"]),
+    ("pin", &["pin"]),
+    ("person", &["person"]),
+    ("default", &["normal", "arrow", "pointer", "default"]),
+];
+
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Init {
+    /// Extract a downloaded cursor pack (a `.zip`, `.cab` or `.7z` archive) into the package
+    /// first.
+    #[clap(long, visible_alias = "archive")]
+    from: Option<PathBuf>,
+
+    /// Review the role guessed for each cursor file (accept, rename or skip it) before writing
+    /// Cursor.toml.
+    #[clap(short, long)]
+    interactive: bool,
+
+    /// Map the cursor files by the naming scheme of a well-known source of cursors (e.g., the
+    /// files Windows installs), before guessing their roles from their names.
+    #[clap(long, value_enum)]
+    preset: Option<Preset>,
+}
 
 impl Init {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
     }
 }
 
 impl Run for Init {
-    fn run(&self, _ctx: &mut Context) -> anyhow::Result<()> {
-        let cwd = env::current_dir().context("failed to get current directory")?;
-        let install_inf = cwd.join("Install.inf");
-        let cursor_toml = cwd.join("Cursor.toml");
-
-        let child = Command::new("python3")
-            .args([
-                "-c",
-                include_str!("./init.py"),
-                "-vvv",
-                "--input",
-                install_inf
-                    .to_str()
-                    .context("expected path to be valid unicode")?,
-            ])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .context("failed to execute python3")?;
-
-        let output = child
-            .wait_with_output()
-            .context("failed to get output from child process")?;
-
-        if !output.stderr.is_empty() {
-            let err = String::from_utf8_lossy(&output.stderr).to_string();
-            error!("child process returned an error:\n{err}");
-        }
-
-        if output.stdout.is_empty() {
-            bail!("failed to get output from child process");
-        }
-
-        let text = String::from_utf8_lossy(&output.stdout).to_string();
-        fs::write(&cursor_toml, &text).context("failed to print Cursor.toml contents")?;
+    fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let root = if let Some(ref package) = ctx.package {
+            package.as_path().to_owned()
+        } else {
+            env::current_dir().context("failed to get current directory")?
+        };
+
+        if let Some(ref path) = self.from {
+            archive::extract_pack(path, &root).context("failed to extract archive")?;
+        }
+
+        let mut template = if let Some(install_inf) = find_file(&root, "Install.inf") {
+            info!("found Install.inf: {:#}", install_inf.display());
+            from_install_inf(&root, &install_inf, self.preset)?
+        } else {
+            warn!("failed to find Install.inf; guessing cursor roles from file names");
+            from_loose_files(&root, self.preset)?
+        };
+
+        if self.interactive {
+            template.add_unused_files(&root);
+            template = review(template, &mut io::stdin().lock(), &mut io::stderr())?;
+        }
+
+        let mut text = toml::to_string(&template).context("failed to serialize Cursor.toml")?;
+
+        if !template.unmatched.is_empty() {
+            warn!(
+                "failed to assign a role to ({}) cursors; see the TODO in Cursor.toml",
+                template.unmatched.len()
+            );
+
+            text.push_str(
+                "\n# TODO: The roles of these cursors couldn't be guessed from their file names (or\n\
+                # were already taken by another cursor).\n\
+                # Give each one a name (e.g., \"pointer\" or \"wait\") and uncomment it.\n",
+            );
+
+            for input in &template.unmatched {
+                _ = write!(
+                    text,
+                    "#\n# [[cursor]]\n# name = \"TODO\"\n# input = {input:?}\n"
+                );
+            }
+        }
+
+        let cursor_toml = root.join("Cursor.toml");
+        fs::write(&cursor_toml, &text).context("failed to write Cursor.toml")?;
 
         let mut stderr = io::stderr();
         writeln!(stderr, "{}", "Ready!".bold().green())?;
@@ -61,3 +126,621 @@ impl Run for Init {
         Ok(())
     }
 }
+
+#[derive(Debug, serde::Serialize)]
+struct Template {
+    theme: String,
+    version: &'static str,
+
+    #[serde(rename = "cursor")]
+    cursors: Vec<TemplateCursor>,
+
+    /// The inputs of the cursors whose role couldn't be guessed.
+    #[serde(skip)]
+    unmatched: Vec<String>,
+}
+
+impl Template {
+    /// Offer the cursor files of the package that aren't used yet (e.g., the ones `Install.inf`
+    /// doesn't mention) as unmatched, so they can be reviewed too.
+    fn add_unused_files(&mut self, package: &Path) {
+        let mut files = Vec::new();
+        find_cursor_files(package, &mut files);
+
+        for path in files {
+            let relative = path.strip_prefix(package).unwrap_or(&path);
+            let input = format!("./{}", relative.display());
+
+            if !self.cursors.iter().any(|cursor| cursor.input == input)
+                && !self.unmatched.contains(&input)
+            {
+                self.unmatched.push(input);
+            }
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize)]
+struct TemplateCursor {
+    name: String,
+    input: String,
+    aliases: &'static [&'static str],
+}
+
+/// Generate the configuration from the `Install.inf` of a cursor pack.
+fn from_install_inf(
+    package: &Path,
+    install_inf: &Path,
+    preset: Option<Preset>,
+) -> anyhow::Result<Template> {
+    let inf = Inf::from_file(install_inf)?;
+    let theme = install_inf
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|name| name.to_str())
+        .context("expected path to be valid unicode")?
+        .to_owned();
+
+    let cursors = assign_roles(&inf, preset)
+        .into_iter()
+        .map(|(name, path)| TemplateCursor {
+            name: name.to_owned(),
+            input: locate_cursor(package, &path),
+            aliases: names::aliases(name),
+        })
+        .collect();
+
+    Ok(Template {
+        theme,
+        version: "0.1.0",
+        cursors,
+        unmatched: Vec::new(),
+    })
+}
+
+/// Generate a best-effort configuration from the cursor files in the package, for packs that
+/// don't come with an `Install.inf`.
+///
+/// The role of each cursor is guessed from its file name; the first cursor to claim a role
+/// gets it, and the rest are left for the user to sort out.
+fn from_loose_files(package: &Path, preset: Option<Preset>) -> anyhow::Result<Template> {
+    let theme = package
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("expected path to be valid unicode")?
+        .to_owned();
+
+    let mut files = Vec::new();
+    find_cursor_files(package, &mut files);
+
+    let mut cursors = Vec::<TemplateCursor>::new();
+    let mut unmatched = Vec::new();
+
+    for path in files {
+        let relative = path.strip_prefix(package).unwrap_or(&path);
+        let input = format!("./{}", relative.display());
+        let file = path.file_name().and_then(|name| name.to_str());
+
+        match file.and_then(|file| preset_role(preset, file).or_else(|| guess_role_fuzzy(file))) {
+            Some(name) if !cursors.iter().any(|cursor| cursor.name == name) => {
+                info!("guessed the role of cursor {input}: {name}");
+                cursors.push(TemplateCursor {
+                    name: name.to_owned(),
+                    input,
+                    aliases: names::aliases(name),
+                });
+            }
+            _ => unmatched.push(input),
+        }
+    }
+
+    if cursors.is_empty() && unmatched.is_empty() {
+        bail!(
+            "failed to find Install.inf or any cursor files; copy the template Cursor.toml and \
+            fill it out manually"
+        );
+    }
+
+    Ok(Template {
+        theme,
+        version: "0.1.0",
+        cursors,
+        unmatched,
+    })
+}
+
+/// Walk the user through the cursors of `template`, reading their answers from `input`: the
+/// name of the theme, then whether to accept, rename or skip each cursor (including the ones
+/// whose role couldn't be guessed).
+///
+/// Reaching the end of `input` accepts the remaining suggestions.
+fn review(
+    template: Template,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> anyhow::Result<Template> {
+    writeln!(
+        output,
+        "Press Enter to accept the suggestion in brackets, type another name to use it instead, \
+        or type `-` to skip the cursor."
+    )?;
+
+    let theme = match prompt(input, output, &format!("Theme name [{}]", template.theme))? {
+        Some(theme) if theme != "-" => theme,
+        _ => template.theme,
+    };
+
+    let candidates = template
+        .cursors
+        .into_iter()
+        .map(|cursor| (cursor.input, Some(cursor.name)))
+        .chain(template.unmatched.into_iter().map(|input| (input, None)))
+        .collect::<Vec<_>>();
+
+    let mut cursors = Vec::<TemplateCursor>::with_capacity(candidates.len());
+
+    for (file, suggestion) in candidates {
+        let is_taken = |name: &str| cursors.iter().any(|cursor| cursor.name == name);
+        let suggestion = suggestion.filter(|name| !is_taken(name));
+
+        let Some(name) = ask_name(input, output, &file, suggestion.as_deref(), is_taken)? else {
+            info!("skipping cursor: {file}");
+            continue;
+        };
+
+        cursors.push(TemplateCursor {
+            aliases: names::aliases(&name),
+            name,
+            input: file,
+        });
+    }
+
+    Ok(Template {
+        theme,
+        version: template.version,
+        cursors,
+        unmatched: Vec::new(),
+    })
+}
+
+/// Ask for the name of the cursor `file` until the answer is usable, returning `None` if the
+/// cursor is skipped.
+fn ask_name(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    file: &str,
+    suggestion: Option<&str>,
+    is_taken: impl Fn(&str) -> bool,
+) -> anyhow::Result<Option<String>> {
+    loop {
+        let question = format!("{file} [{}]", suggestion.unwrap_or("skip"));
+
+        let name = match prompt(input, output, &question)? {
+            None => return Ok(suggestion.map(str::to_owned)),
+            Some(answer) if answer == "-" => return Ok(None),
+            Some(answer) => answer,
+        };
+
+        let name = if let Some(standard) = names::canonical(&name) {
+            standard.to_owned()
+        } else {
+            let question = format!("{name} is not a standard cursor name; use it anyway? [y/N]");
+
+            if prompt(input, output, &question)?.is_none_or(|answer| answer != "y") {
+                continue;
+            }
+
+            name
+        };
+
+        if is_taken(&name) {
+            writeln!(output, "{name} is already used by another cursor")?;
+            continue;
+        }
+
+        return Ok(Some(name));
+    }
+}
+
+/// Print `question`, then read a line from `input`, returning `None` if it's empty (or there is
+/// nothing left to read).
+fn prompt(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    question: &str,
+) -> anyhow::Result<Option<String>> {
+    write!(output, "{question}: ")?;
+    output.flush()?;
+
+    let mut line = String::new();
+    if input
+        .read_line(&mut line)
+        .context("failed to read answer")?
+        == 0
+    {
+        writeln!(output)?;
+    }
+
+    let answer = line.trim();
+    Ok((!answer.is_empty()).then(|| answer.to_owned()))
+}
+
+/// Collect the `.ani` and `.cur` files in `dir` and its subdirectories, in a stable order.
+fn find_cursor_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut paths = entries
+        .flatten()
+        .map(|entry| (entry.path(), is_dir(&entry)))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    for (path, is_dir) in paths {
+        if is_dir {
+            find_cursor_files(&path, files);
+        } else if path.to_str().is_some_and(is_cursor_file) {
+            files.push(path);
+        }
+    }
+}
+
+/// Decide which X cursor each of the cursor files named by the `Install.inf` becomes.
+///
+/// The position of a file in `[Scheme.Reg]` decides its role. Files the scheme doesn't list
+/// (or every file, if there is no scheme) are matched by the name of their `[Strings]` key or
+/// their file name instead (see [`guess_role`]), going by the `preset` first if there is one.
+fn assign_roles(inf: &Inf, preset: Option<Preset>) -> Vec<(&'static str, String)> {
+    let scheme = scheme_cursors(inf).unwrap_or_else(|err| {
+        warn!("{err:#}; guessing cursor roles from file names instead");
+        Vec::new()
+    });
+
+    let mut assigned = Vec::<(&str, String)>::with_capacity(ROLES.len());
+
+    for (&(role, name), path) in ROLES.iter().zip(scheme) {
+        if path.is_empty() {
+            info!("no cursor provided for role: {role}");
+            continue;
+        }
+
+        let file = file_name(&path);
+
+        if let Some(guess) = preset_role(preset, file).or_else(|| guess_role(file))
+            && guess != name
+        {
+            warn!("[Scheme.Reg] lists {path} as {name}, but its name suggests {guess}");
+        }
+
+        assigned.push((name, path));
+    }
+
+    for (key, path) in string_cursors(inf) {
+        let file = file_name(&path);
+
+        if assigned
+            .iter()
+            .any(|(_, other)| file_name(other).eq_ignore_ascii_case(file))
+        {
+            continue;
+        }
+
+        let Some(name) = preset_role(preset, file)
+            .or_else(|| guess_role(&key))
+            .or_else(|| guess_role_fuzzy(file))
+        else {
+            info!("failed to guess the role of cursor: {path}");
+            continue;
+        };
+
+        if assigned.iter().any(|&(other, _)| other == name) {
+            info!("skipping cursor {path}: {name} is already provided");
+            continue;
+        }
+
+        info!("guessed the role of cursor {path}: {name}");
+        assigned.push((name, path));
+    }
+
+    assigned
+}
+
+/// The cursor files defined in the `[Strings]` section (e.g., `busy = "Busy.ani"`), along with
+/// their key.
+fn string_cursors(inf: &Inf) -> Vec<(String, String)> {
+    let Some(strings) = inf.section("Strings") else {
+        return Vec::new();
+    };
+
+    strings
+        .entries()
+        .iter()
+        .filter_map(|entry| {
+            let key = entry.key()?;
+            let value = entry.values().first()?;
+            is_cursor_file(value).then(|| (key.to_owned(), value.replace('\\', "/")))
+        })
+        .collect()
+}
+
+fn is_cursor_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("ani") || extension.eq_ignore_ascii_case("cur")
+        })
+}
+
+/// The last component of a path that uses forward slashes.
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// The X cursor name `preset` gives to the cursor file `file`, if any.
+fn preset_role(preset: Option<Preset>, file: &str) -> Option<&'static str> {
+    preset.and_then(|preset| preset.role(file))
+}
+
+/// Guess the X cursor name of a cursor from a name describing it, such as its file name (e.g.,
+/// `Busy.ani` or `aero_link.cur`) or its key in the `[Strings]` section.
+///
+/// The name is split into words, which are compared with the [`HINTS`].
+fn guess_role(name: &str) -> Option<&'static str> {
+    let mut words = words(name);
+    words.push(words.concat());
+
+    HINTS
+        .iter()
+        .find(|(_, hints)| {
+            hints
+                .iter()
+                .any(|hint| words.iter().any(|word| word == hint))
+        })
+        .map(|&(name, _)| name)
+}
+
+/// Like [`guess_role`], but also matches hints that appear anywhere in the name (e.g.,
+/// `MyBusyCursor.ani`).
+///
+/// Short hints (e.g., `no` or `up`) only match whole words, since they appear inside of too
+/// many unrelated words.
+fn guess_role_fuzzy(name: &str) -> Option<&'static str> {
+    guess_role(name).or_else(|| {
+        let name = words(name).concat();
+
+        HINTS
+            .iter()
+            .find(|(_, hints)| {
+                hints
+                    .iter()
+                    .any(|hint| hint.len() >= 4 && name.contains(hint))
+            })
+            .map(|&(name, _)| name)
+    })
+}
+
+/// Split the stem of a file name into lowercase words (e.g., `Link Select.ani` becomes `link`
+/// and `select`).
+fn words(name: &str) -> Vec<String> {
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+
+    stem.to_ascii_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Read the list of cursor paths from the `[Scheme.Reg]` section.
+///
+/// The section contains a single registry entry, where the last value is a comma-separated
+/// list of paths (one per role):
+///
+/// ```text
+/// [Scheme.Reg]
+/// HKCU,"Control Panel\Cursors\Schemes","%SCHEME_NAME%",,"cur1,cur2,..."
+/// ```
+fn scheme_cursors(inf: &Inf) -> anyhow::Result<Vec<String>> {
+    let entry = inf
+        .section("Scheme.Reg")
+        .and_then(|section| section.entries().first())
+        .context("Install.inf is missing the [Scheme.Reg] section")?;
+
+    let Some(list) = entry.values().get(4) else {
+        bail!("expected [Scheme.Reg] to contain a list of cursors");
+    };
+
+    let cursors = list
+        .split(',')
+        .map(|cursor| {
+            // 10 is a Windows Directory ID meaning %SystemRoot% (e.g., `C:\Windows`).
+            let cursor = cursor.trim().replace("%10%\\", "");
+            inf.expand(&cursor).replace('\\', "/")
+        })
+        .collect();
+
+    Ok(cursors)
+}
+
+/// Find the cursor file referenced by the `Install.inf` within the package.
+///
+/// The paths in `Install.inf` describe where the cursors are installed on Windows, which
+/// rarely matches where they are in the package, so the file is searched for by name.
+fn locate_cursor(package: &Path, path: &str) -> String {
+    let file_name = file_name(path);
+
+    let Some(found) = find_file(package, file_name) else {
+        warn!("failed to find cursor file: {file_name}");
+        return format!("./{path}");
+    };
+
+    let relative = found.strip_prefix(package).unwrap_or(&found);
+    let parts = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    format!("./{}", parts.join("/"))
+}
+
+/// Search `dir` and its subdirectories for a file named `name` (case-insensitive).
+///
+/// Cursor packs extracted from an archive often keep their files inside of a subdirectory.
+fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    let mut subdirs = Vec::new();
+
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        let path = entry.path();
+
+        if is_dir(&entry) {
+            subdirs.push(path);
+        } else if entry
+            .file_name()
+            .to_str()
+            .is_some_and(|file_name| file_name.eq_ignore_ascii_case(name))
+        {
+            return Some(path);
+        }
+    }
+
+    subdirs.sort();
+    subdirs.iter().find_map(|subdir| find_file(subdir, name))
+}
+
+/// Whether `entry` is a directory, not following symlinks (which may form a loop).
+fn is_dir(entry: &fs::DirEntry) -> bool {
+    entry.file_type().is_ok_and(|file_type| file_type.is_dir())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_are_standard_names() {
+        for &(role, name) in ROLES {
+            assert_eq!(names::canonical(name), Some(name), "role: {role}");
+        }
+
+        for &(name, _) in HINTS {
+            assert_eq!(names::canonical(name), Some(name));
+        }
+    }
+
+    #[test]
+    fn guess_roles() {
+        let cases = [
+            ("Busy.ani", Some("wait")),
+            ("Working.ani", Some("progress")),
+            ("Link Select.ani", Some("pointer")),
+            ("Help Select.ani", Some("help")),
+            ("Normal Select.ani", Some("default")),
+            ("Handwriting.ani", Some("pencil")),
+            ("Diagonal1.ani", Some("nwse-resize")),
+            ("aero_nesw.cur", Some("nesw-resize")),
+            ("aero_up.cur", Some("up-arrow")),
+            ("Text.ani", Some("text")),
+            ("Sparkles.ani", None),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(guess_role(name), expected, "{name}");
+        }
+
+        assert_eq!(guess_role("MyBusyCursor.ani"), None);
+        assert_eq!(guess_role_fuzzy("MyBusyCursor.ani"), Some("wait"));
+        assert_eq!(guess_role_fuzzy("LinkSelect2.ani"), Some("pointer"));
+        assert_eq!(guess_role_fuzzy("Snowman.ani"), None);
+    }
+
+    #[test]
+    fn roles_without_scheme() {
+        let inf = Inf::parse(
+            r#"
+            [Strings]
+            SCHEME_NAME = "Theme"
+            pointer = "Normal.ani"
+            link = "Link.ani"
+            busy = "Busy.ani"
+            other = "Sparkles.ani"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            assign_roles(&inf, None),
+            [
+                ("default", "Normal.ani".to_owned()),
+                ("pointer", "Link.ani".to_owned()),
+                ("wait", "Busy.ani".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn review_cursors() {
+        let cursor = |name: &str, input: &str| TemplateCursor {
+            name: name.to_owned(),
+            input: input.to_owned(),
+            aliases: names::aliases(name),
+        };
+        let template = Template {
+            theme: "Pack".to_owned(),
+            version: "0.1.0",
+            cursors: vec![
+                cursor("wait", "./Busy.ani"),
+                cursor("pointer", "./Link.ani"),
+                cursor("help", "./Help.ani"),
+            ],
+            unmatched: vec!["./Sparkles.ani".to_owned(), "./Extra.ani".to_owned()],
+        };
+
+        // Keep the theme name, accept `wait`, rename `pointer` to an alias (then to a name that
+        // is taken, which is asked again), skip `help`, name an unmatched cursor with a custom
+        // name and leave the last one to the end of the input.
+        let answers = "\n\nwatch\nhand2\n-\nsparkles\ny\n";
+        let mut output = Vec::new();
+        let template = review(template, &mut answers.as_bytes(), &mut output).unwrap();
+
+        let reviewed = template
+            .cursors
+            .iter()
+            .map(|cursor| (cursor.name.as_str(), cursor.input.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(template.theme, "Pack");
+        assert_eq!(
+            reviewed,
+            [
+                ("wait", "./Busy.ani"),
+                ("pointer", "./Link.ani"),
+                ("sparkles", "./Sparkles.ani"),
+            ]
+        );
+        assert!(template.unmatched.is_empty());
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("wait is already used by another cursor"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loops() {
+        let temp = crate::test_dir();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("pack")).unwrap();
+        fs::write(dir.join("pack/Busy.ani"), b"").unwrap();
+        std::os::unix::fs::symlink(dir, dir.join("pack/loop")).unwrap();
+
+        let mut files = Vec::new();
+        find_cursor_files(dir, &mut files);
+        assert_eq!(files, [dir.join("pack/Busy.ani")]);
+        assert_eq!(find_file(dir, "busy.ani"), Some(dir.join("pack/Busy.ani")));
+        assert_eq!(find_file(dir, "install.inf"), None);
+    }
+}
@@ -0,0 +1,156 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use ani::de::{jiffies_to_ms_f64, Ani, Frame, FrameSummary, Header, Metadata};
+use anyhow::Context as _;
+use colored::Colorize as _;
+
+use crate::commands::build::{decode_quietly, Mode};
+use crate::commands::Run;
+use crate::context::Context;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct Inspect {
+    /// The cursor to inspect (`.ani`, `.cur`, `.ico` or `.png`).
+    input: PathBuf,
+
+    /// Print the report as JSON, for use by other tools.
+    #[clap(long)]
+    json: bool,
+
+    /// How forgiving to be of input that doesn't follow the ANI file format specification.
+    #[clap(long, value_enum, default_value_t)]
+    mode: Mode,
+}
+
+impl Run for Inspect {
+    fn run(&self, _ctx: &mut Context) -> anyhow::Result<()> {
+        let data = fs::read(&self.input)
+            .with_context(|| format!("failed to read file: {}", self.input.display()))?;
+        let ani = decode_quietly(&data, self.mode)?;
+        let report = Report::new(&ani);
+
+        let mut stdout = io::stdout().lock();
+
+        if self.json {
+            serde_json::to_writer_pretty(&mut stdout, &report)
+                .context("failed to serialize report")?;
+            writeln!(stdout)?;
+        } else {
+            report.write(&mut stdout)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Everything known about a decoded cursor.
+#[derive(Debug, serde::Serialize)]
+struct Report {
+    /// The `INFO` subchunks (e.g., `INAM` for the title), keyed by identifier.
+    metadata: Metadata,
+    header: Header,
+    frames: Vec<FrameSummary>,
+    rates: Option<Vec<u32>>,
+    sequence: Option<Vec<u32>>,
+    warnings: Vec<String>,
+    issues: Vec<String>,
+}
+
+impl Report {
+    fn new(ani: &Ani) -> Self {
+        Self {
+            metadata: ani.metadata().cloned().unwrap_or_default(),
+            header: *ani.header(),
+            frames: ani.frames().iter().map(Frame::summary).collect(),
+            rates: ani.rates().map(<[u32]>::to_vec),
+            sequence: ani.sequence().map(<[u32]>::to_vec),
+            warnings: ani.warnings().iter().map(ToString::to_string).collect(),
+            issues: ani.validate().iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// Write the report as a human-readable table.
+    fn write<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let header = &self.header;
+
+        if !self.metadata.fields().is_empty() {
+            writeln!(w, "{}", "Metadata".bold())?;
+            for (identifier, value) in self.metadata.fields() {
+                let identifier = String::from_utf8_lossy(identifier);
+                writeln!(w, "  {identifier:<10} {value}")?;
+            }
+            writeln!(w)?;
+        }
+
+        writeln!(w, "{}", "Header".bold())?;
+        writeln!(w, "  {:<10} {}", "Frames", header.frames())?;
+        writeln!(w, "  {:<10} {}", "Steps", header.steps())?;
+        writeln!(
+            w,
+            "  {:<10} {} ({:.0}ms)",
+            "JIF rate",
+            header.jif_rate(),
+            jiffies_to_ms_f64(header.jif_rate())
+        )?;
+        let flags = header
+            .flags()
+            .iter_names()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+        writeln!(w, "  {:<10} {}", "Flags", flags.join(" | "))?;
+
+        if header.width() != 0 || header.height() != 0 || header.bit_count() != 0 {
+            writeln!(w, "  {:<10} {}x{}", "Size", header.width(), header.height())?;
+            writeln!(w, "  {:<10} {}", "Bit count", header.bit_count())?;
+            writeln!(w, "  {:<10} {}", "Planes", header.planes())?;
+        }
+
+        writeln!(w)?;
+        writeln!(w, "{}", "Frames".bold())?;
+        writeln!(w, "  {:<6} {:<10} Hotspot", "#", "Size")?;
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            for image in frame.images() {
+                let size = format!("{}x{}", image.width(), image.height());
+                let hotspot = image.hotspot().map_or_else(
+                    || "-".to_owned(),
+                    |hotspot| format!("{}, {}", hotspot.x(), hotspot.y()),
+                );
+                writeln!(w, "  {i:<6} {size:<10} {hotspot}")?;
+            }
+        }
+
+        writeln!(w)?;
+        writeln!(w, "  {:<10} {}", "Rates", join(self.rates.as_deref()))?;
+        writeln!(w, "  {:<10} {}", "Sequence", join(self.sequence.as_deref()))?;
+
+        for (title, messages) in [("Warnings", &self.warnings), ("Issues", &self.issues)] {
+            if messages.is_empty() {
+                continue;
+            }
+
+            writeln!(w)?;
+            writeln!(w, "{}", title.bold().yellow())?;
+            for message in messages {
+                writeln!(w, "  {message}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn join(values: Option<&[u32]>) -> String {
+    values.map_or_else(
+        || "-".to_owned(),
+        |values| {
+            values
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        },
+    )
+}
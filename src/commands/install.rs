@@ -1,60 +1,265 @@
-use std::io::Write as _;
-use std::path::Path;
-use std::process::{Command, Stdio};
-use std::{env, io};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Write as _};
+use std::path::{Path, PathBuf};
 
-use anyhow::Context as _;
+use anyhow::{bail, Context as _};
 use colored::Colorize;
+use tracing::info;
 
-use crate::commands::build::{symlink, Build};
+use crate::commands::build::{Build, Format, Mode, Selection};
 use crate::commands::init::Init;
+use crate::commands::uninstall::is_generated_theme;
 use crate::commands::Run;
-use crate::config::Config;
 use crate::context::Context;
-use crate::package::Package;
+use crate::desktop::{self, Desktop};
+use crate::exit;
 
 #[derive(Debug, Clone, Default, clap::Args)]
 pub struct Install {
+    /// How forgiving to be of input that doesn't follow the ANI file format specification.
+    #[clap(long, value_enum, default_value_t)]
+    mode: Mode,
+
+    /// Symlink the theme instead of copying it (the theme breaks if the package is removed).
+    #[clap(long, conflicts_with = "prefix")]
+    symlink: bool,
+
+    /// Replace an installed theme with the same name even if it doesn't look like it was
+    /// installed by ani-to-xcursor.
     #[clap(long)]
-    strict: bool,
+    force: bool,
+
+    #[clap(flatten)]
+    selection: Selection,
+
+    #[clap(flatten)]
+    location: Location,
+
+    #[clap(flatten)]
+    activation: Activation,
+
+    /// How to report the result of the installation.
+    #[clap(long, value_enum, default_value_t)]
+    format: Format,
+}
+
+/// Where themes are installed.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Location {
+    /// Install for every user, in `/usr/share/icons` (usually requires root).
+    #[clap(long, conflicts_with = "prefix")]
+    system: bool,
+
+    /// Install in `<PREFIX>/share/icons` (e.g., `$pkgdir/usr` when packaging the theme).
+    #[clap(long)]
+    prefix: Option<PathBuf>,
+}
+
+impl Location {
+    /// The directory the theme named `theme_name` is installed to.
+    pub fn theme_path(&self, theme_name: &str) -> anyhow::Result<PathBuf> {
+        Ok(self.icons_dir()?.join(theme_name))
+    }
+
+    /// The directory themes are installed to.
+    pub fn icons_dir(&self) -> anyhow::Result<PathBuf> {
+        let mut path = if let Some(ref prefix) = self.prefix {
+            prefix.join("share")
+        } else if self.system {
+            PathBuf::from("/usr/share")
+        } else {
+            dirs::data_dir().context("failed to get data directory")?
+        };
+
+        path.push("icons");
+        Ok(path)
+    }
 }
 
 impl Run for Install {
     fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
-        if ctx.package.is_none() {
-            let current_dir = env::current_dir().context("failed to get current directory")?;
-            ctx.package = Some(Package::new(current_dir));
+        if !ctx.load_package()?.config().exists() {
+            Init::new().run(&mut ctx.clone())?;
         }
-        let package = ctx.package.as_ref().unwrap();
 
-        if !package.config().exists() {
-            Init::new().run(&mut ctx.clone())?;
+        let build = Build::new(self.mode, self.format).selection(self.selection.clone());
+        let mut report = match build.build(ctx) {
+            Ok(report) => report,
+            Err(err) if self.format == Format::Json => {
+                exit::print_json(&err)?;
+                return Err(err);
+            }
+            Err(err) => return Err(err),
+        };
+
+        if let Some(err) = report.error() {
+            report.print()?;
+            return Err(err);
         }
 
-        if ctx.config.is_none() {
-            let path = package.config();
-            ctx.config = Some(Config::from_file(&path)?);
+        // Everything that was built is installed, so variants and profiles can be switched to
+        // like any other theme.
+        for theme in &mut report.themes {
+            let theme_output = self.location.theme_path(&theme.theme)?;
+            install_theme(
+                &theme.output,
+                &theme_output,
+                &theme.theme,
+                self.symlink,
+                self.force,
+            )?;
+
+            if self.format == Format::Text {
+                let mut stderr = io::stderr();
+                writeln!(
+                    stderr,
+                    "{}",
+                    format!("Successfully installed theme: {}", theme.theme)
+                        .bold()
+                        .green()
+                )?;
+            }
+
+            theme.installed = Some(theme_output);
         }
-        let config = ctx.config.as_ref().unwrap();
 
-        let theme_input = package.build().theme().as_path().to_owned();
-        let theme_name = config.theme().to_owned();
+        if self.format == Format::Json {
+            report.print()?;
+        }
 
-        Build::new(self.strict).run(ctx)?;
+        // The first theme is the base theme, or the one selected with `--theme` or `--profile`.
+        let Some(theme_name) = report.themes.first().map(|theme| theme.theme.as_str()) else {
+            return Ok(());
+        };
 
-        install_theme(&theme_input, &theme_name)?;
-        print_install_instructions(&theme_name)?;
+        if self.activation.apply {
+            self.activation.run(theme_name)?;
+        } else if self.format == Format::Text && self.location.prefix.is_none() {
+            // Packaged themes are set by the user after installing the package.
+            print_install_instructions(theme_name)?;
+        }
 
         Ok(())
     }
 }
 
-fn install_theme(theme_input: &Path, theme_name: &str) -> anyhow::Result<()> {
-    let mut theme_output = dirs::data_dir().context("failed to get data directory")?;
-    theme_output.extend(["icons", theme_name]);
+fn install_theme(
+    theme_input: &Path,
+    theme_output: &Path,
+    theme_name: &str,
+    link: bool,
+    force: bool,
+) -> anyhow::Result<()> {
+    if let Some(parent) = theme_output.parent() {
+        check_writable(parent)?;
+    }
+
+    remove_existing(theme_output, theme_name, force)?;
+
+    if link {
+        xcursor::symlink(theme_input, theme_output)
+            .with_context(|| format!("failed to create symlink to {}", theme_output.display()))?;
+
+        return Ok(());
+    }
 
-    symlink(theme_input, &theme_output)
-        .with_context(|| format!("failed to create symlink to {}", theme_output.display()))?;
+    copy_theme(theme_input, theme_input, theme_output)
+        .with_context(|| format!("failed to copy theme to {}", theme_output.display()))?;
+    info!("copied theme: {:#}", theme_output.display());
+
+    Ok(())
+}
+
+/// Make sure themes can be installed in (and removed from) `dir`, creating it if needed, so a
+/// lack of permissions is reported before anything is changed.
+pub fn check_writable(dir: &Path) -> anyhow::Result<()> {
+    let probe = dir.join(".ani-to-xcursor");
+    let result = fs::create_dir_all(dir).and_then(|()| File::create(&probe));
+
+    match result {
+        Ok(_) => {
+            _ = fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => bail!(
+            "permission denied: {}; re-run as root (e.g., with sudo)",
+            dir.display()
+        ),
+        Err(err) => Err(err).with_context(|| format!("failed to write to {}", dir.display())),
+    }
+}
+
+/// Remove a previous installation of the theme, whether it was symlinked or copied.
+///
+/// Directories are only removed if they look like a theme built by ani-to-xcursor (see
+/// [`is_generated_theme`]), unless `force` is set, so an unrelated theme with the same name is
+/// never replaced by accident.
+fn remove_existing(theme_output: &Path, theme_name: &str, force: bool) -> anyhow::Result<()> {
+    let Ok(metadata) = fs::symlink_metadata(theme_output) else {
+        return Ok(());
+    };
+
+    if metadata.is_dir() {
+        if !force && !is_generated_theme(theme_output, theme_name) {
+            bail!(
+                "refusing to replace {} because it doesn't look like a theme built by \
+                ani-to-xcursor; use --force to replace it anyway",
+                theme_output.display()
+            );
+        }
+
+        fs::remove_dir_all(theme_output)
+    } else {
+        fs::remove_file(theme_output)
+    }
+    .context("failed to remove existing theme")?;
+    info!("removed existing theme: {:#}", theme_output.display());
+
+    Ok(())
+}
+
+/// Recursively copy the theme at `root` into `output`.
+///
+/// Aliases point to other cursors inside of the theme, so they are recreated as relative
+/// symlinks to keep the copy self-contained (older builds linked them by their absolute path).
+/// Any other symlink (e.g., a cursor linked into `build/frames` by older builds) is copied as a
+/// regular file.
+pub fn copy_theme(root: &Path, input: &Path, output: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(output).context("failed to create directory")?;
+
+    for entry in fs::read_dir(input).context("failed to read directory")? {
+        let entry = entry.context("failed to read directory entry")?;
+        let source = entry.path();
+        let destination = output.join(entry.file_name());
+        let file_type = entry.file_type().context("failed to get file type")?;
+
+        if file_type.is_dir() {
+            copy_theme(root, &source, &destination)?;
+            continue;
+        }
+
+        if file_type.is_symlink() {
+            let target = fs::read_link(&source).context("failed to read symlink")?;
+
+            if target.is_relative() {
+                xcursor::symlink(&target, &destination)
+                    .with_context(|| format!("failed to create {}", destination.display()))?;
+                continue;
+            }
+
+            if target.starts_with(root)
+                && let Some(parent) = source.parent()
+                && let Ok(relative) = target.strip_prefix(parent)
+            {
+                xcursor::symlink(relative, &destination)
+                    .with_context(|| format!("failed to create {}", destination.display()))?;
+                continue;
+            }
+        }
+
+        fs::copy(&source, &destination)
+            .with_context(|| format!("failed to copy file: {}", source.display()))?;
+    }
 
     Ok(())
 }
@@ -63,32 +268,67 @@ fn print_install_instructions(theme_name: &str) -> anyhow::Result<()> {
     let mut stderr = io::stderr();
     let mut stdout = io::stdout();
 
-    writeln!(stderr, "{}", "Successfully installed theme!".bold().green())?;
+    let Some(desktop) = Desktop::detect() else {
+        writeln!(
+            stderr,
+            "{}",
+            "No known theme-setting command detected.".cyan()
+        )?;
+        return Ok(());
+    };
+
     writeln!(
         stderr,
         "{}",
-        "Use the following command to set the cursor theme:".cyan()
+        "Use the following command to set the cursor theme (or pass --apply):".cyan()
     )?;
 
-    let command = if has_command("gsettings") {
-        format!("gsettings set org.gnome.desktop.interface cursor-theme {theme_name:?}")
-    } else if has_command("xfconf-query") {
-        format!("xfconf-query -c xsettings -p /Gtk/CursorThemeName -s {theme_name:?}")
-    } else if has_command("kwriteconfig5") {
-        format!("kwriteconfig5 --file kcminputrc --group Mouse --key cursorTheme {theme_name:?}")
-    } else {
-        "No known theme-setting command detected.".to_owned()
-    };
+    for command in desktop.commands(theme_name, None) {
+        writeln!(stdout, "  {}", desktop::display(&command).bold())?;
+    }
 
-    writeln!(stdout, "  {}", command.bold())?;
     Ok(())
 }
 
-fn has_command(cmd: &str) -> bool {
-    Command::new("sh")
-        .arg("-c")
-        .arg(format!("command -v {cmd}"))
-        .stdout(Stdio::null())
-        .status()
-        .is_ok_and(|status| status.success())
+/// Options for making the installed theme the active cursor theme.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Activation {
+    /// Make the theme the active cursor theme of the desktop environment (if several themes are
+    /// installed, the base theme or the one selected with `--theme` or `--profile`).
+    #[clap(long, conflicts_with = "prefix")]
+    apply: bool,
+
+    /// The cursor size to set along with the theme.
+    #[clap(long, requires = "apply")]
+    size: Option<u32>,
+
+    /// Print the commands `--apply` would run, instead of running them.
+    #[clap(long, requires = "apply")]
+    dry_run: bool,
+}
+
+impl Activation {
+    /// Make the theme the active cursor theme, or only print how with `--dry-run`.
+    fn run(&self, theme_name: &str) -> anyhow::Result<()> {
+        let desktop = Desktop::detect()
+            .context("failed to detect the desktop environment; set the cursor theme manually")?;
+        info!("detected desktop: {desktop:?}");
+
+        let mut stdout = io::stdout();
+
+        for command in desktop.commands(theme_name, self.size) {
+            if self.dry_run {
+                writeln!(stdout, "Would run: {}", desktop::display(&command).bold())?;
+            } else {
+                desktop::run(&command)?;
+            }
+        }
+
+        if !self.dry_run {
+            let mut stderr = io::stderr();
+            writeln!(stderr, "{}", "Cursor theme applied!".bold().green())?;
+        }
+
+        Ok(())
+    }
 }
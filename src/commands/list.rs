@@ -0,0 +1,177 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use colored::Colorize as _;
+use ico::IconImage;
+use serde::Serialize;
+
+use crate::commands::build::{decode_quietly, read_input, Mode};
+use crate::commands::Run;
+use crate::config::{Config, Cursor};
+use crate::context::Context;
+use crate::package::Build;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct List {
+    /// Print the summary as JSON, for use by other tools.
+    #[clap(long)]
+    json: bool,
+
+    /// How forgiving to be of input that doesn't follow the ANI file format specification.
+    #[clap(long, value_enum, default_value_t)]
+    mode: Mode,
+}
+
+impl Run for List {
+    fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let (package, config) = ctx.load()?;
+        let report = Report::new(config, package.build(), self.mode);
+
+        let mut stdout = io::stdout().lock();
+
+        if self.json {
+            serde_json::to_writer_pretty(&mut stdout, &report)
+                .context("failed to serialize report")?;
+            writeln!(stdout)?;
+        } else {
+            report.write(&mut stdout)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The state of every cursor of the package.
+#[derive(Debug, Serialize)]
+struct Report {
+    theme: String,
+
+    /// The directory of `Cursor.toml`, which the inputs are shown relative to.
+    #[serde(skip)]
+    base: PathBuf,
+
+    cursors: Vec<CursorSummary>,
+}
+
+#[derive(Debug, Serialize)]
+struct CursorSummary {
+    name: String,
+    input: PathBuf,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive: Option<PathBuf>,
+
+    /// Whether the theme has the cursor (from the last build).
+    built: bool,
+
+    /// The number of frames of the input, if it could be decoded.
+    frames: Option<usize>,
+
+    /// The sizes the cursor is built in: the configured ones, or else the ones of the input.
+    sizes: Vec<u32>,
+
+    /// The names the cursor is linked as, besides its own.
+    aliases: Vec<String>,
+
+    /// Why the input couldn't be read or decoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Report {
+    fn new(config: &Config, build: &Build, mode: Mode) -> Self {
+        let cursors = config
+            .cursors()
+            .iter()
+            .map(|cursor| CursorSummary::new(config, cursor, build, mode))
+            .collect();
+
+        Self {
+            theme: config.theme().to_owned(),
+            base: config.base().to_owned(),
+            cursors,
+        }
+    }
+
+    fn write<W: Write>(&self, mut w: W) -> io::Result<()> {
+        writeln!(w, "{} ({} cursors)", self.theme.bold(), self.cursors.len())?;
+
+        for cursor in &self.cursors {
+            let status = if cursor.built {
+                "built".green()
+            } else {
+                "not built".yellow()
+            };
+            writeln!(w)?;
+            writeln!(w, "{} ({status})", cursor.name.bold())?;
+
+            let input = match cursor.archive {
+                Some(ref archive) => format!(
+                    "{}:{}",
+                    self.relative(archive).display(),
+                    cursor.input.display()
+                ),
+                None => self.relative(&cursor.input).display().to_string(),
+            };
+            writeln!(w, "  {:<10} {input}", "Input")?;
+
+            if let Some(ref error) = cursor.error {
+                writeln!(w, "  {:<10} {}", "Error", error.red())?;
+            }
+
+            if let Some(frames) = cursor.frames {
+                writeln!(w, "  {:<10} {frames}", "Frames")?;
+            }
+
+            if !cursor.sizes.is_empty() {
+                let sizes = cursor.sizes.iter().map(u32::to_string).collect::<Vec<_>>();
+                writeln!(w, "  {:<10} {}", "Sizes", sizes.join(", "))?;
+            }
+
+            if !cursor.aliases.is_empty() {
+                writeln!(w, "  {:<10} {}", "Aliases", cursor.aliases.join(", "))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `path`, relative to the directory of `Cursor.toml` if it is inside of it.
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.base).unwrap_or(path)
+    }
+}
+
+impl CursorSummary {
+    fn new(config: &Config, cursor: &Cursor, build: &Build, mode: Mode) -> Self {
+        let mut summary = Self {
+            name: cursor.name().to_owned(),
+            input: cursor.input().to_owned(),
+            archive: cursor.archive().map(ToOwned::to_owned),
+            // A link that resolves to a file, left by the last build.
+            built: fs::metadata(build.theme().cursors().join(cursor.name()))
+                .is_ok_and(|metadata| metadata.is_file()),
+            frames: None,
+            sizes: config.frames().sizes().to_vec(),
+            aliases: cursor.aliases().to_vec(),
+            error: None,
+        };
+
+        match read_input(cursor).and_then(|data| decode_quietly(&data, mode)) {
+            Ok(ani) => {
+                summary.frames = Some(ani.frames().len());
+
+                if summary.sizes.is_empty()
+                    && let Some(frame) = ani.frames().first()
+                {
+                    summary.sizes = frame.images().iter().map(IconImage::width).collect();
+                }
+            }
+            Err(err) => summary.error = Some(format!("{err:#}")),
+        }
+
+        summary
+    }
+}
@@ -1,6 +1,18 @@
-mod build;
+pub mod build;
+mod check;
+mod clean;
+pub mod completions;
+mod convert;
+mod doctor;
 mod init;
+mod inspect;
 mod install;
+mod list;
+mod package;
+mod preview;
+mod reverse;
+mod uninstall;
+mod verify;
 
 use crate::context::Context;
 
@@ -16,8 +28,50 @@ pub enum Subcommand {
     /// Generate the custom cursor theme.
     Build(build::Build),
 
-    /// Symlink the cursor theme to `$HOME/.local/share/icons`.
+    /// Copy (or symlink) the cursor theme (and every variant or profile built with it) to the
+    /// user's icons directory, or `/usr/share/icons` / `<PREFIX>/share/icons`.
     Install(install::Install),
+
+    /// Remove an installed cursor theme from the user's icons directory, or `/usr/share/icons` /
+    /// `<PREFIX>/share/icons`.
+    Uninstall(uninstall::Uninstall),
+
+    /// Build the theme and package it as a `.tar.gz` archive, ready to be distributed.
+    Package(package::Package),
+
+    /// Convert a single ANI (or CUR) file into an Xcursor, without a `Cursor.toml`.
+    Convert(convert::Convert),
+
+    /// Convert an Xcursor (or every cursor of a theme) back into Windows cursors.
+    Reverse(reverse::Reverse),
+
+    /// Check `Cursor.toml` for mistakes (e.g., missing inputs or conflicting names) without
+    /// building anything.
+    Check(check::Check),
+
+    /// Remove build artifacts (everything, or only the frames, theme or a cursor).
+    Clean(clean::Clean),
+
+    /// Check the environment for what building and installing themes needs (xcursorgen, a
+    /// valid `Cursor.toml`, ...), with hints on how to fix what is missing.
+    Doctor(doctor::Doctor),
+
+    /// Check that the built theme is complete and every Xcursor is valid.
+    Verify(verify::Verify),
+
+    /// Summarize the cursors of the package: their inputs, frames, sizes and aliases, and
+    /// whether they were built.
+    List(list::List),
+
+    /// Print the completion script for a shell (e.g., to save as
+    /// `/usr/share/bash-completion/completions/ani-to-xcursor`).
+    Completions(completions::Completions),
+
+    /// Print the structure of a cursor file (header, frames, rates and sequence).
+    Inspect(inspect::Inspect),
+
+    /// Render a cursor as an animated GIF (or APNG), to see it without installing the theme.
+    Preview(preview::Preview),
 }
 
 impl Subcommand {
@@ -26,6 +80,18 @@ impl Subcommand {
             Self::Init(ref inner) => inner,
             Self::Build(ref inner) => inner,
             Self::Install(ref inner) => inner,
+            Self::Uninstall(ref inner) => inner,
+            Self::Package(ref inner) => inner,
+            Self::Convert(ref inner) => inner,
+            Self::Reverse(ref inner) => inner,
+            Self::Check(ref inner) => inner,
+            Self::Clean(ref inner) => inner,
+            Self::Doctor(ref inner) => inner,
+            Self::Verify(ref inner) => inner,
+            Self::List(ref inner) => inner,
+            Self::Completions(ref inner) => inner,
+            Self::Inspect(ref inner) => inner,
+            Self::Preview(ref inner) => inner,
         };
 
         handler.run(ctx)
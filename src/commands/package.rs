@@ -0,0 +1,193 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context as _};
+use colored::Colorize as _;
+use tracing::{info, warn};
+
+use crate::commands::build::{Build, Format, Mode};
+use crate::commands::install::copy_theme;
+use crate::commands::Run;
+use crate::config::Config;
+use crate::context::Context;
+
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Package {
+    /// How forgiving to be of input that doesn't follow the ANI file format specification.
+    #[clap(long, value_enum, default_value_t)]
+    mode: Mode,
+
+    /// Where to write the archive [default: `<THEME>.tar.gz` in the package]
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+}
+
+impl Run for Package {
+    fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        Build::new(self.mode, Format::Text).build(ctx)?;
+
+        let (package, config) = ctx.load()?;
+        let theme_name = config.theme();
+        let build = package.build();
+
+        let dist = build.dist();
+        if dist.exists() {
+            fs::remove_dir_all(&dist).context("failed to remove previous dist directory")?;
+        }
+
+        let theme_input = build.theme().as_path();
+        let staged = dist.join(theme_name);
+        copy_theme(theme_input, theme_input, &staged)
+            .with_context(|| format!("failed to copy theme to {}", staged.display()))?;
+        info!("copied theme: {:#}", staged.display());
+
+        let files = [
+            ("README", render_readme(config)),
+            ("LICENSE", render_license(config)),
+        ];
+
+        for (file_name, stub) in files {
+            let own = package.as_path().join(file_name);
+            let output = staged.join(file_name);
+
+            // Files written by the author are more accurate than anything we can generate.
+            if own.is_file() {
+                fs::copy(&own, &output)
+                    .with_context(|| format!("failed to copy file: {}", own.display()))?;
+            } else {
+                fs::write(&output, stub)
+                    .with_context(|| format!("failed to create file: {}", output.display()))?;
+            }
+            info!("created file: {:#}", output.display());
+        }
+
+        let output = self
+            .output
+            .clone()
+            .unwrap_or_else(|| package.as_path().join(format!("{theme_name}.tar.gz")));
+        create_tarball(&dist, theme_name, &output)?;
+
+        let mut stderr = io::stderr();
+        writeln!(
+            stderr,
+            "{}",
+            format!("Successfully packaged theme: {}", output.display())
+                .bold()
+                .green()
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Render a README describing the theme and how to install it.
+fn render_readme(config: &Config) -> String {
+    let theme_name = config.theme();
+    let metadata = config.metadata();
+    let mut contents = format!("# {theme_name}\n\n");
+
+    if let Some(comment) = config.index_theme().comment() {
+        _ = writeln!(contents, "{comment}\n");
+    }
+
+    match metadata.author() {
+        Some(author) => _ = writeln!(contents, "A cursor theme by {author}."),
+        None => contents.push_str("A cursor theme.\n"),
+    }
+
+    if let Some(url) = metadata.url() {
+        _ = writeln!(contents, "\nOriginal cursors: {url}");
+    }
+
+    _ = write!(
+        contents,
+        "\n## Installation\n\n\
+        Extract the archive into `~/.local/share/icons` (for the current user) or\n\
+        `/usr/share/icons` (for every user), then select {theme_name} as the cursor theme in\n\
+        the settings of your desktop environment.\n"
+    );
+
+    contents
+}
+
+/// Render a LICENSE stating who the cursors belong to and how they may be used.
+fn render_license(config: &Config) -> String {
+    let metadata = config.metadata();
+    let mut contents = format!("{}\n", config.theme());
+
+    if let Some(author) = metadata.author() {
+        _ = writeln!(contents, "Copyright (c) {author}");
+    }
+
+    if let Some(license) = metadata.license() {
+        _ = writeln!(contents, "\nThese cursors are licensed under {license}.");
+    } else {
+        warn!("no license set in [metadata]; the packaged LICENSE file will not grant any rights");
+        contents.push_str(
+            "\nNo license was specified for these cursors. Ask the author for permission \
+            before redistributing them.\n",
+        );
+    }
+
+    contents
+}
+
+/// Archive the directory `theme_name` inside `dir` as a gzipped tarball at `output`.
+fn create_tarball(dir: &Path, theme_name: &str, output: &Path) -> anyhow::Result<()> {
+    let status = Command::new("tar")
+        .arg("--create")
+        .arg("--gzip")
+        .arg("--file")
+        .arg(output)
+        .arg("--directory")
+        .arg(dir)
+        .arg(theme_name)
+        .status()
+        .context("failed to execute tar")?;
+
+    match status.code() {
+        Some(0) => {
+            info!("created archive: {:#}", output.display());
+            Ok(())
+        }
+        Some(code) => Err(anyhow!("process failed with exit code: {code}")),
+        None => Err(anyhow!("process terminated due to signal")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stubs() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [index_theme]
+            comment = "Animated cursors"
+
+            [metadata]
+            author = "Jane Doe"
+            license = "CC-BY-SA-4.0"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+        "#
+        .parse()
+        .unwrap();
+
+        let readme = render_readme(&config);
+        assert!(readme.starts_with("# Theme\n\nAnimated cursors\n\nA cursor theme by Jane Doe.\n"));
+        assert!(!readme.contains("Original cursors"));
+
+        assert_eq!(
+            render_license(&config),
+            "Theme\nCopyright (c) Jane Doe\n\nThese cursors are licensed under CC-BY-SA-4.0.\n"
+        );
+    }
+}
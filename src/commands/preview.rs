@@ -0,0 +1,218 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write as _};
+use std::path::PathBuf;
+
+use ani::de::Ani;
+use anyhow::{bail, Context as _};
+use colored::Colorize as _;
+use ico::IconImage;
+use tracing::info;
+
+use crate::commands::build::{decode, Mode};
+use crate::commands::Run;
+use crate::config::{Animation, Filter};
+use crate::context::Context;
+use crate::scale;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct Preview {
+    /// The cursor to preview (`.ani`, `.cur`, `.ico` or `.png`).
+    input: PathBuf,
+
+    /// Where to write the animation: a `.gif` or an animated `.png` (APNG).
+    #[clap(short, long)]
+    output: PathBuf,
+
+    /// The size to render the cursor at [default: the largest size available]
+    #[clap(long)]
+    size: Option<u32>,
+
+    /// How forgiving to be of input that doesn't follow the ANI file format specification.
+    #[clap(long, value_enum, default_value_t)]
+    mode: Mode,
+}
+
+/// The format of the rendered animation, chosen by the output's file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Gif,
+    Apng,
+}
+
+impl Run for Preview {
+    fn run(&self, _ctx: &mut Context) -> anyhow::Result<()> {
+        let extension = self
+            .output
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        let format = match extension.as_deref() {
+            Some("gif") => Format::Gif,
+            Some("png" | "apng") => Format::Apng,
+            _ => bail!("expected output to end in .gif or .png"),
+        };
+
+        let data = fs::read(&self.input)
+            .with_context(|| format!("failed to read file: {}", self.input.display()))?;
+        let ani = decode(&data, self.mode)?;
+        let frames = render(&ani, self.size)?;
+
+        let file = File::create(&self.output)
+            .with_context(|| format!("failed to create file: {}", self.output.display()))?;
+        let writer = BufWriter::new(file);
+
+        match format {
+            Format::Gif => write_gif(writer, &frames)?,
+            Format::Apng => write_apng(writer, &frames)?,
+        }
+
+        let mut stderr = io::stderr();
+        writeln!(
+            stderr,
+            "{}",
+            format!("Successfully created preview: {}", self.output.display())
+                .bold()
+                .green()
+        )?;
+
+        Ok(())
+    }
+}
+
+/// A single frame of the rendered animation.
+struct Rendered {
+    rgba: Vec<u8>,
+    duration: u32,
+}
+
+/// The rendered animation, one entry per step; every frame has the same dimensions.
+struct Frames {
+    width: u32,
+    height: u32,
+    steps: Vec<Rendered>,
+}
+
+/// Lay out each step of the animation on a canvas that fits every frame.
+fn render(ani: &Ani, size: Option<u32>) -> anyhow::Result<Frames> {
+    let images = ani
+        .frames()
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let largest = frame
+                .images()
+                .iter()
+                .max_by_key(|image| image.width())
+                .with_context(|| format!("frame {i} has no images"))?;
+
+            let Some(size) = size else {
+                return Ok(largest.clone());
+            };
+
+            if let Some(image) = frame.images().iter().find(|image| image.width() == size) {
+                return Ok(image.clone());
+            }
+
+            info!("resampling frame {i} to {size}x{size}");
+            scale::resize(largest, size, Filter::default())
+        })
+        .collect::<anyhow::Result<Vec<IconImage>>>()?;
+
+    let width = images.iter().map(IconImage::width).max().unwrap_or(1);
+    let height = images.iter().map(IconImage::height).max().unwrap_or(1);
+    let min_duration = Animation::default().min_frame_duration();
+
+    let steps = xcursor::steps(ani, Animation::default().speed())?
+        .into_iter()
+        .map(|step| Rendered {
+            rgba: canvas(&images[step.frame], width, height),
+            duration: step.duration.max(min_duration),
+        })
+        .collect();
+
+    Ok(Frames {
+        width,
+        height,
+        steps,
+    })
+}
+
+/// Copy `image` onto a transparent canvas, aligned to the top-left corner.
+fn canvas(image: &IconImage, width: u32, height: u32) -> Vec<u8> {
+    let (width, image_width) = (width as usize, image.width() as usize);
+    let mut rgba = vec![0; width * height as usize * 4];
+
+    for (y, row) in image.rgba_data().chunks_exact(image_width * 4).enumerate() {
+        let start = y * width * 4;
+        rgba[start..start + row.len()].copy_from_slice(row);
+    }
+
+    rgba
+}
+
+fn write_gif<W: io::Write>(writer: W, frames: &Frames) -> anyhow::Result<()> {
+    let too_large = || anyhow::anyhow!("GIF images can be at most 65535 pixels wide");
+    let width = u16::try_from(frames.width).map_err(|_| too_large())?;
+    let height = u16::try_from(frames.height).map_err(|_| too_large())?;
+
+    let mut encoder =
+        gif::Encoder::new(writer, width, height, &[]).context("failed to create GIF")?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .context("failed to create GIF")?;
+
+    for step in &frames.steps {
+        let mut rgba = step.rgba.clone();
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut rgba, 10);
+        // GIF delays are in hundredths of a second.
+        frame.delay = u16::try_from(step.duration.div_ceil(10)).unwrap_or(u16::MAX);
+        frame.dispose = gif::DisposalMethod::Background;
+
+        encoder
+            .write_frame(&frame)
+            .context("failed to write GIF frame")?;
+    }
+
+    Ok(())
+}
+
+fn write_apng<W: io::Write>(writer: W, frames: &Frames) -> anyhow::Result<()> {
+    let steps = u32::try_from(frames.steps.len()).context("too many frames")?;
+
+    let mut encoder = png::Encoder::new(writer, frames.width, frames.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(steps, 0)
+        .context("failed to create APNG")?;
+
+    let mut writer = encoder.write_header().context("failed to create APNG")?;
+
+    for step in &frames.steps {
+        let duration = u16::try_from(step.duration).unwrap_or(u16::MAX);
+        writer
+            .set_frame_delay(duration, 1000)
+            .context("failed to write APNG frame")?;
+        writer
+            .write_image_data(&step.rgba)
+            .context("failed to write APNG frame")?;
+    }
+
+    writer.finish().context("failed to write APNG")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canvas_pads_smaller_frames() {
+        let image = IconImage::from_rgba_data(1, 1, vec![0xff; 4]);
+        let rgba = canvas(&image, 2, 2);
+
+        assert_eq!(rgba.len(), 2 * 2 * 4);
+        assert_eq!(&rgba[..4], &[0xff; 4]);
+        assert!(rgba[4..].iter().all(|&byte| byte == 0));
+    }
+}
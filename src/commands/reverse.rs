@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::{fs, path};
+
+use ani::de::ms_to_jiffies;
+use ani::ser::AniBuilder;
+use anyhow::{bail, ensure, Context as _};
+use colored::Colorize as _;
+use ico::{IconDir, IconDirEntry, IconImage, ResourceType};
+use tracing::{error, info, warn};
+
+use crate::commands::Run;
+use crate::context::Context;
+use crate::windows;
+use crate::xcursor_file::{Image, Xcursor};
+
+/// The largest image a Windows cursor can hold.
+const MAX_SIZE: u32 = 256;
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct Reverse {
+    /// The Xcursor file, or theme directory, to convert.
+    input: PathBuf,
+
+    /// Where to write the cursor, or the directory to write the cursors of a theme to
+    /// [default: the input's name, in the current directory]
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl Run for Reverse {
+    fn run(&self, _ctx: &mut Context) -> anyhow::Result<()> {
+        let input = path::absolute(&self.input).context("failed to resolve input path")?;
+
+        let output = if input.is_dir() {
+            self.reverse_theme(&input)?
+        } else {
+            let output = if let Some(ref output) = self.output {
+                output.clone()
+            } else {
+                let name = input.file_name().context("expected a file name")?;
+                PathBuf::from(name)
+            };
+
+            reverse_file(&input, &output, self.output.is_none())?
+        };
+
+        let mut stderr = io::stderr();
+        writeln!(
+            stderr,
+            "{}",
+            format!("Successfully created: {}", output.display())
+                .bold()
+                .green()
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Reverse {
+    /// Convert every cursor of the theme at `input` (or of the `cursors` directory itself), and
+    /// write an `Install.inf` that installs them as a cursor scheme on Windows.
+    ///
+    /// Aliases are symlinks to other cursors of the theme, so they are skipped.
+    fn reverse_theme(&self, input: &Path) -> anyhow::Result<PathBuf> {
+        let (theme_dir, cursors_dir) = if input.join("cursors").is_dir() {
+            (input, input.join("cursors"))
+        } else if input.ends_with("cursors") {
+            (input.parent().unwrap_or(input), input.to_owned())
+        } else {
+            (input, input.to_owned())
+        };
+
+        let theme = theme_dir
+            .file_name()
+            .context("expected a directory name")?
+            .to_string_lossy();
+
+        let output = if let Some(ref output) = self.output {
+            output.clone()
+        } else {
+            PathBuf::from(format!("{theme}-windows"))
+        };
+        fs::create_dir_all(&output).context("failed to create output directory")?;
+
+        let mut entries = fs::read_dir(&cursors_dir)
+            .context("failed to read directory")?
+            .collect::<Result<Vec<_>, _>>()
+            .context("failed to read directory entry")?;
+        entries.sort_by_key(fs::DirEntry::file_name);
+
+        let mut failures = 0;
+        let mut converted = Vec::new();
+        let mut aliases = Vec::new();
+
+        for entry in entries {
+            let path = entry.path();
+            let file_type = entry.file_type().context("failed to get file type")?;
+
+            if file_type.is_symlink() {
+                info!("skipping alias: {}", path.display());
+
+                if let Ok(target) = fs::read_link(&path)
+                    && let Some(target) = target.file_name()
+                {
+                    aliases.push((entry.file_name(), target.to_owned()));
+                }
+                continue;
+            }
+
+            if !file_type.is_file() {
+                continue;
+            }
+
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+
+            match reverse_file(&path, &output.join(name), true) {
+                Ok(file) => {
+                    if let Some(file_name) = file.file_name() {
+                        converted.push((
+                            name.to_string_lossy().into_owned(),
+                            file_name.to_string_lossy().into_owned(),
+                        ));
+                    }
+                }
+                Err(err) => {
+                    error!("failed to convert {}: {err:#}", path.display());
+                    failures += 1;
+                }
+            }
+        }
+
+        if failures > 0 {
+            bail!("failed to convert ({failures}) cursors");
+        }
+
+        // Aliases share the file of the cursor they link to, so they can fill a role too.
+        for (alias, target) in aliases {
+            let target = target.to_string_lossy();
+            if let Some((_, file_name)) = converted.iter().find(|(name, _)| *name == target) {
+                let file_name = file_name.clone();
+                converted.push((alias.to_string_lossy().into_owned(), file_name));
+            }
+        }
+
+        let install_inf = output.join("Install.inf");
+        let files = windows::assign_roles(&converted);
+        fs::write(&install_inf, windows::render_install_inf(&theme, &files))
+            .context("failed to create Install.inf file")?;
+        info!("created file: {:#}", install_inf.display());
+
+        Ok(output)
+    }
+}
+
+/// Convert the Xcursor at `input` into an ANI file (or a CUR file, if it isn't animated).
+///
+/// With `add_extension`, the matching extension is added to `output`.
+fn reverse_file(input: &Path, output: &Path, add_extension: bool) -> anyhow::Result<PathBuf> {
+    let xcursor = Xcursor::open(input)?;
+    let name = input
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let (data, extension) = to_windows(&xcursor, &name)?;
+
+    let output = if add_extension {
+        let mut file_name = output.as_os_str().to_owned();
+        file_name.push(format!(".{extension}"));
+        PathBuf::from(file_name)
+    } else {
+        output.to_owned()
+    };
+
+    fs::write(&output, data)
+        .with_context(|| format!("failed to write file: {}", output.display()))?;
+    info!("created file: {:#}", output.display());
+
+    Ok(output)
+}
+
+/// Encode an Xcursor as an ANI file, or as a CUR file if it only has one frame, returning the
+/// data and its file extension.
+fn to_windows(xcursor: &Xcursor, name: &str) -> anyhow::Result<(Vec<u8>, &'static str)> {
+    // Images are grouped by nominal size, each size holding every frame of the animation.
+    let mut sizes = BTreeMap::<u32, Vec<&Image>>::new();
+    for image in xcursor.images() {
+        sizes.entry(image.size()).or_default().push(image);
+    }
+
+    sizes.retain(|&size, _| {
+        let fits = size <= MAX_SIZE;
+        if !fits {
+            warn!("skipping {size}x{size} images, which are too large for a Windows cursor");
+        }
+        fits
+    });
+
+    let frame_count = sizes.values().map(Vec::len).max().unwrap_or_default();
+    ensure!(
+        frame_count > 0,
+        "no images small enough for a Windows cursor"
+    );
+
+    sizes.retain(|&size, images| {
+        let complete = images.len() == frame_count;
+        if !complete {
+            warn!(
+                "skipping {size}x{size} images, which have {} frames instead of {frame_count}",
+                images.len()
+            );
+        }
+        complete
+    });
+
+    let frames = (0..frame_count)
+        .map(|i| {
+            sizes
+                .values()
+                .map(|images| to_icon_image(images[i]))
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if frame_count == 1 {
+        let mut icon_dir = IconDir::new(ResourceType::Cursor);
+        for image in &frames[0] {
+            icon_dir.add_entry(IconDirEntry::encode(image).context("failed to encode image")?);
+        }
+
+        let mut data = Vec::new();
+        icon_dir
+            .write(&mut data)
+            .context("failed to encode cursor")?;
+        return Ok((data, "cur"));
+    }
+
+    // Every size shares the timing of the animation, so the first one is used. Delays are
+    // never rounded down to zero jiffies, so no frame is skipped.
+    let rates = sizes
+        .values()
+        .next()
+        .into_iter()
+        .flatten()
+        .map(|image| ms_to_jiffies(image.delay()).max(1))
+        .collect::<Vec<_>>();
+
+    let mut builder = AniBuilder::new().title(name).jif_rate(rates[0]);
+
+    if rates.iter().any(|&rate| rate != rates[0]) {
+        builder = builder.rates(rates);
+    }
+
+    if let Some(copyright) = xcursor.copyright() {
+        builder = builder.author(copyright);
+    }
+
+    for images in frames {
+        builder = builder.frame(images);
+    }
+
+    let data = builder.encode().context("failed to encode animation")?;
+    Ok((data, "ani"))
+}
+
+fn to_icon_image(image: &Image) -> anyhow::Result<IconImage> {
+    let (x, y) = image.hotspot();
+    let mut icon = IconImage::from_rgba_data(image.width(), image.height(), image.rgba().to_vec());
+    icon.set_cursor_hotspot(Some((
+        u16::try_from(x).context("hotspot out of range")?,
+        u16::try_from(y).context("hotspot out of range")?,
+    )));
+
+    Ok(icon)
+}
+
+#[cfg(test)]
+mod tests {
+    use ani::de::{Ani, Hotspot};
+
+    use super::*;
+
+    fn encode(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn animation() {
+        const IMAGE_TYPE: u32 = 0xfffd_0002;
+        let delays = [50, 100];
+
+        let mut data = b"Xcur".to_vec();
+        data.extend(encode(&[16, 0x1_0000, 2]));
+        data.extend(encode(&[IMAGE_TYPE, 32, 40, IMAGE_TYPE, 32, 80]));
+
+        for delay in delays {
+            data.extend(encode(&[36, IMAGE_TYPE, 32, 1, 1, 1, 1, 0, delay]));
+            data.extend(encode(&[0xff00_00ff]));
+        }
+
+        let xcursor = Xcursor::from_bytes(&data).unwrap();
+        let (data, extension) = to_windows(&xcursor, "wait").unwrap();
+        assert_eq!(extension, "ani");
+
+        let ani = Ani::from_bytes_strict(&data).unwrap();
+        assert_eq!(ani.frames().len(), 2);
+        assert_eq!(ani.rates(), Some(&[3, 6][..]));
+        assert_eq!(ani.frames()[0].hotspot(0), Some(Hotspot::new(1, 0)));
+        assert_eq!(ani.frames()[0].rgba(0), Some(&[0, 0, 0xff, 0xff][..]));
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::Path;
+
+use anyhow::{bail, Context as _};
+use colored::Colorize as _;
+use tracing::info;
+
+use crate::commands::install::{check_writable, Location};
+use crate::commands::Run;
+use crate::config;
+use crate::context::Context;
+use crate::hyprcursor;
+
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Uninstall {
+    /// The name of the theme to remove [default: the theme in `Cursor.toml`]
+    theme: Option<String>,
+
+    /// Remove the theme even if it doesn't look like it was installed by ani-to-xcursor.
+    #[clap(long)]
+    force: bool,
+
+    #[clap(flatten)]
+    location: Location,
+}
+
+impl Run for Uninstall {
+    fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let theme_name = if let Some(ref theme) = self.theme {
+            theme.clone()
+        } else {
+            let (_, config) = ctx.load()?;
+            config.theme().to_owned()
+        };
+
+        config::validate_name("theme", &theme_name)?;
+
+        let path = self.location.theme_path(&theme_name)?;
+        let metadata = fs::symlink_metadata(&path)
+            .with_context(|| format!("theme is not installed: {}", path.display()))?;
+
+        if let Some(parent) = path.parent() {
+            check_writable(parent)?;
+        }
+
+        if metadata.is_symlink() {
+            // Removing the link leaves the build directory it points to untouched.
+            fs::remove_file(&path).context("failed to remove symlink")?;
+        } else if metadata.is_dir() {
+            if !self.force && !is_generated_theme(&path, &theme_name) {
+                bail!(
+                    "refusing to remove {} because it doesn't look like a theme built by \
+                    ani-to-xcursor; use --force to remove it anyway",
+                    path.display()
+                );
+            }
+
+            fs::remove_dir_all(&path).context("failed to remove theme directory")?;
+        } else {
+            bail!("expected a directory or symlink: {}", path.display());
+        }
+        info!("removed: {:#}", path.display());
+
+        let mut stderr = io::stderr();
+        writeln!(
+            stderr,
+            "{}",
+            format!("Successfully uninstalled theme: {theme_name}")
+                .bold()
+                .green()
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Check whether `path` has the layout of a theme generated by the `build` command.
+///
+/// Generated themes contain only an `index.theme` file naming the theme and a `cursors`
+/// directory (plus the hyprcursor `manifest.hl` file and `hyprcursors` directory, if they were
+/// built); anything else suggests the directory belongs to another theme.
+pub fn is_generated_theme(path: &Path, theme_name: &str) -> bool {
+    let Ok(entries) = fs::read_dir(path) else {
+        return false;
+    };
+
+    let only_expected_entries = entries.flatten().all(|entry| {
+        let name = entry.file_name();
+        [
+            "index.theme",
+            "cursors",
+            "manifest.hl",
+            hyprcursor::CURSORS_DIRECTORY,
+        ]
+        .iter()
+        .any(|expected| name == *expected)
+    });
+
+    let index_theme = fs::read_to_string(path.join("index.theme")).unwrap_or_default();
+    let names_theme = index_theme.lines().any(|line| {
+        line.split_once('=')
+            .is_some_and(|(key, value)| key.trim() == "Name" && value.trim() == theme_name)
+    });
+
+    only_expected_entries && names_theme && path.join("cursors").is_dir()
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::Path;
+
+use anyhow::{anyhow, Context as _};
+use colored::Colorize as _;
+
+use crate::commands::Run;
+use crate::context::Context;
+use crate::exit::Failure;
+use crate::xcursor_file::{Image, Xcursor};
+
+/// Cursor names that desktop environments expect every theme to provide.
+///
+/// Each entry lists interchangeable names; the theme only needs to contain one of them.
+const REQUIRED_CURSORS: &[&[&str]] = &[
+    &["default", "left_ptr"],
+    &["text", "xterm"],
+    &["pointer", "hand2"],
+    &["wait", "watch"],
+    &["progress", "left_ptr_watch"],
+    &["help", "question_arrow"],
+    &["crosshair", "cross"],
+];
+
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct Verify;
+
+impl Run for Verify {
+    fn run(&self, ctx: &mut Context) -> anyhow::Result<()> {
+        let (package, config) = ctx.load()?;
+
+        let theme = package.build().theme();
+        let cursors_dir = theme.cursors();
+        let mut report = Report::default();
+
+        report.check(theme.index_theme().is_file(), "index.theme exists");
+
+        for cursor in config.cursors() {
+            let path = cursors_dir.join(cursor.name());
+
+            match Xcursor::open(&path) {
+                Ok(xcursor) => {
+                    let mut sizes = xcursor.images().iter().map(Image::size).collect::<Vec<_>>();
+                    sizes.sort_unstable();
+                    sizes.dedup();
+
+                    let images = xcursor.images().len();
+                    report.pass(format!(
+                        "{} is a valid Xcursor ({images} images, sizes {sizes:?})",
+                        cursor.name()
+                    ));
+                }
+                Err(err) => report.fail(format!("{}: {err:#}", cursor.name())),
+            }
+
+            for alias in cursor.aliases() {
+                let description = format!("alias {alias} -> {}", cursor.name());
+                report.check(resolves(&cursors_dir.join(alias)), description);
+            }
+        }
+
+        for names in REQUIRED_CURSORS {
+            let found = names.iter().any(|name| resolves(&cursors_dir.join(name)));
+            report.check(
+                found,
+                format!("standard cursor {} is present", names.join(" / ")),
+            );
+        }
+
+        report.print()?;
+
+        if report.failures > 0 {
+            return Err(anyhow!(
+                "theme verification failed with ({}) problems",
+                report.failures
+            ))
+            .context(Failure::Verify);
+        }
+
+        Ok(())
+    }
+}
+
+/// Check whether `path` exists, following symbolic links all the way to a regular file.
+fn resolves(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file())
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    checks: Vec<(bool, String)>,
+    failures: usize,
+}
+
+impl Report {
+    fn check(&mut self, passed: bool, description: impl Into<String>) {
+        if !passed {
+            self.failures += 1;
+        }
+
+        self.checks.push((passed, description.into()));
+    }
+
+    fn pass(&mut self, description: impl Into<String>) {
+        self.check(true, description);
+    }
+
+    fn fail(&mut self, description: impl Into<String>) {
+        self.check(false, description);
+    }
+
+    fn print(&self) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+
+        for (passed, description) in &self.checks {
+            let status = if *passed {
+                "PASS".bold().green()
+            } else {
+                "FAIL".bold().red()
+            };
+
+            writeln!(stdout, "{status} {description}")?;
+        }
+
+        let passed = self.checks.len() - self.failures;
+        writeln!(stdout, "\n{passed} passed; {} failed", self.failures)
+    }
+}
@@ -1,41 +1,1246 @@
-use std::fs;
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::{env, fs, iter};
 
-use anyhow::Context as _;
+use ani::de::Hotspot;
+use anyhow::{bail, Context as _};
+pub use xcursor::Compression;
+
+use crate::names;
+use crate::template::{self, Value};
 
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct Config {
     theme: String,
 
+    /// Also link every cursor under the other names of the same standard cursor (e.g., a cursor
+    /// named `hand2` is linked as `pointer`, `pointing_hand`, ...), unless another cursor uses
+    /// that name.
+    #[serde(default = "enabled")]
+    auto_aliases: bool,
+
+    /// Where to build the theme, relative to the package (defaults to `build`).
+    build_dir: Option<PathBuf>,
+
+    /// The `xcursorgen` binary to use: a path, or a name to look up in `PATH` (overridden by
+    /// the `XCURSORGEN` environment variable).
+    xcursorgen: Option<PathBuf>,
+
+    /// Stop building at the first cursor that fails, instead of attempting every cursor and
+    /// reporting the failures at the end (overridden by `build --fail-fast` and `--keep-going`).
+    #[serde(default)]
+    fail_fast: bool,
+
+    #[serde(default)]
+    frames: Frames,
+
+    #[serde(default)]
+    animation: Animation,
+
+    #[serde(default)]
+    index_theme: IndexTheme,
+
+    #[serde(default)]
+    metadata: Metadata,
+
+    /// Color adjustments applied to every cursor (unless a cursor has its own).
+    #[serde(default)]
+    transform: Transform,
+
+    /// A shadow (or outline) drawn under every cursor, unless a cursor opts out.
+    shadow: Option<Shadow>,
+
     #[serde(rename = "cursor")]
     cursors: Vec<Cursor>,
+
+    /// Variants of the theme that share the same cursor definitions.
+    #[serde(default, rename = "profile")]
+    profiles: BTreeMap<String, Profile>,
+
+    /// Sizes of the theme that are built alongside it, each into a theme of its own.
+    #[serde(default, rename = "variant")]
+    variants: BTreeMap<String, Variant>,
+
+    /// The configuration files merged into this one with `include`, in the order they were read.
+    #[serde(skip)]
+    includes: Vec<PathBuf>,
+
+    /// The directory of the configuration file, which relative inputs are resolved against
+    /// (empty for a configuration that wasn't read from a file).
+    #[serde(skip)]
+    base: PathBuf,
+
+    /// The other themes of the package, when it defines several with `[[theme]]` (this
+    /// configuration is the first one).
+    #[serde(skip)]
+    themes: Vec<Config>,
 }
 
 impl FromStr for Config {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        toml::from_str(s).context("failed to parse configuration")
+        let mut table: toml::Table = toml::from_str(s).context("failed to parse configuration")?;
+
+        if table.contains_key("include") {
+            bail!("include is only supported in configuration files");
+        }
+
+        expand_inputs(&mut table)?;
+
+        Self::from_table(table)
     }
 }
 
 impl Config {
+    /// Parse a configuration, which defines either a single theme (`theme = "Name"`), or several
+    /// (`[[theme]]` tables, with a `name` each).
+    ///
+    /// Each `[[theme]]` is merged on top of the rest of the configuration (see [`merge`]), so the
+    /// themes share everything they don't set themselves, such as `[frames]` and the cursors that
+    /// are the same in every theme.
+    fn from_table(mut table: toml::Table) -> anyhow::Result<Self> {
+        let themes = match table.remove("theme") {
+            Some(toml::Value::Array(themes)) => themes,
+            theme => {
+                if let Some(theme) = theme {
+                    table.insert("theme".to_owned(), theme);
+                }
+
+                return Self::from_theme_table(table);
+            }
+        };
+
+        let mut configs = Vec::<Self>::with_capacity(themes.len());
+
+        for theme in themes {
+            let toml::Value::Table(mut theme) = theme else {
+                bail!("theme must be a name or a list of tables");
+            };
+            let Some(toml::Value::String(name)) = theme.remove("name") else {
+                bail!("every [[theme]] must have a name");
+            };
+
+            if configs.iter().any(|config| config.theme == name) {
+                bail!("duplicate theme: {name}");
+            }
+
+            let mut merged = table.clone();
+            merge(&mut merged, theme);
+            merged.insert("theme".to_owned(), toml::Value::String(name.clone()));
+
+            let config =
+                Self::from_theme_table(merged).with_context(|| format!("invalid theme: {name}"))?;
+            configs.push(config);
+        }
+
+        let mut configs = configs.into_iter();
+        let mut config = configs.next().context("expected at least one [[theme]]")?;
+        config.themes = configs.collect();
+        Ok(config)
+    }
+
+    fn from_theme_table(table: toml::Table) -> anyhow::Result<Self> {
+        let mut config: Self = toml::Value::Table(table)
+            .try_into()
+            .context("failed to parse configuration")?;
+        validate_name("theme", &config.theme)?;
+        config.frames.validate()?;
+        config.animation.validate()?;
+        config.index_theme.validate()?;
+        config.transform.validate()?;
+
+        if let Some(ref shadow) = config.shadow {
+            shadow.validate()?;
+        }
+
+        for cursor in &config.cursors {
+            cursor
+                .validate(&config.frames)
+                .with_context(|| format!("invalid cursor: {}", cursor.name))?;
+        }
+
+        for (name, profile) in &config.profiles {
+            validate_name("profile", name)?;
+
+            if let Some(ref theme) = profile.theme {
+                validate_name("theme", theme)
+                    .with_context(|| format!("invalid profile: {name}"))?;
+            }
+
+            profile
+                .validate(&config.cursors)
+                .with_context(|| format!("invalid profile: {name}"))?;
+        }
+
+        for (name, variant) in &config.variants {
+            validate_name("variant", name)?;
+            validate_name("theme", &variant.theme_name(&config.theme, name)?)
+                .with_context(|| format!("invalid variant: {name}"))?;
+
+            variant
+                .validate(&config.frames)
+                .with_context(|| format!("invalid variant: {name}"))?;
+        }
+
+        for cursor in &config.cursors {
+            for name in cursor.variants.iter().flatten() {
+                if !config.variants.contains_key(name) {
+                    bail!("cursor {} uses an unknown variant: {name}", cursor.name);
+                }
+            }
+        }
+
+        if config.auto_aliases {
+            config.add_standard_aliases();
+        }
+
+        if let Some(ref example) = config.index_theme.example
+            && !config
+                .cursors
+                .iter()
+                .any(|cursor| &cursor.name == example || cursor.aliases.contains(example))
+        {
+            bail!("index_theme.example is not the name of a cursor: {example}");
+        }
+
+        // Disabled cursors still claim their names, so enabling one again doesn't change the
+        // aliases of the others.
+        config.cursors.retain(|cursor| cursor.enabled);
+
+        Ok(config)
+    }
+
+    /// Read the configuration file at `path`, along with the files it includes.
+    ///
+    /// Relative inputs are resolved against the directory of `path`, so they don't depend on
+    /// the current directory.
     pub fn from_file(path: &Path) -> anyhow::Result<Self> {
-        let contents = fs::read_to_string(path).context("failed to read configuration file")?;
-        contents.parse()
+        let base = path.parent().unwrap_or(Path::new(""));
+        let mut includes = Vec::new();
+        let mut table = read_table(path, &mut Vec::new(), &mut includes)?;
+        rebase_inputs(&mut table, base);
+
+        let mut config = Self::from_table(table)?;
+        for theme in &mut config.themes {
+            theme.includes.clone_from(&includes);
+            base.clone_into(&mut theme.base);
+        }
+        config.includes = includes;
+        base.clone_into(&mut config.base);
+        Ok(config)
+    }
+
+    /// The configuration files merged into this one with `include`.
+    pub fn includes(&self) -> &[PathBuf] {
+        &self.includes
+    }
+
+    /// The directory of the configuration file (see [`from_file`](Self::from_file)).
+    pub fn base(&self) -> &Path {
+        &self.base
     }
 
     pub fn theme(&self) -> &str {
         &self.theme
     }
 
+    pub fn build_dir(&self) -> Option<&Path> {
+        self.build_dir.as_deref()
+    }
+
+    pub fn xcursorgen(&self) -> Option<&Path> {
+        self.xcursorgen.as_deref()
+    }
+
+    pub const fn fail_fast(&self) -> bool {
+        self.fail_fast
+    }
+
+    pub const fn frames(&self) -> &Frames {
+        &self.frames
+    }
+
+    pub const fn animation(&self) -> &Animation {
+        &self.animation
+    }
+
+    pub const fn index_theme(&self) -> &IndexTheme {
+        &self.index_theme
+    }
+
+    pub const fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// The color adjustments for `cursor`: its own, or else the ones for the whole theme.
+    pub fn transform<'a>(&'a self, cursor: &'a Cursor) -> &'a Transform {
+        cursor.transform.as_ref().unwrap_or(&self.transform)
+    }
+
+    /// The shadow to draw under `cursor`, if any.
+    pub fn shadow(&self, cursor: &Cursor) -> Option<&Shadow> {
+        self.shadow.as_ref().filter(|_| cursor.shadow)
+    }
+
     pub fn cursors(&self) -> &[Cursor] {
         &self.cursors
     }
+
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    pub fn variants(&self) -> impl Iterator<Item = &str> {
+        self.variants.keys().map(String::as_str)
+    }
+
+    /// The names of the other themes defined with `[[theme]]`.
+    pub fn themes(&self) -> impl Iterator<Item = &str> {
+        self.themes.iter().map(Self::theme)
+    }
+
+    /// Return the configuration of the theme `name` of the package.
+    pub fn with_theme(&self, name: &str) -> anyhow::Result<Self> {
+        if self.theme == name {
+            let mut config = self.clone();
+            config.themes.clear();
+            return Ok(config);
+        }
+
+        self.themes
+            .iter()
+            .find(|theme| theme.theme == name)
+            .cloned()
+            .with_context(|| format!("theme not found: {name}"))
+    }
+
+    /// Add the names of each cursor's standard cursor (see [`names::CURSORS`]) to its aliases.
+    ///
+    /// Names that are already used (by any cursor) are skipped, so configured names always
+    /// win, and the first cursor to claim a name keeps it.
+    fn add_standard_aliases(&mut self) {
+        let mut taken = self
+            .cursors
+            .iter()
+            .flat_map(|cursor| iter::once(&cursor.name).chain(&cursor.aliases))
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        for cursor in &mut self.cursors {
+            let Some(standard) = names::canonical(&cursor.name) else {
+                continue;
+            };
+
+            for &name in iter::once(&standard).chain(names::aliases(standard)) {
+                if taken.insert(name.to_owned()) {
+                    cursor.aliases.push(name.to_owned());
+                }
+            }
+        }
+    }
+
+    /// Leave out the aliases that another cursor already uses (see [`xcursor::claim_aliases`]),
+    /// and return them.
+    pub fn claim_aliases(&mut self) -> Vec<xcursor::AliasCollision> {
+        xcursor::claim_aliases(
+            self.cursors
+                .iter_mut()
+                .map(|cursor| (cursor.name.as_str(), &mut cursor.aliases)),
+        )
+    }
+
+    /// Return a copy of the configuration with the overrides from profile `name` applied.
+    pub fn with_profile(&self, name: &str) -> anyhow::Result<Self> {
+        let profile = self
+            .profiles
+            .get(name)
+            .with_context(|| format!("profile not found: {name}"))?;
+
+        let mut config = self.clone();
+        config.profiles.clear();
+        config.themes.clear();
+        config.theme = profile
+            .theme
+            .clone()
+            .unwrap_or_else(|| format!("{}-{name}", self.theme));
+
+        if let Some(speed) = profile.speed {
+            config.animation.speed = speed;
+        }
+
+        if let Some(ref sizes) = profile.sizes {
+            config.frames.sizes.clone_from(sizes);
+        }
+
+        if let Some(ref transform) = profile.transform {
+            config.transform.clone_from(transform);
+        }
+
+        if let Some(ref shadow) = profile.shadow {
+            config.shadow = Some(shadow.clone());
+        }
+
+        for cursor in &mut config.cursors {
+            let overrides = profile.cursors.get(&cursor.name);
+
+            if let Some(overrides) = overrides
+                && let Some(ref input) = overrides.input
+            {
+                cursor.input.clone_from(input);
+                cursor.archive.clone_from(&overrides.archive);
+            }
+
+            let is_mirrored = names::canonical(&cursor.name)
+                .is_some_and(|standard| names::MIRRORED.contains(&standard));
+
+            if let Some(mirror) = overrides.and_then(|overrides| overrides.mirror) {
+                cursor.mirror = mirror;
+            } else if profile.mirror && is_mirrored {
+                cursor.mirror = true;
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Return a copy of the configuration for variant `name`: the cursors built in that variant,
+    /// in the variant's sizes.
+    pub fn with_variant(&self, name: &str) -> anyhow::Result<Self> {
+        let variant = self
+            .variants
+            .get(name)
+            .with_context(|| format!("variant not found: {name}"))?;
+
+        let mut config = self.clone();
+        config.profiles.clear();
+        config.variants.clear();
+        config.themes.clear();
+        config.theme = variant.theme_name(&self.theme, name)?;
+        config.frames.sizes = variant.sizes(&self.frames.sizes);
+        config.cursors.retain(|cursor| {
+            cursor
+                .variants
+                .as_ref()
+                .is_none_or(|variants| variants.iter().any(|variant| variant == name))
+        });
+
+        Ok(config)
+    }
+
+    /// Return the configuration with only the cursors `keep` returns `true` for (e.g., for
+    /// `build --only`).
+    pub fn with_cursors(&self, keep: impl FnMut(&Cursor) -> bool) -> Self {
+        let mut config = self.clone();
+        config.cursors.retain(keep);
+        config
+    }
+}
+
+const fn enabled() -> bool {
+    true
+}
+
+/// Check that `name` (of a theme, profile, variant, cursor or frame) can be used as the name of
+/// a file or directory, without escaping the directory it is created in (e.g., `icons/<theme>`
+/// when installing, or `<theme>/cursors/<cursor>` when building).
+pub fn validate_name(kind: &str, name: &str) -> anyhow::Result<()> {
+    if matches!(name, "" | "." | "..") || name.contains(['/', '\\']) {
+        bail!("invalid {kind} name: {name:?}");
+    }
+
+    Ok(())
+}
+
+/// Read the configuration file at `path` as a table, with the files it includes merged in.
+///
+/// The files listed in `include` (relative to `path`) are merged in order, and `path` is
+/// merged on top of them, so later files override earlier ones (see [`merge`]). `stack` holds
+/// the files currently being read, to catch files that include themselves.
+fn read_table(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    includes: &mut Vec<PathBuf>,
+) -> anyhow::Result<toml::Table> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read configuration file: {}", path.display()))?;
+    let mut table: toml::Table = toml::from_str(&contents)
+        .with_context(|| format!("failed to parse configuration: {}", path.display()))?;
+    expand_inputs(&mut table)
+        .with_context(|| format!("failed to read configuration: {}", path.display()))?;
+
+    let Some(include) = table.remove("include") else {
+        return Ok(table);
+    };
+    let include: Vec<PathBuf> = include
+        .try_into()
+        .context("include must be a list of paths")?;
+
+    let canonical = fs::canonicalize(path)
+        .with_context(|| format!("failed to resolve path: {}", path.display()))?;
+    if stack.contains(&canonical) {
+        bail!("configuration includes itself: {}", path.display());
+    }
+    stack.push(canonical);
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut merged = toml::Table::new();
+
+    for relative in include {
+        let relative = PathBuf::from(expand_path(&relative.to_string_lossy())?);
+        let file = dir.join(&relative);
+        let mut table = read_table(&file, stack, includes)?;
+        rebase_inputs(&mut table, relative.parent().unwrap_or(Path::new("")));
+        merge(&mut merged, table);
+        includes.push(file);
+    }
+
+    stack.pop();
+    merge(&mut merged, table);
+    Ok(merged)
+}
+
+/// Merge `over` into `base`: tables are merged key by key, cursors (and `[[theme]]` tables)
+/// replace the one of the same name (or are added after the others), and anything else is
+/// replaced.
+fn merge(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        let value = match (base.remove(&key), value) {
+            (Some(toml::Value::Array(mut entries)), toml::Value::Array(over))
+                if key == "cursor" || key == "theme" =>
+            {
+                for entry in over {
+                    let name = entry.get("name").and_then(toml::Value::as_str);
+                    let existing = entries.iter_mut().find(|existing| {
+                        name.is_some() && existing.get("name").and_then(toml::Value::as_str) == name
+                    });
+
+                    match existing {
+                        Some(existing) => *existing = entry,
+                        None => entries.push(entry),
+                    }
+                }
+
+                toml::Value::Array(entries)
+            }
+            (Some(toml::Value::Table(mut table)), toml::Value::Table(over)) => {
+                merge(&mut table, over);
+                toml::Value::Table(table)
+            }
+            (_, value) => value,
+        };
+
+        base.insert(key, value);
+    }
+}
+
+/// Make the relative inputs of the cursors in `table` (and of its profiles' overrides and its
+/// themes) relative to `dir` instead: the directory of the file they were read from.
+fn rebase_inputs(table: &mut toml::Table, dir: &Path) {
+    if dir.as_os_str().is_empty() {
+        return;
+    }
+
+    for_each_input(table, &mut |path| {
+        if Path::new(path.as_str()).is_relative() {
+            *path = dir.join(path.as_str()).to_string_lossy().into_owned();
+        }
+    });
+}
+
+/// Expand `~` and environment variables in the inputs of the cursors in `table` (see
+/// [`expand_path`]).
+fn expand_inputs(table: &mut toml::Table) -> anyhow::Result<()> {
+    let mut result = Ok(());
+
+    for_each_input(table, &mut |path| {
+        if result.is_ok() {
+            result = expand_path(path).map(|expanded| *path = expanded);
+        }
+    });
+
+    result
+}
+
+/// Call `f` with the input of every cursor in `table`, of its profiles' overrides and of its
+/// themes.
+///
+/// The input of a cursor read from an archive is a path inside of the archive, so `f` is called
+/// with the path of the archive instead.
+fn for_each_input(table: &mut toml::Table, f: &mut impl FnMut(&mut String)) {
+    fn visit(cursor: &mut toml::Table, f: &mut impl FnMut(&mut String)) {
+        let key = if cursor.contains_key("archive") {
+            "archive"
+        } else {
+            "input"
+        };
+
+        if let Some(toml::Value::String(path)) = cursor.get_mut(key) {
+            f(path);
+        }
+    }
+
+    if let Some(toml::Value::Array(cursors)) = table.get_mut("cursor") {
+        for cursor in cursors.iter_mut().filter_map(toml::Value::as_table_mut) {
+            visit(cursor, f);
+        }
+    }
+
+    let overrides = table
+        .get_mut("profile")
+        .and_then(toml::Value::as_table_mut)
+        .into_iter()
+        .flat_map(|profiles| profiles.iter_mut().map(|(_, profile)| profile))
+        .filter_map(|profile| profile.get_mut("cursor")?.as_table_mut())
+        .flat_map(|cursors| cursors.iter_mut().map(|(_, cursor)| cursor))
+        .filter_map(toml::Value::as_table_mut);
+
+    for cursor in overrides {
+        visit(cursor, f);
+    }
+
+    if let Some(toml::Value::Array(themes)) = table.get_mut("theme") {
+        for theme in themes.iter_mut().filter_map(toml::Value::as_table_mut) {
+            for_each_input(theme, f);
+        }
+    }
+}
+
+/// Expand a leading `~` to the home directory, and `$VAR` (or `${VAR}`) to the value of the
+/// environment variable `VAR`, in `path`. A `$` that doesn't start a variable is kept as is.
+fn expand_path(path: &str) -> anyhow::Result<String> {
+    let mut output = String::with_capacity(path.len());
+    let mut rest = path;
+
+    if let Some(after) = rest.strip_prefix('~')
+        && (after.is_empty() || after.starts_with('/'))
+    {
+        let home = dirs::home_dir().context("failed to get home directory")?;
+        output.push_str(&home.to_string_lossy());
+        rest = after;
+    }
+
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let (name, next) = if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .with_context(|| format!("unclosed variable in path: {path}"))?;
+            (&braced[..end], &braced[end + 1..])
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        };
+
+        if name.is_empty() {
+            output.push('$');
+            rest = after;
+            continue;
+        }
+
+        let value = env::var(name)
+            .with_context(|| format!("environment variable not set: {name} (in path {path})"))?;
+        output.push_str(&value);
+        rest = next;
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// A variant of the theme (e.g., "left-handed" or "large") built into its own directory.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Profile {
+    /// The name of the generated theme. Defaults to `<theme>-<profile>`.
+    theme: Option<String>,
+
+    /// Overrides the animation speed multiplier.
+    speed: Option<f64>,
+
+    /// Overrides the sizes to generate each cursor in (e.g., for a "large" profile).
+    sizes: Option<Vec<u32>>,
+
+    /// Overrides the color adjustments for the whole theme (e.g., for a recolored variant).
+    transform: Option<Transform>,
+
+    /// Overrides the shadow drawn under every cursor.
+    shadow: Option<Shadow>,
+
+    /// Flip the arrows and hands horizontally to make a left-handed theme (see
+    /// [`names::MIRRORED`]), leaving the other cursors as they are.
+    #[serde(default)]
+    mirror: bool,
+
+    /// Per-cursor overrides, keyed by cursor name.
+    #[serde(default, rename = "cursor")]
+    cursors: BTreeMap<String, CursorOverride>,
+}
+
+impl Profile {
+    fn validate(&self, cursors: &[Cursor]) -> anyhow::Result<()> {
+        if self
+            .speed
+            .is_some_and(|speed| !(speed.is_finite() && speed > 0.0))
+        {
+            bail!("speed must be greater than zero");
+        }
+
+        if let Some(ref sizes) = self.sizes {
+            validate_sizes(sizes)?;
+        }
+
+        if let Some(ref transform) = self.transform {
+            transform.validate()?;
+        }
+
+        if let Some(ref shadow) = self.shadow {
+            shadow.validate()?;
+        }
+
+        for (name, overrides) in &self.cursors {
+            if !cursors.iter().any(|cursor| &cursor.name == name) {
+                bail!("cannot override unknown cursor: {name}");
+            }
+
+            // The archive is only used along with the input it holds (see `with_profile`).
+            if overrides.archive.is_some() && overrides.input.is_none() {
+                bail!("cannot override the archive of {name} without its input");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A size of the theme (e.g., "large") built into its own theme, from the same cursors.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Variant {
+    /// Template for the name of the generated theme (e.g., `"{theme}-Large"`).
+    ///
+    /// Available placeholders: `{theme}` and `{variant}`. Defaults to `{theme}-{variant}`.
+    theme: Option<String>,
+
+    /// The sizes to generate each cursor in.
+    sizes: Option<Vec<u32>>,
+
+    /// Multiplier for the sizes of the base theme (`frames.sizes`), instead of listing them.
+    scale: Option<f64>,
+}
+
+impl Variant {
+    /// Render the name of the theme for variant `name` of `theme`.
+    fn theme_name(&self, theme: &str, name: &str) -> anyhow::Result<String> {
+        let vars = [("theme", Value::Str(theme)), ("variant", Value::Str(name))];
+        let template = self.theme.as_deref().unwrap_or("{theme}-{variant}");
+
+        template::render(template, &vars)
+    }
+
+    /// The sizes to generate each cursor in, given the sizes of the base theme.
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn sizes(&self, base: &[u32]) -> Vec<u32> {
+        if let Some(ref sizes) = self.sizes {
+            return sizes.clone();
+        }
+
+        // Small sizes can round to the same size; keep the first.
+        let scale = self.scale.unwrap_or(1.0);
+        let mut sizes = Vec::with_capacity(base.len());
+        for &size in base {
+            let size = (f64::from(size) * scale).round().max(1.0) as u32;
+            if !sizes.contains(&size) {
+                sizes.push(size);
+            }
+        }
+
+        sizes
+    }
+
+    fn validate(&self, frames: &Frames) -> anyhow::Result<()> {
+        self.theme_name("theme", "variant")
+            .context("invalid theme name template")?;
+
+        match (&self.sizes, self.scale) {
+            (Some(_), Some(_)) => bail!("sizes and scale cannot be used together"),
+            (Some(sizes), None) => validate_sizes(sizes)?,
+            (None, Some(scale)) if !(scale.is_finite() && scale > 0.0) => {
+                bail!("scale must be greater than zero")
+            }
+            (None, Some(_)) if frames.sizes.is_empty() => {
+                bail!("scale requires frames.sizes to be set")
+            }
+            (None, Some(_)) => validate_sizes(&self.sizes(&frames.sizes))?,
+            (None, None) => bail!("either sizes or scale must be set"),
+        }
+
+        Ok(())
+    }
+}
+
+/// Replaces the source of a cursor within a profile.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct CursorOverride {
+    input: Option<PathBuf>,
+    archive: Option<PathBuf>,
+
+    /// Overrides whether the cursor is mirrored (see [`Profile::mirror`]).
+    mirror: Option<bool>,
+}
+
+/// Controls how frame timing from the ANI file is translated to the Xcursor.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Animation {
+    /// The shortest duration (in milliseconds) a frame is allowed to be displayed for.
+    ///
+    /// Some compositors skip frames with a duration of zero entirely, so frames declared with
+    /// a rate of `0` are raised to this value.
+    min_frame_duration: u32,
+
+    /// Multiplier for the playback speed (e.g., `2.0` plays the animation twice as fast).
+    speed: f64,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            min_frame_duration: 10,
+            speed: 1.0,
+        }
+    }
+}
+
+impl Animation {
+    pub const fn min_frame_duration(&self) -> u32 {
+        self.min_frame_duration
+    }
+
+    pub const fn speed(&self) -> f64 {
+        self.speed
+    }
+
+    /// Return a copy of the settings, playing `speed` times as fast.
+    pub fn with_speed(&self, speed: f64) -> Self {
+        Self {
+            speed: self.speed * speed,
+            ..self.clone()
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if !(self.speed.is_finite() && self.speed > 0.0) {
+            bail!("animation speed must be greater than zero");
+        }
+
+        Ok(())
+    }
+}
+
+/// Color adjustments applied to the decoded frames, in the order listed (e.g., to make a
+/// recolored variant of a theme).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Transform {
+    /// Invert the colors (but not the transparency).
+    invert: bool,
+
+    /// Rotate the hue of every pixel, in degrees (like the CSS `hue-rotate()` filter).
+    hue: f64,
+
+    /// Recolor every pixel with this color (e.g., `"#ff8800"`), keeping its brightness.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tint: Option<String>,
+
+    /// How much of the tint to apply, from 0 to 1.
+    tint_amount: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            invert: false,
+            hue: 0.0,
+            tint: None,
+            tint_amount: 1.0,
+        }
+    }
+}
+
+impl Transform {
+    pub const fn invert(&self) -> bool {
+        self.invert
+    }
+
+    pub const fn hue(&self) -> f64 {
+        self.hue
+    }
+
+    /// The tint color as RGB, and how much of it to apply.
+    pub fn tint(&self) -> Option<([u8; 3], f64)> {
+        let color = parse_color(self.tint.as_deref()?)?;
+        Some((color, self.tint_amount))
+    }
+
+    /// Whether the transform leaves the images as they are.
+    pub fn is_identity(&self) -> bool {
+        !self.invert && self.hue % 360.0 == 0.0 && self.tint.is_none()
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if !self.hue.is_finite() {
+            bail!("transform.hue must be a number of degrees");
+        }
+
+        if let Some(ref tint) = self.tint
+            && parse_color(tint).is_none()
+        {
+            bail!("transform.tint must be a color like \"#ff8800\": {tint}");
+        }
+
+        if !(0.0..=1.0).contains(&self.tint_amount) {
+            bail!("transform.tint_amount must be between 0 and 1");
+        }
+
+        Ok(())
+    }
+}
+
+/// A shadow drawn under each image: a copy of its silhouette, filled with `color`, grown by
+/// `spread`, blurred, moved by `x` and `y`, and drawn behind it.
+///
+/// Distances are in pixels at 32x32, and are scaled for other sizes. A shadow without an offset
+/// or blur, but with a `spread`, is an outline.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Shadow {
+    x: f64,
+    y: f64,
+    blur: f64,
+    spread: f64,
+    opacity: f64,
+    color: String,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Self {
+            x: 1.0,
+            y: 1.0,
+            blur: 1.0,
+            spread: 0.0,
+            opacity: 0.5,
+            color: "#000000".to_owned(),
+        }
+    }
+}
+
+impl Shadow {
+    /// The horizontal and vertical offset.
+    pub const fn offset(&self) -> (f64, f64) {
+        (self.x, self.y)
+    }
+
+    /// The standard deviation of the blur.
+    pub const fn blur(&self) -> f64 {
+        self.blur
+    }
+
+    /// How far the silhouette grows before being blurred.
+    pub const fn spread(&self) -> f64 {
+        self.spread
+    }
+
+    pub const fn opacity(&self) -> f64 {
+        self.opacity
+    }
+
+    pub fn color(&self) -> [u8; 3] {
+        parse_color(&self.color).unwrap_or_default()
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if !self.x.is_finite() || !self.y.is_finite() {
+            bail!("shadow offset must be a number of pixels");
+        }
+
+        if !(self.blur.is_finite() && self.blur >= 0.0) {
+            bail!("shadow.blur must not be negative");
+        }
+
+        if !(self.spread.is_finite() && self.spread >= 0.0) {
+            bail!("shadow.spread must not be negative");
+        }
+
+        if !(0.0..=1.0).contains(&self.opacity) {
+            bail!("shadow.opacity must be between 0 and 1");
+        }
+
+        if parse_color(&self.color).is_none() {
+            bail!(
+                "shadow.color must be a color like \"#000000\": {}",
+                self.color
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a `#rrggbb` color.
+fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Extra fields for the `index.theme` file of the generated theme, which some desktop
+/// environments show in their theme settings.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct IndexTheme {
+    /// The themes to fall back to for cursors this theme doesn't provide (comma-separated).
+    /// Set to `""` to not inherit from any theme.
+    inherits: String,
+
+    /// A short description of the theme.
+    comment: Option<String>,
+
+    /// The name of the cursor to show as a preview of the theme (e.g., `default`).
+    example: Option<String>,
+}
+
+impl Default for IndexTheme {
+    fn default() -> Self {
+        Self {
+            inherits: "Adwaita".to_owned(),
+            comment: None,
+            example: None,
+        }
+    }
 }
 
+impl IndexTheme {
+    pub fn inherits(&self) -> Option<&str> {
+        Some(self.inherits.as_str()).filter(|inherits| !inherits.is_empty())
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn example(&self) -> Option<&str> {
+        self.example.as_deref()
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let values = [
+            Some(&self.inherits),
+            self.comment.as_ref(),
+            self.example.as_ref(),
+        ];
+
+        if values
+            .into_iter()
+            .flatten()
+            .any(|value| value.contains(['\n', '\r']))
+        {
+            bail!("index_theme values must fit on a single line");
+        }
+
+        Ok(())
+    }
+}
+
+/// Information about the theme, used to generate the README and LICENSE files of a packaged
+/// theme.
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Metadata {
+    /// Who made the cursors.
+    author: Option<String>,
+
+    /// The license the cursors are distributed under (e.g., `CC-BY-SA-4.0`).
+    license: Option<String>,
+
+    /// Where the original cursors can be found.
+    url: Option<String>,
+}
+
+impl Metadata {
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+}
+
+/// Controls how extracted frames are named and arranged inside `build/frames`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Frames {
+    /// Template for the file name of each extracted frame.
+    ///
+    /// Available placeholders: `{cursor}`, `{stem}`, `{index}` and `{size}`.
+    name: String,
+
+    layout: Layout,
+
+    /// The sizes to generate each cursor in (e.g., `[24, 32, 48, 64]`).
+    ///
+    /// Sizes the input doesn't provide are resampled from its largest image, so high-DPI displays
+    /// don't have to make do with a 32x32 cursor. Defaults to the sizes found in the input.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    sizes: Vec<u32>,
+
+    /// How images are resampled when generating a missing size.
+    filter: Filter,
+
+    /// Write identical images only once, and reference the same file from every frame that
+    /// uses it.
+    dedup: bool,
+
+    /// How hard to compress the extracted frames.
+    compression: Compression,
+}
+
+impl Default for Frames {
+    fn default() -> Self {
+        Self {
+            name: "{index:02}-{size}.png".to_owned(),
+            layout: Layout::default(),
+            sizes: Vec::new(),
+            filter: Filter::default(),
+            dedup: true,
+            compression: Compression::default(),
+        }
+    }
+}
+
+impl Frames {
+    pub const fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn sizes(&self) -> &[u32] {
+        &self.sizes
+    }
+
+    pub const fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    pub const fn dedup(&self) -> bool {
+        self.dedup
+    }
+
+    pub const fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Render the file name for a single frame image.
+    pub fn file_name(
+        &self,
+        cursor: &str,
+        stem: &str,
+        index: usize,
+        size: u32,
+    ) -> anyhow::Result<String> {
+        let vars = [
+            ("cursor", Value::Str(cursor)),
+            ("stem", Value::Str(stem)),
+            ("index", Value::Int(index)),
+            ("size", Value::Int(size as usize)),
+        ];
+
+        let name = template::render(&self.name, &vars)?;
+        validate_name("frame", &name)?;
+        Ok(name)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        // Render once with placeholder values to catch syntax errors before building.
+        self.file_name("cursor", "stem", 0, 0)
+            .context("invalid frame name template")?;
+
+        let is_unique_per_cursor =
+            template::references(&self.name, "cursor") || template::references(&self.name, "stem");
+
+        if self.layout == Layout::Flat && !is_unique_per_cursor {
+            bail!(
+                "frame name template must include {{cursor}} or {{stem}} when using the flat layout"
+            );
+        }
+
+        validate_sizes(&self.sizes)
+    }
+}
+
+fn validate_sizes(sizes: &[u32]) -> anyhow::Result<()> {
+    for (i, &size) in sizes.iter().enumerate() {
+        if size == 0 || size > MAX_SIZE {
+            bail!("cursor size must be between 1 and {MAX_SIZE}: {size}");
+        }
+
+        if sizes[..i].contains(&size) {
+            bail!("duplicate cursor size: {size}");
+        }
+    }
+
+    Ok(())
+}
+
+/// The largest cursor size that can be generated, in pixels.
+const MAX_SIZE: u32 = 512;
+
+/// The filter used to resample images (see `frames.sizes`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Filter {
+    /// Keep hard edges (best for pixel art).
+    Nearest,
+
+    /// Linear interpolation.
+    Triangle,
+
+    /// Cubic interpolation.
+    CatmullRom,
+
+    /// Gaussian blur.
+    Gaussian,
+
+    /// Lanczos with a window of 3 (sharpest, but slowest).
+    #[default]
+    Lanczos3,
+}
+
+/// Where extracted frames are written relative to `build/frames`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// One subdirectory per input file (e.g., `build/frames/Wait/00-32.png`).
+    #[default]
+    Nested,
+
+    /// All frames share a single directory (e.g., `build/frames/wait-00.png`).
+    Flat,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[expect(clippy::struct_excessive_bools)]
 pub struct Cursor {
     name: String,
 
@@ -43,6 +1248,53 @@ pub struct Cursor {
     aliases: Vec<String>,
 
     input: PathBuf,
+
+    /// A zip archive containing `input`, if the cursor should be read from an archive.
+    archive: Option<PathBuf>,
+
+    /// Overrides the hotspot of every image (e.g., for `.png` inputs, which don't have one).
+    hotspot_x: Option<u16>,
+    hotspot_y: Option<u16>,
+
+    /// Overrides the hotspot of the images of a specific size, taking precedence over
+    /// `hotspot_x` and `hotspot_y`.
+    #[serde(default, rename = "hotspot", skip_serializing_if = "Vec::is_empty")]
+    hotspots: Vec<SizeHotspot>,
+
+    /// Multiplier for the playback speed of this cursor, on top of `animation.speed`.
+    speed: Option<f64>,
+
+    /// Color adjustments for this cursor, instead of the ones for the whole theme.
+    transform: Option<Transform>,
+
+    /// Flip the cursor horizontally (and its hotspots with it).
+    #[serde(default)]
+    mirror: bool,
+
+    /// Draw the theme's shadow under this cursor (e.g., disable it for the text cursor).
+    #[serde(default = "enabled")]
+    shadow: bool,
+
+    /// Crop the transparent borders shared by every frame, moving the hotspots to match.
+    #[serde(default)]
+    trim: bool,
+
+    /// The variants (see [`Variant`]) to build this cursor in. Defaults to every variant.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    variants: Option<Vec<String>>,
+
+    /// Leave the cursor out of the theme (e.g., while working on the others). Not serialized,
+    /// since it doesn't affect the output of the cursors that are built.
+    #[serde(default = "enabled", skip_serializing)]
+    enabled: bool,
+}
+
+/// The hotspot to use for the images of a single size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SizeHotspot {
+    size: u32,
+    x: u16,
+    y: u16,
 }
 
 impl Cursor {
@@ -57,4 +1309,722 @@ impl Cursor {
     pub fn input(&self) -> &Path {
         &self.input
     }
+
+    pub fn archive(&self) -> Option<&Path> {
+        self.archive.as_deref()
+    }
+
+    pub const fn trim(&self) -> bool {
+        self.trim
+    }
+
+    pub const fn mirror(&self) -> bool {
+        self.mirror
+    }
+
+    /// The configured hotspot for images of `size`, if any.
+    ///
+    /// A `[[cursor.hotspot]]` entry for `size` wins; otherwise, `hotspot_x` and `hotspot_y` are
+    /// used if either was set (the other defaults to 0).
+    pub fn hotspot(&self, size: u32) -> Option<Hotspot> {
+        if let Some(entry) = self.hotspots.iter().find(|entry| entry.size == size) {
+            return Some(Hotspot::new(entry.x, entry.y));
+        }
+
+        if self.hotspot_x.is_none() && self.hotspot_y.is_none() {
+            return None;
+        }
+
+        Some(Hotspot::new(
+            self.hotspot_x.unwrap_or_default(),
+            self.hotspot_y.unwrap_or_default(),
+        ))
+    }
+
+    /// The playback speed multiplier for this cursor (defaults to 1).
+    pub fn speed(&self) -> f64 {
+        self.speed.unwrap_or(1.0)
+    }
+
+    fn validate(&self, frames: &Frames) -> anyhow::Result<()> {
+        validate_name("cursor", &self.name)?;
+
+        for alias in &self.aliases {
+            validate_name("alias", alias)?;
+        }
+
+        if self
+            .speed
+            .is_some_and(|speed| !(speed.is_finite() && speed > 0.0))
+        {
+            bail!("speed must be greater than zero");
+        }
+
+        if let Some(ref transform) = self.transform {
+            transform.validate()?;
+        }
+
+        // The sizes of the input aren't known until it's decoded; hotspots outside of them are
+        // moved to the edge of the image when building.
+        if let Some(&size) = frames.sizes.iter().min() {
+            let x = self.hotspot_x.unwrap_or_default();
+            let y = self.hotspot_y.unwrap_or_default();
+
+            if u32::from(x) >= size || u32::from(y) >= size {
+                bail!("hotspot must be within the image: {size}x{size}");
+            }
+        }
+
+        for (i, entry) in self.hotspots.iter().enumerate() {
+            if u32::from(entry.x) >= entry.size || u32::from(entry.y) >= entry.size {
+                bail!(
+                    "hotspot must be within the image: {}x{}",
+                    entry.size,
+                    entry.size
+                );
+            }
+
+            if self.hotspots[..i]
+                .iter()
+                .any(|other| other.size == entry.size)
+            {
+                bail!("duplicate hotspot for size: {}", entry.size);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include() {
+        let temp = crate::test_dir();
+        let dir = temp.path();
+        fs::create_dir_all(dir.join("base")).unwrap();
+        fs::create_dir_all(dir.join("large")).unwrap();
+
+        fs::write(
+            dir.join("base/Cursor.toml"),
+            r#"
+                theme = "Base"
+
+                [frames]
+                sizes = [24, 32]
+                dedup = false
+
+                [[cursor]]
+                name = "default"
+                input = "./Arrow.ani"
+
+                [[cursor]]
+                name = "text"
+                input = "IBeam.cur"
+                archive = "pack.zip"
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("large/Cursor.toml"),
+            r#"
+                include = ["../base/Cursor.toml"]
+                theme = "Large"
+
+                [frames]
+                sizes = [48, 64]
+
+                [[cursor]]
+                name = "text"
+                input = "./Text.cur"
+
+                [[cursor]]
+                name = "wait"
+                input = "./Busy.ani"
+            "#,
+        )
+        .unwrap();
+
+        let config = Config::from_file(&dir.join("large/Cursor.toml"));
+        let cycle = fs::write(dir.join("base/Cursor.toml"), "include = [\"Cursor.toml\"]")
+            .map(|()| Config::from_file(&dir.join("large/Cursor.toml")));
+        let config = config.unwrap();
+
+        assert_eq!(config.theme(), "Large");
+        assert_eq!(config.frames().sizes(), [48, 64]);
+        assert!(!config.frames().dedup());
+        assert_eq!(config.includes(), [dir.join("large/../base/Cursor.toml")]);
+
+        let cursors = config.cursors();
+        assert_eq!(cursors.len(), 3);
+        assert_eq!(cursors[0].input(), dir.join("large/../base/./Arrow.ani"));
+        assert_eq!(cursors[1].input(), dir.join("large/./Text.cur"));
+        assert_eq!(cursors[1].archive(), None);
+        assert_eq!(cursors[2].name(), "wait");
+
+        assert!(cycle.unwrap().is_err());
+        assert!("include = []\ntheme = \"T\"\ncursor = []"
+            .parse::<Config>()
+            .is_err());
+    }
+
+    #[test]
+    fn profile_overrides() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+
+            [[cursor]]
+            name = "text"
+            input = "./IBeam.ani"
+
+            [profile.left-handed]
+            speed = 2.0
+
+            [profile.left-handed.cursor.default]
+            input = "./left/Arrow.ani"
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        let profile = config.with_profile("left-handed").unwrap();
+
+        assert_eq!(profile.theme(), "Theme-left-handed");
+        assert!((profile.animation().speed() - 2.0).abs() < f64::EPSILON);
+        assert_eq!(profile.cursors()[0].input(), Path::new("./left/Arrow.ani"));
+        assert_eq!(profile.cursors()[1].input(), Path::new("./IBeam.ani"));
+        assert!(config.with_profile("missing").is_err());
+    }
+
+    #[test]
+    fn transform_overrides() {
+        let config: Config = r##"
+            theme = "Theme"
+
+            [transform]
+            hue = 90
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+
+            [[cursor]]
+            name = "wait"
+            input = "./Busy.ani"
+            transform = { invert = true }
+
+            [profile.dark.transform]
+            tint = "#202020"
+        "##
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        let [arrow, busy] = config.cursors() else {
+            panic!("expected two cursors");
+        };
+        assert!((config.transform(arrow).hue() - 90.0).abs() < f64::EPSILON);
+        assert!(config.transform(busy).invert());
+
+        let dark = config.with_profile("dark").unwrap();
+        assert_eq!(dark.transform(arrow).tint(), Some(([0x20; 3], 1.0)));
+        assert!(dark.transform(busy).invert());
+
+        let invalid = r#"
+            theme = "Theme"
+            cursor = []
+
+            [transform]
+            tint = "orange"
+        "#
+        .parse::<Config>();
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn mirror_profile() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+
+            [[cursor]]
+            name = "size_fdiag"
+            input = "./Diagonal.ani"
+
+            [[cursor]]
+            name = "text"
+            input = "./IBeam.ani"
+
+            [[cursor]]
+            name = "help"
+            input = "./Help.ani"
+
+            [[cursor]]
+            name = "hand2"
+            input = "./Link.ani"
+
+            [[cursor]]
+            name = "pencil"
+            input = "./Pencil.ani"
+
+            [profile.left-handed]
+            mirror = true
+
+            [profile.left-handed.cursor.hand2]
+            mirror = false
+
+            [profile.left-handed.cursor.pencil]
+            mirror = true
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        let mirrored = config.with_profile("left-handed").unwrap();
+        let mirror = mirrored
+            .cursors()
+            .iter()
+            .map(Cursor::mirror)
+            .collect::<Vec<_>>();
+        assert_eq!(mirror, [true, false, false, false, false, true]);
+        assert!(!config.cursors()[0].mirror());
+    }
+
+    #[test]
+    fn profile_unknown_cursor() {
+        let result = r#"
+            theme = "Theme"
+            cursor = []
+
+            [profile.large.cursor.missing]
+            input = "./Missing.ani"
+        "#
+        .parse::<Config>();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn invalid_speeds() {
+        let cases = [
+            "[animation]\nspeed = nan",
+            "[animation]\nspeed = inf",
+            "[profile.fast]\nspeed = nan",
+            "[profile.fast]\nspeed = -inf",
+            "[profile.fast.cursor.default]\narchive = \"./pack.zip\"",
+        ];
+
+        for tables in cases {
+            let result = format!(
+                "theme = \"Theme\"\n[[cursor]]\nname = \"default\"\ninput = \"./a.ani\"\n{tables}"
+            )
+            .parse::<Config>();
+            assert!(result.is_err(), "{tables}");
+        }
+
+        let result =
+            "theme = \"Theme\"\n[[cursor]]\nname = \"default\"\ninput = \"./a.ani\"\nspeed = inf"
+                .parse::<Config>();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn variants() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [frames]
+            sizes = [24, 32]
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+
+            [[cursor]]
+            name = "wait"
+            input = "./Busy.ani"
+            variants = ["large"]
+
+            [variant.large]
+            theme = "{theme}-Large"
+            scale = 1.5
+
+            [variant.xl]
+            sizes = [64, 96]
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        assert_eq!(config.variants().collect::<Vec<_>>(), ["large", "xl"]);
+
+        let large = config.with_variant("large").unwrap();
+        assert_eq!(large.theme(), "Theme-Large");
+        assert_eq!(large.frames().sizes(), [36, 48]);
+        assert_eq!(large.cursors().len(), 2);
+        assert_eq!(large.variants().count(), 0);
+
+        let xl = config.with_variant("xl").unwrap();
+        assert_eq!(xl.theme(), "Theme-xl");
+        assert_eq!(xl.frames().sizes(), [64, 96]);
+        assert_eq!(xl.cursors().len(), 1);
+        assert!(config.with_variant("missing").is_err());
+
+        for invalid in [
+            "variant.large = { scale = 0.0 }",
+            "variant.large = { scale = nan }",
+            "variant.large = { scale = inf }",
+            "variant.large = { sizes = [48], scale = 2.0 }",
+            "variant.large = {}",
+        ] {
+            let result = format!("theme = \"Theme\"\ncursor = []\nframes.sizes = [24]\n{invalid}")
+                .parse::<Config>();
+            assert!(result.is_err(), "{invalid}");
+        }
+
+        let unknown = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+            variants = ["xl"]
+
+            [variant.large]
+            sizes = [48]
+        "#
+        .parse::<Config>();
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn themes() {
+        let config: Config = r#"
+            [frames]
+            sizes = [32]
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+
+            [[cursor]]
+            name = "text"
+            input = "./IBeam.ani"
+
+            [[theme]]
+            name = "Light"
+
+            [[theme]]
+            name = "Dark"
+            transform = { invert = true }
+
+            [[theme.cursor]]
+            name = "default"
+            input = "./dark/Arrow.ani"
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        assert_eq!(config.theme(), "Light");
+        assert_eq!(config.themes().collect::<Vec<_>>(), ["Dark"]);
+        assert_eq!(config.cursors()[0].input(), Path::new("./Arrow.ani"));
+
+        let dark = config.with_theme("Dark").unwrap();
+        assert_eq!(dark.theme(), "Dark");
+        assert_eq!(dark.frames().sizes(), [32]);
+        assert!(dark.transform(&dark.cursors()[1]).invert());
+        assert_eq!(dark.cursors()[0].input(), Path::new("./dark/Arrow.ani"));
+        assert_eq!(dark.cursors()[1].input(), Path::new("./IBeam.ani"));
+        assert_eq!(config.with_theme("Light").unwrap().themes().count(), 0);
+        assert!(config.with_theme("missing").is_err());
+
+        for invalid in [
+            "[[theme]]\nname = \"A\"\n[[theme]]\nname = \"A\"",
+            "[[theme]]\ncursor = []",
+            "theme = []",
+        ] {
+            let result = format!("cursor = []\n{invalid}").parse::<Config>();
+            assert!(result.is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn hotspot_override() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+
+            [[cursor]]
+            name = "progress"
+            input = "./Progress.png"
+            hotspot_y = 4
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        assert_eq!(config.cursors()[0].hotspot(32), None);
+        assert_eq!(config.cursors()[1].hotspot(32), Some(Hotspot::new(0, 4)));
+    }
+
+    #[test]
+    fn cursor_speed() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [animation]
+            speed = 2.0
+
+            [[cursor]]
+            name = "wait"
+            input = "./Wait.ani"
+            speed = 0.5
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        let cursor = &config.cursors()[0];
+        let animation = config.animation().with_speed(cursor.speed());
+        assert!((animation.speed() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn hotspot_per_size() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+            hotspot_x = 1
+            hotspot_y = 1
+
+            [[cursor.hotspot]]
+            size = 48
+            x = 6
+            y = 7
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        assert_eq!(config.cursors()[0].hotspot(32), Some(Hotspot::new(1, 1)));
+        assert_eq!(config.cursors()[0].hotspot(48), Some(Hotspot::new(6, 7)));
+
+        let outside = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+            hotspot = [{ size = 32, x = 32, y = 0 }]
+        "#
+        .parse::<Config>();
+
+        assert!(outside.is_err());
+
+        // The fallback is checked against every configured size (it applies to all of them).
+        let outside = r#"
+            theme = "Theme"
+
+            [frames]
+            sizes = [24, 48]
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+            hotspot_x = 30
+        "#
+        .parse::<Config>();
+
+        assert!(outside.is_err());
+    }
+
+    #[test]
+    fn auto_aliases() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "hand2"
+            input = "./Hand.ani"
+
+            [[cursor]]
+            name = "hand1"
+            aliases = ["grab"]
+            input = "./Grab.ani"
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        let aliases = config.cursors()[0].aliases();
+        assert!(aliases.iter().any(|alias| alias == "pointer"));
+        assert!(aliases
+            .iter()
+            .any(|alias| alias == "9d800788f1b08800ae810202380a0822"));
+        assert!(!aliases
+            .iter()
+            .any(|alias| alias == "hand1" || alias == "hand2"));
+        assert_eq!(config.cursors()[1].aliases(), ["grab"]);
+
+        let config: Config = r#"
+            theme = "Theme"
+            auto_aliases = false
+
+            [[cursor]]
+            name = "hand2"
+            input = "./Hand.ani"
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        assert!(config.cursors()[0].aliases().is_empty());
+    }
+
+    #[test]
+    fn invalid_names() {
+        let configs = [
+            (r#"theme = """#, ""),
+            (r#"theme = "..""#, ""),
+            (r#"theme = "../OtherTheme""#, ""),
+            (r#"theme = "a\\b""#, ""),
+            ("", "[[theme]]\nname = \".\""),
+            (r#"theme = "Theme""#, "[profile.\"a/b\"]"),
+            (r#"theme = "Theme""#, "[profile.large]\ntheme = \"..\""),
+            (
+                r#"theme = "Theme""#,
+                "[variant.large]\ntheme = \"{theme}/{variant}\"\nscale = 2.0",
+            ),
+            (r#"theme = "Theme""#, "[frames]\nname = \"../{index}.png\""),
+            (r#"theme = "Theme""#, "[frames]\nname = \"..\""),
+        ];
+
+        for (top, tables) in configs {
+            let config =
+                format!("{top}\n[[cursor]]\nname = \"default\"\ninput = \"./a.ani\"\n{tables}");
+            let err = config.parse::<Config>().map(|_| ()).unwrap_err();
+            assert!(format!("{err:#}").contains("name: "), "{config}: {err:#}");
+        }
+
+        for cursor in [
+            "name = \"../../x\"",
+            "name = \"..\"",
+            "name = \"\"",
+            "name = \"default\"\naliases = [\"../arrow\"]",
+            "name = \"default\"\naliases = [\".\"]",
+        ] {
+            let config = format!("theme = \"Theme\"\n[[cursor]]\ninput = \"./a.ani\"\n{cursor}");
+            let err = config.parse::<Config>().map(|_| ()).unwrap_err();
+            assert!(format!("{err:#}").contains("name: "), "{config}: {err:#}");
+        }
+
+        // Templates are also checked with the values they are rendered with when building.
+        let frames = Frames::default();
+        assert!(frames.file_name("default", "Arrow", 0, 32).is_ok());
+        let frames = Frames {
+            name: "{stem}".to_owned(),
+            ..Frames::default()
+        };
+        assert!(frames.file_name("default", "..", 0, 32).is_err());
+    }
+
+    #[test]
+    fn disabled_cursors() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "hand2"
+            input = "./Hand.ani"
+            enabled = false
+
+            [[cursor]]
+            name = "hand1"
+            input = "./Grab.ani"
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        // The disabled cursor keeps its names to itself.
+        assert_eq!(config.cursors().len(), 1);
+        assert_eq!(config.cursors()[0].name(), "hand1");
+        assert!(!config.cursors()[0]
+            .aliases()
+            .iter()
+            .any(|alias| alias == "pointer"));
+
+        let only = config.with_cursors(|cursor| cursor.name() == "hand2");
+        assert!(only.cursors().is_empty());
+    }
+
+    #[test]
+    fn index_theme() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [index_theme]
+            inherits = ""
+            comment = "Animated cursors"
+            example = "left_ptr"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        let index_theme = config.index_theme();
+        assert_eq!(index_theme.inherits(), None);
+        assert_eq!(index_theme.comment(), Some("Animated cursors"));
+        assert_eq!(index_theme.example(), Some("left_ptr"));
+
+        let unknown_example = r#"
+            theme = "Theme"
+
+            [index_theme]
+            example = "pointer"
+
+            [[cursor]]
+            name = "default"
+            input = "./Arrow.ani"
+        "#
+        .parse::<Config>();
+
+        assert!(unknown_example.is_err());
+    }
+
+    #[test]
+    fn expand() {
+        let home = dirs::home_dir().unwrap();
+        let home = home.to_str().unwrap();
+        let var = env::var("HOME").unwrap();
+
+        assert_eq!(
+            expand_path("~/pack/Busy.ani").unwrap(),
+            format!("{home}/pack/Busy.ani")
+        );
+        assert_eq!(expand_path("$HOME/a.ani").unwrap(), format!("{var}/a.ani"));
+        assert_eq!(
+            expand_path("${HOME}_x/a.ani").unwrap(),
+            format!("{var}_x/a.ani")
+        );
+        assert_eq!(expand_path("~user/a$.ani").unwrap(), "~user/a$.ani");
+        assert!(expand_path("$ANI_TO_XCURSOR_UNSET/a.ani").is_err());
+        assert!(expand_path("${HOME/a.ani").is_err());
+
+        let config = "theme = \"T\"\n[[cursor]]\nname = \"default\"\ninput = \"~/Arrow.ani\""
+            .parse::<Config>()
+            .unwrap();
+        assert_eq!(
+            config.cursors()[0].input(),
+            Path::new(home).join("Arrow.ani")
+        );
+    }
 }
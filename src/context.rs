@@ -1,4 +1,9 @@
+use std::env;
+
+use anyhow::Context as _;
+
 use crate::config::Config;
+use crate::exit::Failure;
 use crate::package::Package;
 use crate::verbosity::VerbosityLevel;
 
@@ -13,4 +18,53 @@ impl Context {
     pub fn with_level(self, level: VerbosityLevel) -> Self {
         Self { level, ..self }
     }
+
+    pub fn with_package(self, package: Package) -> Self {
+        Self {
+            package: Some(package),
+            ..self
+        }
+    }
+
+    /// The package to work on, defaulting to the current directory.
+    pub fn load_package(&mut self) -> anyhow::Result<&Package> {
+        if self.package.is_none() {
+            let current_dir = env::current_dir().context("failed to get current directory")?;
+            self.package = Some(Package::new(current_dir));
+        }
+
+        Ok(self.package.as_ref().unwrap())
+    }
+
+    /// Forget the configuration, so it is read again by the next call to [`load`](Self::load).
+    pub fn reload(&mut self) {
+        self.config = None;
+        self.package = self
+            .package
+            .take()
+            .map(|package| Package::new(package.as_path().to_owned()));
+    }
+
+    /// The package to work on and its configuration, which is read on first use.
+    ///
+    /// If the configuration sets a `build_dir`, the package is built there instead.
+    pub fn load(&mut self) -> anyhow::Result<(&Package, &Config)> {
+        self.load_package()?;
+
+        if self.config.is_none() {
+            let package = self.package.take().unwrap();
+            let config = Config::from_file(&package.config()).context(Failure::Config)?;
+
+            self.package = Some(match config.build_dir() {
+                Some(dir) => package.with_build_dir(dir),
+                None => package,
+            });
+            self.config = Some(config);
+        }
+
+        Ok((
+            self.package.as_ref().unwrap(),
+            self.config.as_ref().unwrap(),
+        ))
+    }
 }
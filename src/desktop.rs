@@ -0,0 +1,165 @@
+//! Set the active cursor theme of the desktop environment.
+
+use std::env;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context as _};
+use tracing::info;
+
+/// A desktop environment whose cursor theme can be set from the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Desktop {
+    /// GNOME and the desktops built on its settings (Cinnamon, Budgie, Pantheon, ...).
+    Gnome,
+    Xfce,
+    Kde,
+}
+
+impl Desktop {
+    /// Detect the current desktop from `XDG_CURRENT_DESKTOP`, falling back to whichever
+    /// settings tool is installed.
+    pub fn detect() -> Option<Self> {
+        let current = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+
+        for name in current.split(':') {
+            match name.to_ascii_lowercase().as_str() {
+                "gnome" | "unity" | "cinnamon" | "x-cinnamon" | "budgie" | "pantheon" | "mate" => {
+                    return Some(Self::Gnome);
+                }
+                "xfce" => return Some(Self::Xfce),
+                "kde" => return Some(Self::Kde),
+                _ => {}
+            }
+        }
+
+        if has_command("gsettings") {
+            Some(Self::Gnome)
+        } else if has_command("xfconf-query") {
+            Some(Self::Xfce)
+        } else if kwriteconfig().is_some() {
+            Some(Self::Kde)
+        } else {
+            None
+        }
+    }
+
+    /// The commands that set the cursor theme (and size, if given).
+    pub fn commands(self, theme_name: &str, size: Option<u32>) -> Vec<Vec<String>> {
+        let owned = |args: &[&str]| args.iter().map(|&arg| arg.to_owned()).collect::<Vec<_>>();
+        let mut commands = Vec::new();
+
+        match self {
+            Self::Gnome => {
+                let schema = "org.gnome.desktop.interface";
+                commands.push(owned(&[
+                    "gsettings",
+                    "set",
+                    schema,
+                    "cursor-theme",
+                    theme_name,
+                ]));
+
+                if let Some(size) = size {
+                    let size = size.to_string();
+                    commands.push(owned(&["gsettings", "set", schema, "cursor-size", &size]));
+                }
+            }
+            Self::Xfce => {
+                let set = |property: &str, kind: &str, value: &str| {
+                    owned(&[
+                        "xfconf-query",
+                        "--channel",
+                        "xsettings",
+                        "--property",
+                        property,
+                        "--create",
+                        "--type",
+                        kind,
+                        "--set",
+                        value,
+                    ])
+                };
+                commands.push(set("/Gtk/CursorThemeName", "string", theme_name));
+
+                if let Some(size) = size {
+                    commands.push(set("/Gtk/CursorThemeSize", "int", &size.to_string()));
+                }
+            }
+            Self::Kde => {
+                let program = kwriteconfig().unwrap_or("kwriteconfig6");
+                let set = |key: &str, value: &str| {
+                    owned(&[
+                        program,
+                        "--file",
+                        "kcminputrc",
+                        "--group",
+                        "Mouse",
+                        "--key",
+                        key,
+                        value,
+                    ])
+                };
+                commands.push(set("cursorTheme", theme_name));
+
+                if let Some(size) = size {
+                    commands.push(set("cursorSize", &size.to_string()));
+                }
+            }
+        }
+
+        commands
+    }
+}
+
+/// Run a command built by [`Desktop::commands`].
+pub fn run(command: &[String]) -> anyhow::Result<()> {
+    let (program, args) = command.split_first().context("expected a command")?;
+    info!("running: {}", self::display(command));
+
+    let status = Command::new(program)
+        .args(args)
+        .status()
+        .with_context(|| format!("failed to execute {program}"))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("{program} failed: {status}"))
+    }
+}
+
+/// Format a command the way it would be typed into a shell.
+pub fn display(command: &[String]) -> String {
+    command
+        .iter()
+        .map(|arg| {
+            let plain = !arg.is_empty()
+                && arg
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./:".contains(c));
+
+            if plain {
+                arg.clone()
+            } else {
+                format!("{arg:?}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The `kwriteconfig` of the installed version of Plasma, if any.
+fn kwriteconfig() -> Option<&'static str> {
+    ["kwriteconfig6", "kwriteconfig5"]
+        .into_iter()
+        .find(|&program| has_command(program))
+}
+
+fn has_command(cmd: &str) -> bool {
+    Command::new("sh")
+        .arg("-c")
+        .arg(format!("command -v {cmd}"))
+        .stdout(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
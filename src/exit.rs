@@ -0,0 +1,168 @@
+//! Exit codes for each class of failure, so scripts can tell them apart.
+//!
+//! Errors are classified by attaching a [`Failure`] as context (e.g.,
+//! `.context(Failure::Config)`); errors from decoding a cursor or running `xcursorgen` are
+//! recognized on their own.
+
+use std::fmt;
+use std::io::{self, Write as _};
+use std::process::ExitCode;
+
+use ani::de::DecodeError;
+use serde::Serialize;
+use xcursor::ThemeError;
+
+/// A class of failure, reported through the exit code.
+///
+/// Invalid command-line arguments exit with code 2 (see [`clap::error::Error::exit`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Failure {
+    /// Anything that doesn't belong to another class (exit code 1).
+    Other,
+
+    /// `Cursor.toml` is missing or invalid (exit code 3).
+    Config,
+
+    /// An input isn't a valid ANI, CUR or PNG file (exit code 4).
+    Decode,
+
+    /// `xcursorgen` is missing or failed to create an Xcursor (exit code 5).
+    Xcursorgen,
+
+    /// The built theme is incomplete or invalid (exit code 6).
+    Verify,
+}
+
+impl Failure {
+    /// The class of `err`: the outermost [`Failure`] attached to it, or else [`Self::Decode`]
+    /// if it was caused by a [`DecodeError`] and [`Self::Xcursorgen`] if `xcursorgen` is
+    /// missing or failed.
+    pub fn of(err: &anyhow::Error) -> Self {
+        if let Some(&failure) = err.downcast_ref::<Self>() {
+            return failure;
+        }
+
+        if err.chain().any(<dyn std::error::Error>::is::<DecodeError>) {
+            return Self::Decode;
+        }
+
+        let is_xcursorgen = |cause: &(dyn std::error::Error + 'static)| {
+            matches!(
+                cause.downcast_ref::<ThemeError>(),
+                Some(
+                    ThemeError::XcursorgenNotFound { .. }
+                        | ThemeError::XcursorgenFailure { .. }
+                        | ThemeError::XcursorgenExit { .. }
+                )
+            )
+        };
+        if err.chain().any(is_xcursorgen) {
+            return Self::Xcursorgen;
+        }
+
+        Self::Other
+    }
+
+    pub const fn code(self) -> u8 {
+        match self {
+            Self::Other => 1,
+            Self::Config => 3,
+            Self::Decode => 4,
+            Self::Xcursorgen => 5,
+            Self::Verify => 6,
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Other => "failed",
+            Self::Config => "invalid configuration",
+            Self::Decode => "failed to decode input",
+            Self::Xcursorgen => "xcursorgen failed",
+            Self::Verify => "theme verification failed",
+        })
+    }
+}
+
+impl std::error::Error for Failure {}
+
+impl From<Failure> for ExitCode {
+    fn from(failure: Failure) -> Self {
+        Self::from(failure.code())
+    }
+}
+
+/// The causes of `err` to show, leaving out the [`Failure`] it is classified by (which is
+/// reported through the exit code instead).
+pub fn causes(err: &anyhow::Error) -> impl Iterator<Item = String> {
+    // Context can't be told apart from the error it was attached to other than by its message.
+    let failure = err.downcast_ref::<Failure>().map(ToString::to_string);
+
+    err.chain()
+        .map(ToString::to_string)
+        .filter(move |cause| failure.as_ref() != Some(cause))
+}
+
+/// An error, for `--format json`.
+#[derive(Debug, Serialize)]
+struct Report {
+    error: ErrorReport,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorReport {
+    failure: Failure,
+    exit_code: u8,
+    causes: Vec<String>,
+}
+
+/// Print `err` to stdout as JSON, for commands run with `--format json`.
+pub fn print_json(err: &anyhow::Error) -> io::Result<()> {
+    let failure = Failure::of(err);
+    let report = Report {
+        error: ErrorReport {
+            failure,
+            exit_code: failure.code(),
+            causes: causes(err).collect(),
+        },
+    };
+
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &report)?;
+    writeln!(stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{anyhow, Context as _};
+
+    use super::*;
+
+    #[test]
+    fn classify() {
+        let err = anyhow!("no such file").context(Failure::Config);
+        assert_eq!(Failure::of(&err), Failure::Config);
+        assert_eq!(causes(&err).collect::<Vec<_>>(), ["no such file"]);
+
+        let err = Err::<(), _>(DecodeError::MissingChunk { expected: *b"anih" })
+            .context("failed to decode ANI file")
+            .unwrap_err();
+        assert_eq!(Failure::of(&err), Failure::Decode);
+
+        let err = Err::<(), _>(ThemeError::XcursorgenExit { code: Some(1) })
+            .context("failed to create Xcursor")
+            .unwrap_err();
+        assert_eq!(Failure::of(&err), Failure::Xcursorgen);
+
+        // The outermost class wins.
+        let err = anyhow!("exit code: 1")
+            .context(Failure::Xcursorgen)
+            .context(Failure::Verify);
+        assert_eq!(Failure::of(&err), Failure::Verify);
+
+        assert_eq!(Failure::of(&anyhow!("oops")), Failure::Other);
+    }
+}
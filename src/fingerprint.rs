@@ -0,0 +1,133 @@
+//! Track the inputs each cursor was built from, so unchanged cursors can be skipped.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::Context as _;
+
+/// Incrementally compute a 64-bit FNV-1a hash.
+///
+/// The standard library's hashers aren't guaranteed to produce the same output between
+/// releases, which would invalidate every fingerprint after updating Rust.
+#[derive(Debug, Clone, Copy)]
+pub struct Hasher(u64);
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher {
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    /// Hash a length-prefixed field, so adjacent fields can't run into each other.
+    pub fn field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    pub const fn finish(self) -> u64 {
+        self.0
+    }
+}
+
+/// Hash `bytes` in one go.
+pub fn hash(bytes: &[u8]) -> u64 {
+    let mut hasher = Hasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// Hashes recorded by a previous build, keyed by name (e.g., a cursor or a frame).
+#[derive(Debug, Clone, Default)]
+pub struct Fingerprints {
+    entries: BTreeMap<String, u64>,
+}
+
+impl Fingerprints {
+    /// Read the fingerprints file at `path`; a missing or malformed file is treated as empty.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err).context("failed to read fingerprints"),
+        };
+
+        let entries = contents
+            .lines()
+            .filter_map(|line| {
+                let (hash, name) = line.split_once(' ')?;
+                let hash = u64::from_str_radix(hash, 16).ok()?;
+                Some((name.to_owned(), hash))
+            })
+            .collect();
+
+        Ok(Self { entries })
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let mut contents = String::new();
+
+        for (name, hash) in &self.entries {
+            _ = writeln!(contents, "{hash:016x} {name}");
+        }
+
+        fs::write(path, contents).context("failed to write fingerprints")
+    }
+
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.entries.get(name).copied()
+    }
+
+    pub fn insert(&mut self, name: String, hash: u64) {
+        self.entries.insert(name, hash);
+    }
+
+    /// Keep only the entries whose name satisfies `keep`.
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.entries.retain(|name, _| keep(name));
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.entries
+            .iter()
+            .map(|(name, &hash)| (name.as_str(), hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a() {
+        // Reference values from the FNV specification.
+        assert_eq!(Hasher::default().finish(), 0xcbf2_9ce4_8422_2325);
+
+        let mut hasher = Hasher::default();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn fields_are_delimited() {
+        let hash = |fields: &[&[u8]]| {
+            let mut hasher = Hasher::default();
+            for field in fields {
+                hasher.field(field);
+            }
+            hasher.finish()
+        };
+
+        assert_ne!(hash(&[b"ab", b"c"]), hash(&[b"a", b"bc"]));
+    }
+}
@@ -0,0 +1,268 @@
+//! Cache the frames extracted from each input, keyed by its contents, so identical inputs (e.g.,
+//! the same cursor in several variants, or a cursor that was renamed) aren't extracted again.
+//!
+//! Each entry is a directory named after the key (a SHA-256 hash, so different inputs never
+//! share an entry), holding the PNG files and a manifest that lists the images of every frame.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::{process, thread};
+
+use anyhow::{bail, Context as _};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use tracing::{info, warn};
+use xcursor::Image;
+
+use crate::commands::build::Mode;
+use crate::config::{Cursor, Frames, Shadow, Transform};
+
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Clone)]
+pub struct FrameCache {
+    dir: PathBuf,
+}
+
+/// The key of a cache entry (see [`FrameCache::key`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key([u8; 32]);
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// An image of a cached frame.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedImage {
+    size: u32,
+    hotspot_x: u16,
+    hotspot_y: u16,
+    file_name: String,
+}
+
+impl FrameCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The key of the frames extracted from `data`: a hash of the input and of everything that
+    /// changes the pixels of its frames, but not of the names they're written under.
+    pub fn key(
+        data: &[u8],
+        cursor: &Cursor,
+        frames: &Frames,
+        transform: &Transform,
+        shadow: Option<&Shadow>,
+        mode: Mode,
+    ) -> anyhow::Result<Key> {
+        let mut hasher = Sha256::new();
+        // Fields are length-prefixed, so adjacent fields can't run into each other.
+        let mut field = |bytes: &[u8]| {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+
+        field(env!("CARGO_PKG_VERSION").as_bytes());
+        field(data);
+        field(format!("{mode:?}").as_bytes());
+        field(format!("{:?}", frames.sizes()).as_bytes());
+        field(format!("{:?}", frames.filter()).as_bytes());
+        field(format!("{:?}", frames.compression()).as_bytes());
+        field(&[u8::from(frames.dedup()), u8::from(cursor.mirror())]);
+        field(toml::to_string(transform)?.as_bytes());
+        field(format!("{shadow:?}").as_bytes());
+
+        Ok(Key(hasher.finalize().into()))
+    }
+
+    fn entry(&self, key: Key) -> PathBuf {
+        self.dir.join(key.to_string())
+    }
+
+    /// Copy the cached frames for `key` to `output_dir`, naming each file with `file_name` (given
+    /// the index of the frame and the size of the image), like
+    /// [`extract_frames`](crate::commands::build::extract_frames).
+    ///
+    /// Files named in `keep` are left as they are. Returns `None` if nothing is cached for `key`
+    /// (or the entry can't be used).
+    pub fn restore<F>(
+        &self,
+        key: Key,
+        output_dir: &Path,
+        file_name: F,
+        keep: &HashSet<String>,
+    ) -> Option<Vec<Vec<Image>>>
+    where
+        F: Fn(usize, u32) -> anyhow::Result<String>,
+    {
+        let entry = self.entry(key);
+        if !entry.is_dir() {
+            return None;
+        }
+
+        match restore(&entry, output_dir, file_name, keep) {
+            Ok(extracted) => {
+                info!("reused cached frames: {:#}", entry.display());
+                Some(extracted)
+            }
+            Err(err) => {
+                warn!("ignoring frame cache entry {}: {err:#}", entry.display());
+                None
+            }
+        }
+    }
+
+    /// Cache the frames that were just extracted to `output_dir` under `key`.
+    ///
+    /// Failing to write the cache only costs time on the next build, so errors are logged
+    /// rather than returned.
+    pub fn store(&self, key: Key, output_dir: &Path, extracted: &[Vec<Image>]) {
+        let entry = self.entry(key);
+        if entry.exists() {
+            return;
+        }
+
+        // Written under a temporary name first, so a cursor with the same input being built at
+        // the same time never sees a partial entry.
+        let staging = self.dir.join(format!(
+            ".{key}-{}-{:?}",
+            process::id(),
+            thread::current().id()
+        ));
+
+        let result = store(&staging, output_dir, extracted).and_then(|()| {
+            fs::rename(&staging, &entry).context("failed to move cache entry into place")
+        });
+
+        if let Err(err) = result {
+            _ = fs::remove_dir_all(&staging);
+
+            if !entry.exists() {
+                warn!("failed to cache frames: {err:#}");
+            }
+        }
+    }
+}
+
+fn store(staging: &Path, output_dir: &Path, extracted: &[Vec<Image>]) -> anyhow::Result<()> {
+    fs::create_dir_all(staging)
+        .with_context(|| format!("failed to create {}", staging.display()))?;
+
+    let mut copied = HashSet::new();
+    let mut manifest = Vec::with_capacity(extracted.len());
+
+    for images in extracted {
+        let mut frame = Vec::with_capacity(images.len());
+
+        for image in images {
+            let file_name = image.file_name();
+            if copied.insert(file_name) {
+                fs::copy(output_dir.join(file_name), staging.join(file_name))
+                    .with_context(|| format!("failed to copy {file_name}"))?;
+            }
+
+            let (hotspot_x, hotspot_y) = image.hotspot();
+            frame.push(CachedImage {
+                size: image.size(),
+                hotspot_x,
+                hotspot_y,
+                file_name: file_name.to_owned(),
+            });
+        }
+
+        manifest.push(frame);
+    }
+
+    let manifest = serde_json::to_vec(&manifest).context("failed to serialize manifest")?;
+    fs::write(staging.join(MANIFEST), manifest).context("failed to write manifest")
+}
+
+fn restore<F>(
+    entry: &Path,
+    output_dir: &Path,
+    file_name: F,
+    keep: &HashSet<String>,
+) -> anyhow::Result<Vec<Vec<Image>>>
+where
+    F: Fn(usize, u32) -> anyhow::Result<String>,
+{
+    let manifest = fs::read(entry.join(MANIFEST)).context("failed to read manifest")?;
+    let manifest: Vec<Vec<CachedImage>> =
+        serde_json::from_slice(&manifest).context("failed to parse manifest")?;
+
+    // The name given to each cached file, where it is first used.
+    let mut names = HashMap::<&str, String>::new();
+    let mut seen = HashSet::new();
+    let mut extracted = Vec::with_capacity(manifest.len());
+
+    for (i, frame) in manifest.iter().enumerate() {
+        let mut images = Vec::with_capacity(frame.len());
+
+        for image in frame {
+            let name = if let Some(name) = names.get(image.file_name.as_str()) {
+                name.clone()
+            } else {
+                let name = file_name(i, image.size)?;
+
+                if !seen.insert(name.clone()) {
+                    bail!("frame name template produced a duplicate file name: {name}");
+                }
+
+                if !keep.contains(&name) {
+                    fs::copy(entry.join(&image.file_name), output_dir.join(&name))
+                        .with_context(|| format!("failed to copy {}", image.file_name))?;
+                }
+
+                names.insert(&image.file_name, name.clone());
+                name
+            };
+
+            images.push(Image::new(
+                image.size,
+                (image.hotspot_x, image.hotspot_y),
+                name,
+            ));
+        }
+
+        extracted.push(images);
+    }
+
+    Ok(extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let temp = crate::test_dir();
+        let dir = temp.path();
+        let (frames, output) = (dir.join("frames"), dir.join("renamed"));
+        fs::create_dir_all(&frames).unwrap();
+        fs::create_dir_all(&output).unwrap();
+        fs::write(frames.join("a-0.png"), "first").unwrap();
+
+        // Two frames sharing the same image.
+        let image = |x| Image::new(32, (x, 1), "a-0.png");
+        let cache = FrameCache::new(dir.join("cache"));
+        cache.store(Key([7; 32]), &frames, &[vec![image(0)], vec![image(2)]]);
+
+        let name = |index: usize, size: u32| Ok(format!("b-{index}-{size}.png"));
+        let restored = cache.restore(Key([7; 32]), &output, name, &HashSet::new());
+        let missing = cache.restore(Key([8; 32]), &output, name, &HashSet::new());
+        let contents = fs::read_to_string(output.join("b-0-32.png"));
+
+        let restored = restored.unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[1][0].file_name(), "b-0-32.png");
+        assert_eq!(restored[1][0].hotspot(), (2, 1));
+        assert_eq!(contents.unwrap(), "first");
+        assert!(missing.is_none());
+    }
+}
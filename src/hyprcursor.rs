@@ -0,0 +1,218 @@
+//! Write the hyprcursor format used by Hyprland.
+//!
+//! A hyprcursor theme is a `manifest.hl` file and a directory with one `.hlc` archive per cursor
+//! shape. Each archive holds the images of the shape, along with a `meta.hl` file describing
+//! their sizes, timing and hotspot.
+//!
+//! <https://github.com/hyprwm/hyprcursor/blob/main/docs/MAKING_THEMES.md>
+
+use std::cmp::Reverse;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::Write as _;
+use std::path::Path;
+
+use anyhow::Context as _;
+use tracing::info;
+use xcursor::{Image, Step};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::config::{Config, Cursor, Filter};
+
+/// The directory (inside of the theme) holding the shapes, as named by the manifest.
+pub const CURSORS_DIRECTORY: &str = "hyprcursors";
+
+/// Render the `manifest.hl` file, which names the theme and where its shapes are.
+pub fn render_manifest(config: &Config) -> String {
+    let description = config
+        .index_theme()
+        .comment()
+        .unwrap_or("Generated by ani-to-xcursor");
+
+    format!(
+        "name = {}\ndescription = {description}\nversion = {}\ncursors_directory = \
+        {CURSORS_DIRECTORY}\n",
+        config.theme(),
+        env!("CARGO_PKG_VERSION"),
+    )
+}
+
+/// Render the `meta.hl` file of a shape, listing the image to show for each size and step.
+///
+/// Hyprcursor has a single hotspot per shape, relative to the size of the image, so it is taken
+/// from the largest image of the first step.
+///
+/// Every size lists every step, so the shape animates at each of them: a step without an image
+/// of the size shows its nearest one instead, which Hyprland resizes.
+pub fn render_meta(
+    cursor: &Cursor,
+    frames: &[Vec<Image>],
+    steps: &[Step],
+    filter: Filter,
+) -> String {
+    let resize_algorithm = match filter {
+        Filter::Nearest => "nearest",
+        _ => "bilinear",
+    };
+    let mut contents = format!("resize_algorithm = {resize_algorithm}\n");
+
+    let largest = steps
+        .first()
+        .and_then(|step| frames[step.frame].iter().max_by_key(|image| image.size()));
+
+    if let Some(image) = largest {
+        let size = f64::from(image.size());
+        let (x, y) = image.hotspot();
+        _ = writeln!(
+            contents,
+            "hotspot_x = {:.3}\nhotspot_y = {:.3}",
+            f64::from(x) / size,
+            f64::from(y) / size,
+        );
+    }
+
+    for alias in cursor.aliases() {
+        _ = writeln!(contents, "define_override = {alias}");
+    }
+
+    let mut sizes = frames.iter().flatten().map(Image::size).collect::<Vec<_>>();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    for size in sizes {
+        for step in steps {
+            let nearest = frames[step.frame]
+                .iter()
+                .min_by_key(|image| (image.size().abs_diff(size), Reverse(image.size())));
+            let Some(image) = nearest else {
+                continue;
+            };
+
+            // The delay is only read for animated shapes.
+            if steps.len() > 1 {
+                _ = writeln!(
+                    contents,
+                    "define_size = {size}, {}, {}",
+                    image.file_name(),
+                    step.duration
+                );
+            } else {
+                _ = writeln!(contents, "define_size = {size}, {}", image.file_name());
+            }
+        }
+    }
+
+    contents
+}
+
+/// Write the shape of `cursor` to `shape_dir` (`meta.hl` and a copy of its images), then pack
+/// the directory into the `.hlc` archive at `output`.
+pub fn write_shape(
+    cursor: &Cursor,
+    frames_dir: &Path,
+    frames: &[Vec<Image>],
+    steps: &[Step],
+    filter: Filter,
+    shape_dir: &Path,
+    output: &Path,
+) -> anyhow::Result<()> {
+    if shape_dir.exists() {
+        fs::remove_dir_all(shape_dir).context("failed to remove previous shape directory")?;
+    }
+    fs::create_dir_all(shape_dir).context("failed to create shape directory")?;
+
+    let meta = render_meta(cursor, frames, steps, filter);
+    fs::write(shape_dir.join("meta.hl"), meta).context("failed to create meta.hl file")?;
+
+    for image in frames.iter().flatten() {
+        let file_name = image.file_name();
+        fs::copy(frames_dir.join(file_name), shape_dir.join(file_name))
+            .with_context(|| format!("failed to copy frame: {file_name}"))?;
+    }
+    info!("created shape directory: {:#}", shape_dir.display());
+
+    pack(shape_dir, output)?;
+    info!("created hyprcursor shape: {:#}", output.display());
+
+    Ok(())
+}
+
+/// Archive every file in `dir` (without the directory itself) as a zip file at `output`.
+fn pack(dir: &Path, output: &Path) -> anyhow::Result<()> {
+    let file = File::create(output)
+        .with_context(|| format!("failed to create file: {}", output.display()))?;
+    let mut zip = ZipWriter::new(file);
+
+    let mut entries = fs::read_dir(dir)
+        .context("failed to read shape directory")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("failed to read directory entry")?;
+    entries.sort_by_key(fs::DirEntry::file_name);
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let data = fs::read(entry.path()).with_context(|| format!("failed to read {name}"))?;
+
+        zip.start_file(&name, SimpleFileOptions::default())
+            .context("failed to add file to archive")?;
+        zip.write_all(&data)
+            .context("failed to write file to archive")?;
+    }
+
+    zip.finish().context("failed to finish archive")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meta() {
+        let config: Config = r#"
+            theme = "Theme"
+
+            [[cursor]]
+            name = "wait"
+            input = "./Busy.ani"
+            aliases = ["watch"]
+        "#
+        .parse()
+        .expect("expected hardcoded config to be valid");
+
+        let image = |size, file_name| Image::new(size, (8, 16), file_name);
+        // The second frame has no 64px image, so the 64px size shows its 32px one instead.
+        let frames = vec![
+            vec![image(32, "00-32.png"), image(64, "00-64.png")],
+            vec![image(32, "01-32.png")],
+        ];
+        let steps = [
+            Step {
+                frame: 0,
+                duration: 50,
+            },
+            Step {
+                frame: 1,
+                duration: 100,
+            },
+        ];
+
+        let meta = render_meta(&config.cursors()[0], &frames, &steps, Filter::Nearest);
+        assert_eq!(
+            meta,
+            "resize_algorithm = nearest\n\
+            hotspot_x = 0.125\n\
+            hotspot_y = 0.250\n\
+            define_override = watch\n\
+            define_size = 32, 00-32.png, 50\n\
+            define_size = 32, 01-32.png, 100\n\
+            define_size = 64, 00-64.png, 50\n\
+            define_size = 64, 01-32.png, 100\n"
+        );
+
+        let manifest = render_manifest(&config);
+        assert!(manifest.starts_with("name = Theme\n"));
+        assert!(manifest.ends_with("cursors_directory = hyprcursors\n"));
+    }
+}
@@ -0,0 +1,296 @@
+//! Parse Windows setup information (INF) files, such as the `Install.inf` shipped with cursors.
+//!
+//! <https://learn.microsoft.com/en-us/windows-hardware/drivers/install/general-syntax-rules-for-inf-files>
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context as _};
+
+/// Represents the contents of an INF file.
+#[derive(Debug, Clone, Default)]
+pub struct Inf {
+    sections: Vec<Section>,
+}
+
+/// A named group of entries (e.g., `[Strings]`).
+#[derive(Debug, Clone)]
+pub struct Section {
+    name: String,
+    entries: Vec<Entry>,
+}
+
+/// A single line within a section: either `key = values` or just `values`.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    key: Option<String>,
+    values: Vec<String>,
+}
+
+impl Inf {
+    /// Read and parse an INF file.
+    ///
+    /// Both UTF-8 and UTF-16 (with a byte order mark) encoded files are supported.
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let bytes = fs::read(path).context("failed to read INF file")?;
+        Self::parse(&decode_text(&bytes))
+    }
+
+    /// Parse the text of an INF file.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut sections = Vec::<Section>::new();
+        let mut pending = String::new();
+
+        for (number, line) in text.lines().enumerate() {
+            let line = strip_comment(line);
+
+            // A trailing backslash continues the line onto the next one.
+            if let Some(continued) = line.trim_end().strip_suffix('\\') {
+                pending.push_str(continued);
+                continue;
+            }
+
+            pending.push_str(line);
+            let line = std::mem::take(&mut pending);
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let Some(name) = header.strip_suffix(']') else {
+                    bail!("invalid section header on line {}: {line}", number + 1);
+                };
+
+                sections.push(Section {
+                    name: name.trim().to_owned(),
+                    entries: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some(section) = sections.last_mut() else {
+                bail!("entry outside of a section on line {}: {line}", number + 1);
+            };
+
+            section.entries.push(parse_entry(line));
+        }
+
+        Ok(Self { sections })
+    }
+
+    /// Find a section by name (case-insensitive).
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections
+            .iter()
+            .find(|section| section.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Replace `%KEY%` tokens in `text` with the matching values from the `[Strings]` section.
+    ///
+    /// Unknown tokens are left untouched, and `%%` is replaced with a single `%`.
+    pub fn expand(&self, text: &str) -> String {
+        let strings = self.section("Strings");
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find('%') {
+            output.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+
+            let Some(end) = after.find('%') else {
+                output.push_str(&rest[start..]);
+                return output;
+            };
+
+            let key = &after[..end];
+            let value = if key.is_empty() {
+                Some("%")
+            } else {
+                strings.and_then(|section| section.value(key))
+            };
+
+            if let Some(value) = value {
+                output.push_str(value);
+            } else {
+                output.push('%');
+                output.push_str(key);
+                output.push('%');
+            }
+
+            rest = &after[end + 1..];
+        }
+
+        output.push_str(rest);
+        output
+    }
+}
+
+impl Section {
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The first value of the entry with the given key (case-insensitive).
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|entry| {
+                entry
+                    .key
+                    .as_deref()
+                    .is_some_and(|k| k.eq_ignore_ascii_case(key))
+            })
+            .and_then(|entry| entry.values.first())
+            .map(String::as_str)
+    }
+}
+
+impl Entry {
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+}
+
+/// Decode the raw bytes of an INF file, which may be UTF-16 encoded.
+fn decode_text(bytes: &[u8]) -> String {
+    let utf16 = |bytes: &[u8], from: fn([u8; 2]) -> u16| {
+        let units = bytes
+            .chunks_exact(2)
+            .map(|pair| from([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        String::from_utf16_lossy(&units)
+    };
+
+    if let Some(rest) = bytes.strip_prefix(&[0xff, 0xfe]) {
+        utf16(rest, u16::from_le_bytes)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xfe, 0xff]) {
+        utf16(rest, u16::from_be_bytes)
+    } else {
+        let bytes = bytes.strip_prefix(&[0xef, 0xbb, 0xbf]).unwrap_or(bytes);
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// Remove a `;` comment from the end of `line`, ignoring semicolons inside of quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+fn parse_entry(line: &str) -> Entry {
+    // The key ends at the first `=` that is outside of quotes.
+    let mut quoted = false;
+    let mut separator = None;
+
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '=' if !quoted => {
+                separator = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    match separator {
+        Some(i) => Entry {
+            key: Some(line[..i].trim().to_owned()),
+            values: split_values(&line[i + 1..]),
+        },
+        None => Entry {
+            key: None,
+            values: split_values(line),
+        },
+    }
+}
+
+/// Split a comma-separated list of values, removing quotes (`""` is an escaped quote).
+fn split_values(text: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => values.push(std::mem::take(&mut current).trim().to_owned()),
+            _ => current.push(c),
+        }
+    }
+
+    values.push(current.trim().to_owned());
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INSTALL_INF: &str = r#"
+        ; Comment
+        [Version]
+        signature="$CHICAGO$"
+
+        [Scheme.Reg]
+        HKCU,"Control Panel\Cursors\Schemes","%SCHEME_NAME%",,"%10%\%CUR_DIR%\%pointer%,%10%\%CUR_DIR%\%help%"
+
+        [Strings]
+        CUR_DIR = "Cursors\Theme"
+        SCHEME_NAME = "Theme ; Name"
+        pointer = "Arrow.ani"
+        help    = "Help.ani"
+    "#;
+
+    #[test]
+    fn parse_sections() {
+        let inf = Inf::parse(INSTALL_INF).expect("expected hardcoded INF to be valid");
+
+        let strings = inf.section("strings").unwrap();
+        assert_eq!(strings.value("SCHEME_NAME"), Some("Theme ; Name"));
+        assert_eq!(strings.value("pointer"), Some("Arrow.ani"));
+
+        let scheme = inf.section("Scheme.Reg").unwrap();
+        let values = scheme.entries()[0].values();
+        assert_eq!(values.len(), 5);
+        assert_eq!(values[3], "");
+        assert_eq!(
+            inf.expand(&values[4]),
+            r"%10%\Cursors\Theme\Arrow.ani,%10%\Cursors\Theme\Help.ani"
+        );
+    }
+
+    #[test]
+    fn utf16_encoding() {
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend("[A]\nkey = value".encode_utf16().flat_map(u16::to_le_bytes));
+
+        let inf = Inf::parse(&decode_text(&bytes)).unwrap();
+        assert_eq!(inf.section("A").unwrap().value("key"), Some("value"));
+    }
+
+    #[test]
+    fn entry_outside_section() {
+        assert!(Inf::parse("key = value").is_err());
+    }
+}
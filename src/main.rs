@@ -9,21 +9,45 @@
     clippy::pedantic
 )]
 
+mod archive;
 mod commands;
 mod config;
 mod context;
+mod desktop;
+mod exit;
+mod fingerprint;
+mod frame_cache;
+mod hyprcursor;
+mod inf;
+mod names;
 mod package;
+mod progress;
+mod roles;
+mod scale;
+mod staging;
+mod svg;
+mod template;
+mod transform;
+mod trim;
 mod verbosity;
+mod watch;
+mod windows;
+mod xcursor_file;
 
 use std::io::Write as _;
+use std::path::PathBuf;
 use std::process::ExitCode;
-use std::{env, io, panic};
+use std::{env, io, panic, path};
 
-use clap::Parser as _;
+use anyhow::{bail, Context as _};
+use clap::error::ErrorKind;
+use clap::{CommandFactory as _, Parser as _};
 use colored::Colorize as _;
 use tracing_subscriber::EnvFilter;
 
 use crate::context::Context;
+use crate::exit::Failure;
+use crate::package::Package;
 use crate::verbosity::{Verbosity, VerbosityLevel};
 
 #[derive(Debug, clap::Parser)]
@@ -34,35 +58,82 @@ use crate::verbosity::{Verbosity, VerbosityLevel};
 )]
 struct Parser {
     #[clap(subcommand)]
-    subcommand: commands::Subcommand,
+    subcommand: Option<commands::Subcommand>,
 
     #[clap(flatten)]
     verbosity: Verbosity,
+
+    /// Run as if started in <DIR> (the package containing `Cursor.toml`) instead of the
+    /// current directory.
+    #[clap(short = 'C', long, global = true, value_name = "DIR")]
+    package: Option<PathBuf>,
+
+    /// Write the man pages of the command and its subcommands to <DIR> (for packagers).
+    #[clap(long, hide = true, value_name = "DIR", exclusive = true)]
+    generate_man: Option<PathBuf>,
 }
 
 fn main() -> ExitCode {
-    try_main().unwrap_or_else(|err| {
+    setup_panic_hook();
+
+    let args = Parser::parse();
+
+    // Either a subcommand or `--generate-man` is expected.
+    match (&args.subcommand, &args.generate_man) {
+        (None, None) => Parser::command()
+            .error(ErrorKind::MissingSubcommand, "a subcommand is required")
+            .exit(),
+        (Some(_), Some(_)) => Parser::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--generate-man can't be used with a subcommand",
+            )
+            .exit(),
+        _ => {}
+    }
+    let level = args.verbosity.level();
+
+    try_main(&args).unwrap_or_else(|err| {
+        // Scripts can still tell what failed from the exit code (see `exit::Failure`).
+        if level == VerbosityLevel::Silent {
+            return Failure::of(&err).into();
+        }
+
         let mut stderr = io::stderr().lock();
         _ = writeln!(stderr, "{}", "ani-to-xcursor failed".bold().red());
 
-        for cause in err.chain() {
+        for cause in exit::causes(&err) {
             _ = writeln!(stderr, "  {}: {}", "Cause".bold(), cause);
         }
 
-        ExitCode::FAILURE
+        Failure::of(&err).into()
     })
 }
 
-fn try_main() -> anyhow::Result<ExitCode> {
-    setup_panic_hook();
-
-    let args = Parser::parse();
+fn try_main(args: &Parser) -> anyhow::Result<ExitCode> {
     let level = args.verbosity.level();
     setup_tracing(level);
 
     let mut ctx = Context::default();
     ctx = ctx.with_level(level);
-    args.subcommand.run(&mut ctx).map(|()| ExitCode::SUCCESS)
+
+    if let Some(ref dir) = args.package {
+        let path = path::absolute(dir).context("failed to resolve package directory")?;
+
+        if !path.is_dir() {
+            bail!("package directory not found: {}", dir.display());
+        }
+
+        ctx = ctx.with_package(Package::new(path));
+    }
+
+    if let Some(ref dir) = args.generate_man {
+        commands::completions::generate_man(dir)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let subcommand = args.subcommand.as_ref().expect("checked by `main`");
+    subcommand.run(&mut ctx).map(|()| ExitCode::SUCCESS)
 }
 
 fn setup_panic_hook() {
@@ -114,13 +185,24 @@ fn setup_tracing(level: VerbosityLevel) {
             tracing_subscriber::fmt::layer()
                 .event_format(tracing_subscriber::fmt::format().pretty())
                 .with_thread_ids(true)
-                .with_writer(io::stderr),
+                .with_writer(|| progress::Writer),
         );
 
         subscriber.init();
     } else {
-        let subscriber = registry.with(tracing_subscriber::fmt::layer().with_writer(io::stderr));
+        let subscriber =
+            registry.with(tracing_subscriber::fmt::layer().with_writer(|| progress::Writer));
 
         subscriber.init();
     }
 }
+
+/// A directory of its own for a test, which is removed when it is dropped (even if the test
+/// fails).
+#[cfg(test)]
+fn test_dir() -> tempfile::TempDir {
+    tempfile::Builder::new()
+        .prefix("ani-to-xcursor-test-")
+        .tempdir()
+        .expect("expected to create a temporary directory")
+}
@@ -0,0 +1,264 @@
+//! The cursor names X applications look up.
+//!
+//! Applications ask the theme for a cursor by name: toolkits following the freedesktop cursor
+//! spec (and CSS) use names like `default` or `pointer`, while older ones use the names from the
+//! X11 cursor font (e.g., `left_ptr` or `hand2`) or hashes of the cursor's bitmap.
+
+/// Every standard cursor name, along with the legacy X11 and hashed names that refer to the
+/// same cursor.
+///
+/// No name may appear more than once, so every name belongs to exactly one cursor.
+pub const CURSORS: &[(&str, &[&str])] = &[
+    (
+        "default",
+        &[
+            "left_ptr",
+            "arrow",
+            "top_left_arrow",
+            "left_arrow",
+            "X_cursor",
+        ],
+    ),
+    ("context-menu", &[]),
+    (
+        "help",
+        &[
+            "question_arrow",
+            "whats_this",
+            "left_ptr_help",
+            "5c6cd98b3f3ebcb1f9c7f1c204630408",
+            "d9ce0ab605698f320427677b458ad60b",
+        ],
+    ),
+    (
+        "pointer",
+        &[
+            "hand2",
+            "hand1",
+            "hand",
+            "pointing_hand",
+            "e29285e634086352946a0e7090d73106",
+            "9d800788f1b08800ae810202380a0822",
+        ],
+    ),
+    (
+        "progress",
+        &[
+            "left_ptr_watch",
+            "half-busy",
+            "00000000000000020006000e7e9ffc3f",
+            "08e8e1c95fe2fc01f976f1e063a24ccd",
+            "3ecb610c1bf2410f44200f48c40d3599",
+        ],
+    ),
+    ("wait", &["watch"]),
+    ("cell", &["plus"]),
+    (
+        "crosshair",
+        &["cross", "cross_reverse", "diamond_cross", "tcross"],
+    ),
+    ("text", &["xterm", "ibeam"]),
+    ("vertical-text", &["048008013003cff3c00c801001200000"]),
+    (
+        "alias",
+        &[
+            "link",
+            "dnd-link",
+            "3085a0e285430894940527032f8b26df",
+            "640fb0e74195791501fd1ed57b41487f",
+            "a2a266d0498c3104214a47bd64ab0fc8",
+        ],
+    ),
+    (
+        "copy",
+        &[
+            "dnd-copy",
+            "1081e37283d90000800003c07f3ef6bf",
+            "6407b0e94181790501fd1e167b474872",
+            "b66166c04f8c3109214a4fbd64a50fc8",
+        ],
+    ),
+    (
+        "move",
+        &[
+            "fleur",
+            "size_all",
+            "all-scroll",
+            "dnd-move",
+            "4498f0e0c1937ffe01fd06f973665830",
+            "9081237383d90e509aa00f00170e968f",
+        ],
+    ),
+    (
+        "not-allowed",
+        &[
+            "no-drop",
+            "dnd-no-drop",
+            "crossed_circle",
+            "forbidden",
+            "circle",
+            "03b6e0fcb3499374a867c041f52298f0",
+        ],
+    ),
+    ("grab", &["openhand"]),
+    ("grabbing", &["closedhand"]),
+    (
+        "col-resize",
+        &["split_h", "14fef782d02440884392942c11205230"],
+    ),
+    (
+        "row-resize",
+        &["split_v", "2870a09082c103050810ffdffffe0204"],
+    ),
+    (
+        "ns-resize",
+        &[
+            "size_ver",
+            "sb_v_double_arrow",
+            "v_double_arrow",
+            "n-resize",
+            "s-resize",
+            "top_side",
+            "bottom_side",
+            "00008160000006810000408080010102",
+        ],
+    ),
+    (
+        "ew-resize",
+        &[
+            "size_hor",
+            "sb_h_double_arrow",
+            "h_double_arrow",
+            "e-resize",
+            "w-resize",
+            "left_side",
+            "right_side",
+            "028006030e0e7ebffc7f7070c0600140",
+        ],
+    ),
+    (
+        "nwse-resize",
+        &[
+            "size_fdiag",
+            "bd_double_arrow",
+            "nw-resize",
+            "se-resize",
+            "top_left_corner",
+            "bottom_right_corner",
+            "c7088f0f3e6c8088236ef8e1e3e70000",
+        ],
+    ),
+    (
+        "nesw-resize",
+        &[
+            "size_bdiag",
+            "fd_double_arrow",
+            "ne-resize",
+            "sw-resize",
+            "top_right_corner",
+            "bottom_left_corner",
+            "fcf1c3c7cd4491d801f1e1c78f100000",
+        ],
+    ),
+    ("zoom-in", &["zoom_in", "f41c0e382c94c0958e07017e42b00462"]),
+    (
+        "zoom-out",
+        &["zoom_out", "f41c0e382c97c0938e07017e42800402"],
+    ),
+    ("up-arrow", &["up_arrow", "center_ptr", "sb_up_arrow"]),
+    ("pencil", &["draft"]),
+    // Windows roles without an X equivalent, kept so their cursors are not reported.
+    ("pin", &[]),
+    ("person", &[]),
+];
+
+/// Cursors shaped like an arrow or a hand, which a mirrored (left-handed) theme flips to point
+/// the other way. The rest are kept as they are: flipping them would only turn a glyph or badge
+/// backwards (e.g., the question mark of `help`), or turn `nwse-resize` into `nesw-resize`.
+pub const MIRRORED: &[&str] = &[
+    "default",
+    "context-menu",
+    "pointer",
+    "alias",
+    "copy",
+    "grab",
+    "grabbing",
+];
+
+/// Cursors that applications use all the time, so a theme without them looks broken (the
+/// cursor falls back to the inherited theme).
+pub const CRITICAL: &[&str] = &[
+    "default",
+    "pointer",
+    "text",
+    "wait",
+    "progress",
+    "help",
+    "crosshair",
+    "move",
+    "not-allowed",
+    "alias",
+    "copy",
+];
+
+/// The standard name of the cursor `name` refers to, if it is a known name.
+pub fn canonical(name: &str) -> Option<&'static str> {
+    CURSORS
+        .iter()
+        .find(|&&(standard, aliases)| standard == name || aliases.contains(&name))
+        .map(|&(standard, _)| standard)
+}
+
+/// The other names of the standard cursor `name`.
+pub fn aliases(name: &str) -> &'static [&'static str] {
+    CURSORS
+        .iter()
+        .find(|&&(standard, _)| standard == name)
+        .map_or(&[], |&(_, aliases)| aliases)
+}
+
+/// The [critical](CRITICAL) cursors that none of `names` refer to.
+pub fn missing_critical<'a>(names: impl IntoIterator<Item = &'a str>) -> Vec<&'static str> {
+    let provided = names.into_iter().filter_map(canonical).collect::<Vec<_>>();
+
+    CRITICAL
+        .iter()
+        .copied()
+        .filter(|name| !provided.contains(name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::iter;
+
+    use super::*;
+
+    #[test]
+    fn names_are_unique() {
+        let mut seen = HashSet::new();
+
+        for &(name, aliases) in CURSORS {
+            for name in iter::once(&name).chain(aliases) {
+                assert!(seen.insert(*name), "duplicate cursor name: {name}");
+            }
+        }
+
+        for name in CRITICAL {
+            assert_eq!(canonical(name), Some(*name));
+        }
+    }
+
+    #[test]
+    fn critical() {
+        assert_eq!(canonical("hand2"), Some("pointer"));
+        assert_eq!(canonical("sparkles"), None);
+
+        let missing = missing_critical(["left_ptr", "hand2", "xterm", "watch", "progress"]);
+        assert_eq!(
+            missing,
+            ["help", "crosshair", "move", "not-allowed", "alias", "copy"]
+        );
+    }
+}
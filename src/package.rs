@@ -12,13 +12,41 @@ impl Package {
         Self { path, build }
     }
 
+    pub fn as_path(&self) -> &Path {
+        &self.path
+    }
+
     pub fn config(&self) -> PathBuf {
         self.path.join("Cursor.toml")
     }
 
+    /// Return a copy of the package that is built in `dir` (relative to the package) instead
+    /// of `build`.
+    pub fn with_build_dir(self, dir: &Path) -> Self {
+        let build = Build::new(self.path.join(dir));
+        Self { build, ..self }
+    }
+
     pub const fn build(&self) -> &Build {
         &self.build
     }
+
+    /// Return a copy of the package that builds the theme `name` of a package with several
+    /// themes, in `build/themes/<name>`.
+    pub fn with_theme(self, name: &str) -> Self {
+        let build = Build::new(self.build.as_path().join("themes").join(name));
+        Self { build, ..self }
+    }
+
+    /// The build directory for the theme profile `name`.
+    pub fn profile_build(&self, name: &str) -> Build {
+        Build::new(self.build.as_path().join("profiles").join(name))
+    }
+
+    /// The build directory for the theme variant `name`.
+    pub fn variant_build(&self, name: &str) -> Build {
+        Build::new(self.build.as_path().join("variants").join(name))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -42,9 +70,40 @@ impl Build {
         self.path.join("frames")
     }
 
+    /// The file recording which inputs each cursor was last built from.
+    pub fn fingerprints(&self) -> PathBuf {
+        self.path.join(".fingerprints")
+    }
+
+    /// The frames extracted by previous builds, keyed by the contents of their input (see
+    /// [`FrameCache`](crate::frame_cache::FrameCache)).
+    pub fn cache(&self) -> PathBuf {
+        self.path.join("cache")
+    }
+
+    /// The file recording the hash of each extracted frame when it was generated.
+    pub fn frame_hashes(&self) -> PathBuf {
+        self.path.join(".frame-hashes")
+    }
+
     pub const fn theme(&self) -> &Theme {
         &self.theme
     }
+
+    /// The directory hyprcursor shapes are assembled in, before being packed into the theme.
+    pub fn hyprcursor_shapes(&self) -> PathBuf {
+        self.path.join("hyprcursors")
+    }
+
+    /// The directory frames are exported to as SVG files (see `build --export-svg`).
+    pub fn svg(&self) -> PathBuf {
+        self.path.join("svg")
+    }
+
+    /// The directory the theme is staged in by the `package` command.
+    pub fn dist(&self) -> PathBuf {
+        self.path.join("dist")
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -68,4 +127,13 @@ impl Theme {
     pub fn index_theme(&self) -> PathBuf {
         self.path.join("index.theme")
     }
+
+    /// The hyprcursor manifest, which names the theme for Hyprland.
+    pub fn manifest(&self) -> PathBuf {
+        self.path.join("manifest.hl")
+    }
+
+    pub fn hyprcursors(&self) -> PathBuf {
+        self.path.join(crate::hyprcursor::CURSORS_DIRECTORY)
+    }
 }
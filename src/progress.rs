@@ -0,0 +1,217 @@
+//! Show the progress of a build on stderr.
+//!
+//! The display is a bar for the whole theme, followed by a line for each cursor being built.
+//! Logs are written through [`Writer`], which moves the display below them so the two don't
+//! garble each other.
+
+use std::error::Error;
+use std::io::{self, IsTerminal as _, Write};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use tracing::warn;
+use xcursor::ProgressObserver;
+
+use crate::verbosity::VerbosityLevel;
+
+/// The progress currently being shown, if any.
+static DISPLAY: Mutex<Option<Display>> = Mutex::new(None);
+
+/// The width of the bar for the whole theme, in characters.
+const THEME_WIDTH: usize = 30;
+
+/// The width of the bar for each cursor, in characters.
+const CURSOR_WIDTH: usize = 20;
+
+#[derive(Debug)]
+struct Display {
+    theme: String,
+    total: usize,
+    done: usize,
+    cursors: Vec<CursorProgress>,
+
+    /// The number of lines printed by the last draw, which are cleared by the next one.
+    lines: usize,
+}
+
+#[derive(Debug)]
+struct CursorProgress {
+    name: String,
+
+    /// The number of frames written so far, and how many there are.
+    frames: Option<(usize, usize)>,
+}
+
+impl Display {
+    fn clear(&mut self, stderr: &mut impl Write) -> io::Result<()> {
+        if self.lines > 0 {
+            // Move to the start of the first line, then clear everything below it.
+            write!(stderr, "\x1b[{}A\r\x1b[J", self.lines)?;
+            self.lines = 0;
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self, stderr: &mut impl Write) -> io::Result<()> {
+        self.clear(stderr)?;
+
+        writeln!(
+            stderr,
+            "Building {} {} {}/{}",
+            self.theme,
+            bar(self.done, self.total, THEME_WIDTH),
+            self.done,
+            self.total
+        )?;
+
+        let width = self
+            .cursors
+            .iter()
+            .map(|cursor| cursor.name.len())
+            .max()
+            .unwrap_or_default();
+
+        for cursor in &self.cursors {
+            match cursor.frames {
+                Some((written, total)) => writeln!(
+                    stderr,
+                    "  {:width$} {} {written}/{total} frames",
+                    cursor.name,
+                    bar(written, total, CURSOR_WIDTH),
+                )?,
+                None => writeln!(stderr, "  {:width$} ...", cursor.name)?,
+            }
+        }
+
+        self.lines = 1 + self.cursors.len();
+        stderr.flush()
+    }
+}
+
+fn lock() -> MutexGuard<'static, Option<Display>> {
+    DISPLAY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Apply `f` to the display and redraw it, if progress is being shown.
+fn update(f: impl FnOnce(&mut Display)) {
+    if let Some(ref mut display) = *lock() {
+        f(display);
+        _ = display.draw(&mut io::stderr().lock());
+    }
+}
+
+/// Shows the progress of a build on stderr, and logs its warnings.
+///
+/// Nothing is shown in quiet mode, or when stderr isn't a terminal (e.g., in a log file).
+#[derive(Debug, Clone, Copy)]
+pub struct Terminal {
+    level: VerbosityLevel,
+}
+
+impl Terminal {
+    pub fn new(level: VerbosityLevel) -> Self {
+        Self { level }
+    }
+}
+
+impl ProgressObserver for Terminal {
+    fn theme_started(&self, theme: &str, total: usize) {
+        if self.level < VerbosityLevel::Default || !io::stderr().is_terminal() {
+            return;
+        }
+
+        *lock() = Some(Display {
+            theme: theme.to_owned(),
+            total,
+            done: 0,
+            cursors: Vec::new(),
+            lines: 0,
+        });
+        update(|_| {});
+    }
+
+    fn cursor_started(&self, name: &str) {
+        update(|display| {
+            display.cursors.push(CursorProgress {
+                name: name.to_owned(),
+                frames: None,
+            });
+        });
+    }
+
+    fn frame_extracted(&self, name: &str, written: usize, total: usize) {
+        update(|display| {
+            if let Some(cursor) = display
+                .cursors
+                .iter_mut()
+                .find(|cursor| cursor.name == name)
+            {
+                cursor.frames = Some((written, total));
+            }
+        });
+    }
+
+    fn warning(&self, _name: &str, message: &str) {
+        // The cursor is already named by the span it is built in.
+        warn!("{message}");
+    }
+
+    fn cursor_finished(&self, name: &str, _error: Option<&(dyn Error + 'static)>) {
+        update(|display| {
+            display.cursors.retain(|cursor| cursor.name != name);
+            display.done += 1;
+        });
+    }
+
+    fn theme_finished(&self) {
+        if let Some(mut display) = lock().take() {
+            _ = display.clear(&mut io::stderr().lock());
+        }
+    }
+}
+
+/// Render a bar that is `done / total` full.
+fn bar(done: usize, total: usize, width: usize) -> String {
+    let filled = (done * width)
+        .checked_div(total)
+        .unwrap_or(width)
+        .min(width);
+    format!("[{}{}]", "#".repeat(filled), " ".repeat(width - filled))
+}
+
+/// Writes to stderr without garbling the progress display (for logging).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Writer;
+
+impl Write for Writer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut display = lock();
+        let mut stderr = io::stderr().lock();
+
+        let Some(ref mut display) = *display else {
+            return stderr.write(buf);
+        };
+
+        display.clear(&mut stderr)?;
+        stderr.write_all(buf)?;
+        display.draw(&mut stderr)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bars() {
+        assert_eq!(bar(0, 4, 4), "[    ]");
+        assert_eq!(bar(1, 4, 4), "[#   ]");
+        assert_eq!(bar(3, 3, 4), "[####]");
+        assert_eq!(bar(0, 0, 4), "[####]");
+    }
+}
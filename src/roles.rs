@@ -0,0 +1,183 @@
+//! Presets mapping the cursor file names used by popular sources of Windows cursors to X cursor
+//! names (see `init --preset`).
+//!
+//! Each table maps the stem of a file name to the standard name of the cursor it provides; the
+//! other names of that cursor become its aliases, as usual. Names are compared ignoring case,
+//! punctuation and leading numbers (e.g., `01. Normal Select.ani` matches `normalselect`).
+
+/// A naming scheme for the cursor files of a pack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Preset {
+    /// The files Windows installs in `C:\Windows\Cursors` (e.g., `aero_busy.ani`), in any size
+    /// (e.g., `aero_arrow_xl.cur`).
+    Windows,
+
+    /// The roles as the Control Panel labels them (e.g., `Normal Select.cur` or `Working in
+    /// Background.ani`), which many cursor packs are named after.
+    ControlPanel,
+
+    /// The short names used by the Windows builds of Bibata and similar themes (e.g.,
+    /// `Work.ani` or `Dgn1.cur`).
+    Bibata,
+}
+
+const WINDOWS: &[(&str, &str)] = &[
+    ("aeroarrow", "default"),
+    ("aerohelpsel", "help"),
+    ("aeroworking", "progress"),
+    ("aerobusy", "wait"),
+    ("cross", "crosshair"),
+    ("beam", "text"),
+    ("aeropen", "pencil"),
+    ("aerounavail", "not-allowed"),
+    ("aerons", "ns-resize"),
+    ("aeroew", "ew-resize"),
+    ("aeronwse", "nwse-resize"),
+    ("aeronesw", "nesw-resize"),
+    ("aeromove", "move"),
+    ("aeroup", "up-arrow"),
+    ("aerolink", "pointer"),
+    ("aeropin", "pin"),
+    ("aeroperson", "person"),
+];
+
+/// The size suffixes of the files Windows installs (e.g., `aero_arrow_l.cur` for large, or
+/// `beam_r.cur`), checked longest first.
+const WINDOWS_SUFFIXES: &[&str] = &["_xl", "_rm", "_l", "_m", "_r"];
+
+const CONTROL_PANEL: &[(&str, &str)] = &[
+    ("normalselect", "default"),
+    ("helpselect", "help"),
+    ("workinginbackground", "progress"),
+    ("busy", "wait"),
+    ("precisionselect", "crosshair"),
+    ("textselect", "text"),
+    ("handwriting", "pencil"),
+    ("unavailable", "not-allowed"),
+    ("verticalresize", "ns-resize"),
+    ("horizontalresize", "ew-resize"),
+    ("diagonalresize1", "nwse-resize"),
+    ("diagonalresize2", "nesw-resize"),
+    ("move", "move"),
+    ("alternateselect", "up-arrow"),
+    ("linkselect", "pointer"),
+    ("locationselect", "pin"),
+    ("personselect", "person"),
+];
+
+const BIBATA: &[(&str, &str)] = &[
+    ("pointer", "default"),
+    ("help", "help"),
+    ("work", "progress"),
+    ("busy", "wait"),
+    ("cross", "crosshair"),
+    ("text", "text"),
+    ("handwriting", "pencil"),
+    // Misspelled in the files of several releases.
+    ("unavailiable", "not-allowed"),
+    ("unavailable", "not-allowed"),
+    ("vert", "ns-resize"),
+    ("horz", "ew-resize"),
+    ("dgn1", "nwse-resize"),
+    ("dgn2", "nesw-resize"),
+    ("move", "move"),
+    ("alternate", "up-arrow"),
+    ("link", "pointer"),
+    ("pin", "pin"),
+    ("person", "person"),
+];
+
+impl Preset {
+    /// The file stems of the preset (normalized, see [`normalize`]) and the X cursor name each
+    /// one provides.
+    pub fn table(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Windows => WINDOWS,
+            Self::ControlPanel => CONTROL_PANEL,
+            Self::Bibata => BIBATA,
+        }
+    }
+
+    /// The X cursor name the file `file_name` provides, if the preset lists it.
+    pub fn role(self, file_name: &str) -> Option<&'static str> {
+        let stem = file_name
+            .rsplit_once('.')
+            .map_or(file_name, |(stem, _)| stem);
+        let lookup = |stem: &str| {
+            let stem = normalize(stem);
+
+            self.table()
+                .iter()
+                .find(|&&(name, _)| name == stem)
+                .map(|&(_, role)| role)
+        };
+
+        lookup(stem).or_else(|| {
+            if self != Self::Windows {
+                return None;
+            }
+
+            let lowercase = stem.to_ascii_lowercase();
+            WINDOWS_SUFFIXES
+                .iter()
+                .find_map(|suffix| lowercase.strip_suffix(suffix))
+                .and_then(lookup)
+        })
+    }
+}
+
+/// Lowercase `stem`, leaving out anything but letters and digits, and any leading numbers
+/// (which some packs use to sort their files).
+fn normalize(stem: &str) -> String {
+    stem.trim_start_matches(|c: char| !c.is_ascii_alphabetic())
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::ValueEnum as _;
+
+    use super::*;
+    use crate::names;
+
+    #[test]
+    fn presets() {
+        for preset in Preset::value_variants() {
+            for &(_, role) in preset.table() {
+                assert_eq!(names::canonical(role), Some(role), "{preset:?}");
+            }
+        }
+
+        let cases = [
+            (Preset::Windows, "aero_busy.ani", Some("wait")),
+            (Preset::Windows, "aero_arrow_xl.cur", Some("default")),
+            (Preset::Windows, "beam_r.cur", Some("text")),
+            (Preset::Windows, "Busy.ani", None),
+            (
+                Preset::ControlPanel,
+                "01. Normal Select.ani",
+                Some("default"),
+            ),
+            (
+                Preset::ControlPanel,
+                "Working in Background.ani",
+                Some("progress"),
+            ),
+            (
+                Preset::ControlPanel,
+                "Diagonal Resize 2.cur",
+                Some("nesw-resize"),
+            ),
+            (Preset::Bibata, "Pointer.cur", Some("default")),
+            (Preset::Bibata, "Unavailiable.cur", Some("not-allowed")),
+            (Preset::Bibata, "Sparkles.ani", None),
+        ];
+
+        for (preset, file_name, expected) in cases {
+            assert_eq!(preset.role(file_name), expected, "{preset:?}: {file_name}");
+        }
+    }
+}
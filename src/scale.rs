@@ -0,0 +1,75 @@
+//! Resample cursor images to sizes the original cursor doesn't provide.
+
+use ani::de::Hotspot;
+use ico::IconImage;
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+
+use crate::config::Filter;
+
+/// Resample `image` so it is `size` pixels wide, keeping its aspect ratio.
+pub fn resize(image: &IconImage, size: u32, filter: Filter) -> anyhow::Result<IconImage> {
+    let (width, height) = (image.width(), image.height());
+    let source = RgbaImage::from_raw(width, height, image.rgba_data().to_vec())
+        .ok_or_else(|| anyhow::anyhow!("image data does not match its dimensions"))?;
+
+    let scaled_height = scale(height, width, size).max(1);
+    let scaled = imageops::resize(&source, size, scaled_height, filter_type(filter));
+
+    Ok(IconImage::from_rgba_data(
+        size,
+        scaled_height,
+        scaled.into_raw(),
+    ))
+}
+
+/// Move `hotspot` from an image `from` pixels wide to one `to` pixels wide.
+pub fn hotspot(hotspot: Hotspot, from: u32, to: u32) -> Hotspot {
+    let last = to.saturating_sub(1);
+    let coordinate = |value: u16| {
+        let scaled = scale(u32::from(value), from, to).min(last);
+        u16::try_from(scaled).unwrap_or(u16::MAX)
+    };
+
+    Hotspot::new(coordinate(hotspot.x()), coordinate(hotspot.y()))
+}
+
+/// Scale `value` by `to / from`, rounding to the nearest integer.
+fn scale(value: u32, from: u32, to: u32) -> u32 {
+    if from == 0 {
+        return 0;
+    }
+
+    let scaled = (u64::from(value) * u64::from(to) + u64::from(from) / 2) / u64::from(from);
+    u32::try_from(scaled).unwrap_or(u32::MAX)
+}
+
+const fn filter_type(filter: Filter) -> FilterType {
+    match filter {
+        Filter::Nearest => FilterType::Nearest,
+        Filter::Triangle => FilterType::Triangle,
+        Filter::CatmullRom => FilterType::CatmullRom,
+        Filter::Gaussian => FilterType::Gaussian,
+        Filter::Lanczos3 => FilterType::Lanczos3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_hotspot() {
+        assert_eq!(hotspot(Hotspot::new(4, 31), 32, 48), Hotspot::new(6, 47));
+        assert_eq!(hotspot(Hotspot::new(5, 0), 32, 24), Hotspot::new(4, 0));
+    }
+
+    #[test]
+    fn resizes_image() {
+        let image = IconImage::from_rgba_data(2, 1, vec![0xff; 2 * 4]);
+        let scaled = resize(&image, 4, Filter::Nearest).unwrap();
+
+        assert_eq!((scaled.width(), scaled.height()), (4, 2));
+        assert_eq!(scaled.rgba_data(), &[0xff; 4 * 2 * 4][..]);
+    }
+}
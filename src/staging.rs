@@ -0,0 +1,234 @@
+//! Staging directories, which themes are built in before they replace the last one (see
+//! `build`).
+//!
+//! A build directory holds the theme in `theme`, the theme being built in
+//! `.theme.tmp-<id>-<suffix>` and, for a moment, the theme being replaced in
+//! `.theme.old-<id>-<suffix>` (where `<id>` is the ID of the process and `<suffix>` is random;
+//! see [`xcursor::ThemeBuilder::build_with_hooks`]).
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+
+use tracing::info;
+
+use crate::package::Theme;
+
+/// The prefixes of the directories `build` stages themes in.
+pub const PREFIXES: &[&str] = &[".theme.tmp-", ".theme.old-"];
+
+/// Whether `file_name` is a staging directory (or a theme being replaced).
+pub fn is_staging(file_name: &OsStr) -> bool {
+    staging_id(file_name).is_some()
+}
+
+/// The ID of the process a staging directory named `file_name` belongs to, if it is one.
+fn staging_id(file_name: &OsStr) -> Option<u32> {
+    let file_name = file_name.to_str()?;
+    let rest = PREFIXES
+        .iter()
+        .find_map(|prefix| file_name.strip_prefix(prefix))?;
+    let id = rest.split_once('-').map_or(rest, |(id, _)| id);
+
+    if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    // IDs too large to be a process ID still name a staging directory, but no running build.
+    Some(id.parse().unwrap_or(u32::MAX))
+}
+
+/// Remove the staging directories a build that was interrupted (e.g., with Ctrl+C) left behind
+/// in `build_dir`.
+///
+/// The directories of processes that are still running are left alone, since another `build`
+/// (e.g., `build --watch`) may be using them.
+pub fn remove_stale(build_dir: &Path) -> io::Result<()> {
+    let entries = match fs::read_dir(build_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    for entry in entries {
+        let entry = entry?;
+        let Some(id) = staging_id(&entry.file_name()) else {
+            continue;
+        };
+
+        // This process only stages one theme at a time, so its own directories are stale too.
+        if (id != process::id() && is_running(id)) || !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        fs::remove_dir_all(entry.path())?;
+        info!(
+            "removed stale staging directory: {:#}",
+            entry.path().display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Whether the process `id` is running (or might be, if that can't be told).
+#[cfg(unix)]
+fn is_running(id: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(id) else {
+        return false;
+    };
+
+    if pid == 0 {
+        return false;
+    }
+
+    // SAFETY: Signal 0 only checks whether the process exists (and may be signaled).
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+
+    // The process exists, but belongs to another user.
+    io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Whether the process `id` is running (or might be, if that can't be told).
+#[cfg(not(unix))]
+fn is_running(id: u32) -> bool {
+    id != u32::MAX
+}
+
+/// Copy what carries over from `theme` (if it was built before) to `staged`.
+///
+/// That is the Xcursors and hyprcursors of the cursors in `built`, so the ones that are up to
+/// date don't need to be rebuilt, and in a `partial` build, everything belonging to the cursors
+/// left out. The links of the cursors in `built` (and everything belonging to cursors that were
+/// removed) are left behind, to be created again by the build.
+pub fn stage(
+    theme: &Theme,
+    staged: &Theme,
+    built: &HashSet<&str>,
+    partial: bool,
+) -> io::Result<()> {
+    fs::create_dir_all(staged.as_path())?;
+
+    let cursors_dir = theme.cursors();
+    for entry in read_dir(&cursors_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(name) = file_name.to_str() else {
+            continue;
+        };
+
+        let file_type = entry.file_type()?;
+        let target = if file_type.is_symlink() {
+            Some(fs::read_link(entry.path())?)
+        } else {
+            None
+        };
+
+        // Aliases link to the cursor they belong to (by name, or by the absolute path older
+        // builds used), while cursors are either files or links into `build/frames`.
+        let owner = match target {
+            Some(ref target)
+                if target.is_relative() || target.parent() == Some(cursors_dir.as_path()) =>
+            {
+                target.file_name().and_then(OsStr::to_str)
+            }
+            _ => Some(name),
+        };
+
+        let keep = match owner {
+            Some(owner) if built.contains(owner) => file_type.is_file() && owner == name,
+            _ => partial,
+        };
+
+        if keep {
+            fs::create_dir_all(staged.cursors())?;
+            copy(
+                &entry.path(),
+                target.as_deref(),
+                &staged.cursors().join(&file_name),
+            )?;
+        }
+    }
+
+    for entry in read_dir(&theme.hyprcursors())? {
+        let entry = entry?;
+        let path = entry.path();
+
+        let owner = path.file_stem().and_then(OsStr::to_str);
+        if owner.is_some_and(|owner| built.contains(owner)) || partial {
+            fs::create_dir_all(staged.hyprcursors())?;
+            copy(&path, None, &staged.hyprcursors().join(entry.file_name()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the entries of `dir`, or none if it doesn't exist.
+fn read_dir(dir: &Path) -> io::Result<impl Iterator<Item = io::Result<fs::DirEntry>>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => Some(entries),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+
+    Ok(entries.into_iter().flatten())
+}
+
+/// Copy the file at `source` (a link to `target`, if it is a link) to `destination`.
+///
+/// Files are hard linked where possible, since they are only ever replaced rather than written
+/// to (see `build`).
+fn copy(source: &Path, target: Option<&Path>, destination: &Path) -> io::Result<()> {
+    if let Some(target) = target {
+        return xcursor::symlink(target, destination);
+    }
+
+    if fs::hard_link(source, destination).is_err() {
+        fs::copy(source, destination)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staging_names() {
+        assert!(is_staging(OsStr::new(".theme.tmp-1234")));
+        assert!(is_staging(OsStr::new(".theme.tmp-1234-x3Fk9a")));
+        assert!(is_staging(OsStr::new(".theme.old-1-x3Fk9a")));
+        assert!(!is_staging(OsStr::new(".theme.tmp--x3Fk9a")));
+        assert!(!is_staging(OsStr::new(".theme.tmp-")));
+        assert!(!is_staging(OsStr::new(".theme.tmp-12a")));
+        assert!(!is_staging(OsStr::new("theme")));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn keep_running_builds() {
+        let temp = crate::test_dir();
+        let dir = temp.path();
+        let running = dir.join(format!(
+            ".theme.tmp-{}",
+            std::os::unix::process::parent_id()
+        ));
+        let own = dir.join(format!(".theme.old-{}", process::id()));
+        let stale = dir.join(format!(".theme.old-{}", u64::MAX));
+        for dir in [&running, &own, &stale] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        remove_stale(dir).unwrap();
+        let kept = [&running, &own, &stale].map(|dir| dir.is_dir());
+
+        assert_eq!(kept, [true, false, false]);
+    }
+}
@@ -0,0 +1,118 @@
+//! Export the extracted frames as SVG files, for SVG-based cursor theme pipelines.
+//!
+//! Each SVG embeds the largest image of a frame as a bitmap, so the pipeline can re-scale it to
+//! any size. Static cursors are written to `<name>.svg`, and animated cursors to
+//! `<name>/<name>-01.svg`, `<name>/<name>-02.svg` and so on (one per step of the animation).
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context as _;
+use tracing::info;
+use xcursor::{Image, Step};
+
+use crate::config::Filter;
+
+/// Write the SVG files of the cursor `name` to `output_dir`, replacing any from a previous build.
+pub fn export(
+    name: &str,
+    frames_dir: &Path,
+    frames: &[Vec<Image>],
+    steps: &[Step],
+    filter: Filter,
+    output_dir: &Path,
+) -> anyhow::Result<()> {
+    let single = output_dir.join(format!("{name}.svg"));
+    let animated = output_dir.join(name);
+
+    if single.exists() {
+        fs::remove_file(&single).context("failed to remove previous SVG file")?;
+    }
+    if animated.exists() {
+        fs::remove_dir_all(&animated).context("failed to remove previous SVG directory")?;
+    }
+
+    fs::create_dir_all(output_dir).context("failed to create SVG directory")?;
+
+    if steps.len() > 1 {
+        fs::create_dir_all(&animated).context("failed to create SVG directory")?;
+    }
+
+    for (i, step) in steps.iter().enumerate() {
+        let Some(image) = frames[step.frame].iter().max_by_key(|image| image.size()) else {
+            continue;
+        };
+
+        let png = fs::read(frames_dir.join(image.file_name()))
+            .with_context(|| format!("failed to read frame: {}", image.file_name()))?;
+
+        let path = if steps.len() > 1 {
+            animated.join(format!("{name}-{:02}.svg", i + 1))
+        } else {
+            single.clone()
+        };
+
+        fs::write(&path, render(image.size(), &png, filter))
+            .with_context(|| format!("failed to write file: {}", path.display()))?;
+        info!("created file: {:#}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Render an SVG of the given size, embedding the PNG image `png`.
+fn render(size: u32, png: &[u8], filter: Filter) -> String {
+    // Keep the pixels sharp when the pipeline scales the image up.
+    let rendering = match filter {
+        Filter::Nearest => " image-rendering=\"pixelated\"",
+        _ => "",
+    };
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{size}\" height=\"{size}\" \
+        viewBox=\"0 0 {size} {size}\">\n  <image width=\"{size}\" height=\"{size}\"{rendering} \
+        href=\"data:image/png;base64,{}\"/>\n</svg>\n",
+        base64(png),
+    )
+}
+
+/// Encode `data` as (padded) base64.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(char::from(ALPHABET[index as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+}
@@ -0,0 +1,121 @@
+//! Minimal string templating for user-configurable file names.
+//!
+//! Placeholders are written as `{name}` or `{name:WIDTH}`, where a width starting with `0`
+//! pads with zeros instead of spaces (e.g., `{index:03}` renders `7` as `007`). Literal braces
+//! are written as `{{` and `}}`.
+
+use anyhow::{anyhow, bail, Context as _};
+
+/// A value that can be substituted into a template.
+#[derive(Debug, Clone, Copy)]
+pub enum Value<'a> {
+    Str(&'a str),
+    Int(usize),
+}
+
+/// Substitute the placeholders in `template` with the matching entries in `vars`.
+pub fn render(template: &str, vars: &[(&str, Value<'_>)]) -> anyhow::Result<String> {
+    let mut output = String::with_capacity(template.len());
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                let rest = chars.as_str();
+
+                if let Some(stripped) = rest.strip_prefix('{') {
+                    output.push('{');
+                    chars = stripped.chars();
+                    continue;
+                }
+
+                let end = rest
+                    .find('}')
+                    .with_context(|| format!("unclosed placeholder in template: {template:?}"))?;
+                let placeholder = &rest[..end];
+                chars = rest[end + 1..].chars();
+
+                let (name, spec) = placeholder
+                    .split_once(':')
+                    .map_or((placeholder, None), |(name, spec)| (name, Some(spec)));
+
+                let value = vars
+                    .iter()
+                    .find_map(|&(key, value)| (key == name).then_some(value))
+                    .ok_or_else(|| anyhow!("unknown placeholder in template: {{{name}}}"))?;
+
+                push_value(&mut output, value, spec)?;
+            }
+            '}' => {
+                let rest = chars.as_str();
+
+                if let Some(stripped) = rest.strip_prefix('}') {
+                    output.push('}');
+                    chars = stripped.chars();
+                } else {
+                    bail!("unmatched '}}' in template: {template:?}");
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Check whether `template` references the placeholder `name`.
+pub fn references(template: &str, name: &str) -> bool {
+    template.contains(&format!("{{{name}}}")) || template.contains(&format!("{{{name}:"))
+}
+
+fn push_value(output: &mut String, value: Value<'_>, spec: Option<&str>) -> anyhow::Result<()> {
+    let Some(spec) = spec else {
+        match value {
+            Value::Str(s) => output.push_str(s),
+            Value::Int(n) => output.push_str(&n.to_string()),
+        }
+
+        return Ok(());
+    };
+
+    let width = spec
+        .parse::<usize>()
+        .with_context(|| format!("invalid placeholder width: {spec:?}"))?;
+    let zero_pad = spec.starts_with('0');
+
+    let formatted = match (value, zero_pad) {
+        (Value::Int(n), true) => format!("{n:0>width$}"),
+        (Value::Int(n), false) => format!("{n:>width$}"),
+        (Value::Str(s), true) => format!("{s:0>width$}"),
+        (Value::Str(s), false) => format!("{s:<width$}"),
+    };
+
+    output.push_str(&formatted);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_placeholders() {
+        let vars = [("cursor", Value::Str("wait")), ("index", Value::Int(7))];
+        let output = render("{cursor}-{index:03}.png", &vars).unwrap();
+
+        assert_eq!(output, "wait-007.png");
+    }
+
+    #[test]
+    fn render_escaped_braces() {
+        let output = render("{{literal}}", &[]).unwrap();
+
+        assert_eq!(output, "{literal}");
+    }
+
+    #[test]
+    fn render_unknown_placeholder() {
+        assert!(render("{missing}", &[]).is_err());
+        assert!(render("{index", &[("index", Value::Int(0))]).is_err());
+    }
+}
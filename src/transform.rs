@@ -0,0 +1,279 @@
+//! Adjust decoded frames: recolor them (see `[transform]` in `Cursor.toml`), mirror them for
+//! left-handed themes, or draw a shadow under them (see `[shadow]`).
+
+use ani::de::{Ani, Hotspot};
+use ico::IconImage;
+use image::GrayImage;
+
+use crate::config::{Shadow, Transform};
+
+/// Apply `transform` to every image of every frame of `ani`, keeping their hotspots.
+pub fn apply(ani: &mut Ani, transform: &Transform) {
+    let hue = hue_matrix(transform.hue());
+    let tint = transform.tint();
+
+    for frame in ani.frames_mut() {
+        for image in frame.images_mut() {
+            let mut rgba = image.rgba_data().to_vec();
+
+            for pixel in rgba.chunks_exact_mut(4) {
+                let mut rgb = [pixel[0], pixel[1], pixel[2]].map(f64::from);
+
+                if transform.invert() {
+                    rgb = rgb.map(|channel| 255.0 - channel);
+                }
+
+                if let Some(ref matrix) = hue {
+                    rgb = multiply(matrix, rgb);
+                }
+
+                if let Some((color, amount)) = tint {
+                    let luminance = luminance(rgb) / 255.0;
+                    for (channel, &tinted) in rgb.iter_mut().zip(&color) {
+                        let tinted = f64::from(tinted) * luminance;
+                        *channel += (tinted - *channel) * amount;
+                    }
+                }
+
+                for (out, channel) in pixel.iter_mut().zip(rgb) {
+                    // Clamped, so the cast can't truncate.
+                    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let value = channel.round().clamp(0.0, 255.0) as u8;
+                    *out = value;
+                }
+            }
+
+            let mut transformed = IconImage::from_rgba_data(image.width(), image.height(), rgba);
+            transformed.set_cursor_hotspot(image.cursor_hotspot());
+            *image = transformed;
+        }
+    }
+}
+
+/// Flip every image of every frame of `ani` horizontally, along with its hotspot.
+pub fn mirror(ani: &mut Ani) {
+    for frame in ani.frames_mut() {
+        for image in frame.images_mut() {
+            let width = image.width() as usize;
+            let mut rgba = image.rgba_data().to_vec();
+
+            for row in rgba.chunks_exact_mut(width * 4) {
+                for x in 0..width / 2 {
+                    let (left, right) = row.split_at_mut((width - x - 1) * 4);
+                    left[x * 4..x * 4 + 4].swap_with_slice(&mut right[..4]);
+                }
+            }
+
+            let mut mirrored = IconImage::from_rgba_data(image.width(), image.height(), rgba);
+            mirrored.set_cursor_hotspot(image.cursor_hotspot().map(|(x, y)| {
+                let hotspot = mirror_hotspot(Hotspot::new(x, y), image.width());
+                (hotspot.x(), hotspot.y())
+            }));
+            *image = mirrored;
+        }
+    }
+}
+
+/// Move `hotspot` to match an image `width` pixels wide that was flipped horizontally.
+pub fn mirror_hotspot(hotspot: Hotspot, width: u32) -> Hotspot {
+    let last = u16::try_from(width.saturating_sub(1)).unwrap_or(u16::MAX);
+    Hotspot::new(last.saturating_sub(hotspot.x()), hotspot.y())
+}
+
+/// Draw `shadow` under every image of every frame of `ani`, keeping their size and hotspots.
+///
+/// The parts of the shadow that fall outside of the image are cut off.
+pub fn shadow(ani: &mut Ani, shadow: &Shadow) {
+    for frame in ani.frames_mut() {
+        for image in frame.images_mut() {
+            let mut shadowed = IconImage::from_rgba_data(
+                image.width(),
+                image.height(),
+                draw_shadow(image.width(), image.height(), image.rgba_data(), shadow),
+            );
+            shadowed.set_cursor_hotspot(image.cursor_hotspot());
+            *image = shadowed;
+        }
+    }
+}
+
+/// Composite `rgba` (an image of `width` by `height` pixels) over its own shadow.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+fn draw_shadow(width: u32, height: u32, rgba: &[u8], shadow: &Shadow) -> Vec<u8> {
+    // Distances are configured for 32x32 images.
+    let scale = f64::from(width) / 32.0;
+    let (x, y) = shadow.offset();
+    let (offset_x, offset_y) = ((x * scale).round() as i64, (y * scale).round() as i64);
+    let spread = (shadow.spread() * scale).round() as i64;
+    let sigma = shadow.blur() * scale;
+
+    let (w, h) = (i64::from(width), i64::from(height));
+    let alpha = |x: i64, y: i64| rgba[((y * w + x) * 4 + 3) as usize];
+
+    // The silhouette of the image, grown by `spread` pixels in every direction.
+    let mut mask = GrayImage::from_fn(width, height, |x, y| {
+        let (x, y) = (i64::from(x), i64::from(y));
+        let mut value = 0;
+        for dy in -spread..=spread {
+            for dx in -spread..=spread {
+                let (sx, sy) = (x + dx, y + dy);
+                if dx * dx + dy * dy <= spread * spread
+                    && (0..w).contains(&sx)
+                    && (0..h).contains(&sy)
+                {
+                    value = value.max(alpha(sx, sy));
+                }
+            }
+        }
+        image::Luma([value])
+    });
+
+    if sigma > 0.0 {
+        mask = image::imageops::blur(&mask, sigma as f32);
+    }
+
+    let color = shadow.color().map(f64::from);
+    let mut composited = rgba.to_vec();
+
+    for (i, pixel) in composited.chunks_exact_mut(4).enumerate() {
+        let (x, y) = (i as i64 % w - offset_x, i as i64 / w - offset_y);
+        if !(0..w).contains(&x) || !(0..h).contains(&y) {
+            continue;
+        }
+
+        let below = f64::from(mask.get_pixel(x as u32, y as u32)[0]) / 255.0 * shadow.opacity();
+        let above = f64::from(pixel[3]) / 255.0;
+        let out = above + below * (1.0 - above);
+        if out == 0.0 {
+            continue;
+        }
+
+        for (channel, shadow) in pixel.iter_mut().zip(color) {
+            let blended = (f64::from(*channel) * above + shadow * below * (1.0 - above)) / out;
+            *channel = blended.round().clamp(0.0, 255.0) as u8;
+        }
+        pixel[3] = (out * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+
+    composited
+}
+
+/// The relative luminance of a color (ITU-R BT.709), from 0 to 255.
+fn luminance([r, g, b]: [f64; 3]) -> f64 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// The matrix that rotates hues by `degrees`, as used by the CSS `hue-rotate()` filter, or
+/// `None` for a full turn.
+///
+/// <https://www.w3.org/TR/filter-effects-1/#feColorMatrixElement>
+fn hue_matrix(degrees: f64) -> Option<[[f64; 3]; 3]> {
+    if degrees % 360.0 == 0.0 {
+        return None;
+    }
+
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    Some([
+        [
+            0.213 + cos * 0.787 - sin * 0.213,
+            0.715 - cos * 0.715 - sin * 0.715,
+            0.072 - cos * 0.072 + sin * 0.928,
+        ],
+        [
+            0.213 - cos * 0.213 + sin * 0.143,
+            0.715 + cos * 0.285 + sin * 0.140,
+            0.072 - cos * 0.072 - sin * 0.283,
+        ],
+        [
+            0.213 - cos * 0.213 - sin * 0.787,
+            0.715 - cos * 0.715 + sin * 0.715,
+            0.072 + cos * 0.928 + sin * 0.072,
+        ],
+    ])
+}
+
+fn multiply(matrix: &[[f64; 3]; 3], rgb: [f64; 3]) -> [f64; 3] {
+    matrix.map(|row| row.iter().zip(rgb).map(|(a, b)| a * b).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use ani::de::Frame;
+
+    use super::*;
+
+    fn transformed(config: &str, pixel: [u8; 4]) -> Vec<u8> {
+        let transform: Transform = toml::from_str(config).unwrap();
+        let mut image = IconImage::from_rgba_data(1, 1, pixel.to_vec());
+        image.set_cursor_hotspot(Some((0, 0)));
+
+        let mut ani = Ani::new(vec![Frame::new(vec![image])]);
+        apply(&mut ani, &transform);
+
+        assert_eq!(ani.frames()[0].images()[0].cursor_hotspot(), Some((0, 0)));
+        ani.frames()[0].rgba(0).unwrap().to_vec()
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(
+            transformed("invert = true", [0, 64, 255, 128]),
+            [255, 191, 0, 128]
+        );
+        assert_eq!(
+            transformed("hue = 360", [10, 20, 30, 255]),
+            [10, 20, 30, 255]
+        );
+        assert_eq!(transformed("hue = 120", [255, 0, 0, 255])[..3], [0, 113, 0]);
+        assert_eq!(
+            transformed("tint = \"#ff0000\"", [255, 255, 255, 255]),
+            [255, 0, 0, 255]
+        );
+        assert_eq!(
+            transformed(
+                "tint = \"#ff0000\"\ntint_amount = 0.5",
+                [255, 255, 255, 255]
+            ),
+            [255, 128, 128, 255]
+        );
+    }
+
+    #[test]
+    fn mirrored() {
+        let rgba = [[1; 4], [2; 4], [3; 4]].concat();
+        let mut image = IconImage::from_rgba_data(3, 1, rgba);
+        image.set_cursor_hotspot(Some((0, 0)));
+
+        let mut ani = Ani::new(vec![Frame::new(vec![image])]);
+        mirror(&mut ani);
+
+        let frame = &ani.frames()[0];
+        assert_eq!(frame.rgba(0), Some(&[[3; 4], [2; 4], [1; 4]].concat()[..]));
+        assert_eq!(frame.hotspot(0), Some(Hotspot::new(2, 0)));
+    }
+
+    #[test]
+    fn shadowed() {
+        let shadow: Shadow = toml::from_str("x = 1\ny = 0\nblur = 0\nopacity = 0.5").unwrap();
+
+        // A 32x32 image with a single opaque white pixel in the top-left corner.
+        let mut rgba = vec![0; 32 * 32 * 4];
+        rgba[..4].copy_from_slice(&[255; 4]);
+
+        let composited = draw_shadow(32, 32, &rgba, &shadow);
+        assert_eq!(composited[..4], [255; 4]);
+        assert_eq!(composited[4..8], [0, 0, 0, 128]);
+        assert!(composited[8..].iter().all(|&value| value == 0));
+
+        let outline: Shadow =
+            toml::from_str("x = 0\ny = 0\nblur = 0\nspread = 1\nopacity = 1").unwrap();
+        let composited = draw_shadow(32, 32, &rgba, &outline);
+        assert_eq!(composited[4..8], [0, 0, 0, 255]);
+        assert_eq!(composited[32 * 4..32 * 4 + 4], [0, 0, 0, 255]);
+        assert_eq!(composited[33 * 4..33 * 4 + 4], [0; 4]);
+    }
+}
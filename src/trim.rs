@@ -0,0 +1,205 @@
+//! Crop the transparent borders shared by every frame of a cursor.
+//!
+//! Windows cursors are often drawn in the corner of a mostly empty 32x32 canvas, which makes them
+//! look offset (and small) next to cursors made for Linux.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::path::Path;
+
+use ani::de::Hotspot;
+use anyhow::Context as _;
+use ico::IconImage;
+use image::RgbaImage;
+use tracing::{info, warn};
+
+use xcursor::Image;
+
+use crate::config::Compression;
+
+/// A rectangle within an image, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bounds {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Bounds {
+    /// The smallest rectangle containing both `self` and `other`.
+    fn union(self, other: Self) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+
+        Self {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
+}
+
+/// Crop the extracted images of each size to the area that is visible in any of its frames,
+/// and move their hotspots to match.
+///
+/// Every frame of a size is cropped the same way, so the animation doesn't jitter.
+pub fn trim(
+    frames_dir: &Path,
+    frames: &mut [Vec<Image>],
+    compression: Compression,
+) -> anyhow::Result<()> {
+    let mut sizes = BTreeMap::<u32, Vec<&mut Image>>::new();
+    for image in frames.iter_mut().flatten() {
+        sizes.entry(image.size()).or_default().push(image);
+    }
+
+    for (size, images) in sizes {
+        // Deduplicated images are shared between frames, so each file is only cropped once.
+        let mut files = images
+            .iter()
+            .map(|image| image.file_name().to_owned())
+            .collect::<Vec<_>>();
+        files.sort();
+        files.dedup();
+
+        let mut decoded = Vec::with_capacity(files.len());
+        for file_name in files {
+            let path = frames_dir.join(&file_name);
+            let image = File::open(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| Ok(IconImage::read_png(file)?))
+                .and_then(|image| {
+                    RgbaImage::from_raw(image.width(), image.height(), image.rgba_data().to_vec())
+                        .context("image data does not match its dimensions")
+                })
+                .with_context(|| format!("failed to read frame: {file_name}"))?;
+            decoded.push((path, image));
+        }
+
+        // Frames that were edited by hand (and kept) may have been trimmed by a previous build.
+        let dimensions = |image: &RgbaImage| (image.width(), image.height());
+        if decoded
+            .windows(2)
+            .any(|pair| dimensions(&pair[0].1) != dimensions(&pair[1].1))
+        {
+            warn!("{size}x{size} images have different dimensions; not trimming");
+            continue;
+        }
+
+        let Some(bounds) = decoded
+            .iter()
+            .filter_map(|(_, image)| visible_bounds(image))
+            .reduce(Bounds::union)
+        else {
+            info!("every {size}x{size} image is transparent; not trimming");
+            continue;
+        };
+
+        if decoded
+            .iter()
+            .all(|(_, image)| bounds.width == image.width() && bounds.height == image.height())
+        {
+            continue;
+        }
+
+        info!(
+            "trimming {size}x{size} images to {}x{} at ({}, {})",
+            bounds.width, bounds.height, bounds.x, bounds.y
+        );
+
+        let mut cropped = HashSet::new();
+        let mut writer = xcursor::PngWriter::new(compression);
+        for (path, image) in &decoded {
+            let trimmed =
+                image::imageops::crop_imm(image, bounds.x, bounds.y, bounds.width, bounds.height)
+                    .to_image();
+            let trimmed =
+                IconImage::from_rgba_data(trimmed.width(), trimmed.height(), trimmed.into_raw());
+            writer
+                .write(path, &trimmed)
+                .with_context(|| format!("failed to write frame: {}", path.display()))?;
+            cropped.insert(path.clone());
+        }
+
+        for image in images {
+            if cropped.contains(&frames_dir.join(image.file_name())) {
+                let (x, y) = image.hotspot();
+                let hotspot = move_hotspot(Hotspot::new(x, y), bounds);
+                image.set_hotspot((hotspot.x(), hotspot.y()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The area of `image` that isn't fully transparent, if any.
+fn visible_bounds(image: &RgbaImage) -> Option<Bounds> {
+    let mut visible = image
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| pixel[3] != 0)
+        .map(|(x, y, _)| (x, y));
+
+    let (x, y) = visible.next()?;
+    let (mut left, mut top, mut right, mut bottom) = (x, y, x, y);
+
+    for (x, y) in visible {
+        left = left.min(x);
+        top = top.min(y);
+        right = right.max(x);
+        bottom = bottom.max(y);
+    }
+
+    Some(Bounds {
+        x: left,
+        y: top,
+        width: right - left + 1,
+        height: bottom - top + 1,
+    })
+}
+
+/// Move `hotspot` into the cropped area, clamping it to the area's edges if it was outside.
+fn move_hotspot(hotspot: Hotspot, bounds: Bounds) -> Hotspot {
+    let coordinate = |value: u16, start: u32, length: u32| {
+        let moved = u32::from(value).saturating_sub(start).min(length - 1);
+        u16::try_from(moved).unwrap_or(u16::MAX)
+    };
+
+    Hotspot::new(
+        coordinate(hotspot.x(), bounds.x, bounds.width),
+        coordinate(hotspot.y(), bounds.y, bounds.height),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let mut image = RgbaImage::new(8, 8);
+        assert_eq!(visible_bounds(&image), None);
+
+        image.put_pixel(2, 3, Rgba([0, 0, 0, 1]));
+        image.put_pixel(5, 4, Rgba([0, 0, 0, 255]));
+        let bounds = visible_bounds(&image).unwrap();
+        assert_eq!(
+            bounds,
+            Bounds {
+                x: 2,
+                y: 3,
+                width: 4,
+                height: 2,
+            }
+        );
+
+        assert_eq!(move_hotspot(Hotspot::new(3, 3), bounds), Hotspot::new(1, 0));
+        assert_eq!(move_hotspot(Hotspot::new(0, 7), bounds), Hotspot::new(0, 1));
+    }
+}
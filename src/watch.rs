@@ -0,0 +1,107 @@
+//! Detect changes to files by polling their modification times.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// How often files are checked for changes.
+const INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long to wait after a change before reporting it, so editors (and image editors
+/// exporting several frames) can finish writing.
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// The modification time of every file under a set of paths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Snapshot(BTreeMap<PathBuf, SystemTime>);
+
+impl Snapshot {
+    /// Record every file in `paths`, and every file inside of the directories in `paths`.
+    ///
+    /// Paths that don't exist are skipped, so creating them counts as a change.
+    pub fn new(paths: &[PathBuf]) -> Self {
+        let mut snapshot = Self::default();
+
+        for path in paths {
+            snapshot.visit(path);
+        }
+
+        snapshot
+    }
+
+    fn visit(&mut self, path: &Path) {
+        let Ok(metadata) = fs::metadata(path) else {
+            return;
+        };
+
+        if metadata.is_dir() {
+            for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+                self.visit(&entry.path());
+            }
+        } else if let Ok(modified) = metadata.modified() {
+            self.0.insert(path.to_owned(), modified);
+        }
+    }
+
+    /// The first file that was added, removed or modified between `self` and `other`.
+    pub fn changed<'a>(&'a self, other: &'a Self) -> Option<&'a Path> {
+        let modified = other
+            .0
+            .iter()
+            .find(|&(path, time)| self.0.get(path) != Some(time));
+        let removed = self.0.keys().find(|&path| !other.0.contains_key(path));
+
+        modified
+            .map(|(path, _)| path)
+            .or(removed)
+            .map(PathBuf::as_path)
+    }
+}
+
+/// Block until a file under `paths` is added, removed or modified, and return its path.
+pub fn wait_for_change(paths: &[PathBuf]) -> PathBuf {
+    let before = Snapshot::new(paths);
+
+    loop {
+        thread::sleep(INTERVAL);
+        let after = Snapshot::new(paths);
+
+        if let Some(path) = before.changed(&after) {
+            thread::sleep(SETTLE);
+            return path.to_owned();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed() {
+        let time = SystemTime::UNIX_EPOCH;
+        let later = time + Duration::from_secs(1);
+        let snapshot = |files: &[(&str, SystemTime)]| {
+            Snapshot(
+                files
+                    .iter()
+                    .map(|&(path, time)| (PathBuf::from(path), time))
+                    .collect(),
+            )
+        };
+
+        let before = snapshot(&[("a", time), ("b", time)]);
+        assert_eq!(before.changed(&before.clone()), None);
+
+        let modified = snapshot(&[("a", time), ("b", later)]);
+        assert_eq!(before.changed(&modified), Some(Path::new("b")));
+
+        let added = snapshot(&[("a", time), ("b", time), ("c", time)]);
+        assert_eq!(before.changed(&added), Some(Path::new("c")));
+
+        let removed = snapshot(&[("b", time)]);
+        assert_eq!(before.changed(&removed), Some(Path::new("a")));
+    }
+}
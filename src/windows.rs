@@ -0,0 +1,164 @@
+//! Package cursors for Windows, which installs a cursor scheme from the `Install.inf` shipped
+//! with it (right-click → Install).
+//!
+//! <https://learn.microsoft.com/en-us/windows-hardware/drivers/install/inf-defaultinstall-section>
+
+use std::fmt::Write as _;
+
+use crate::names;
+
+/// The cursor roles listed by the `[Scheme.Reg]` section of an `Install.inf`, in order.
+///
+/// Each role maps to the X cursor name to create; the other names of that cursor (see
+/// [`names::CURSORS`]) become its aliases.
+pub const ROLES: &[(&str, &str)] = &[
+    ("Arrow", "default"),
+    ("Help", "help"),
+    ("AppStarting", "progress"),
+    ("Wait", "wait"),
+    ("Crosshair", "crosshair"),
+    ("IBeam", "text"),
+    ("NWPen", "pencil"),
+    ("No", "not-allowed"),
+    ("SizeNS", "ns-resize"),
+    ("SizeWE", "ew-resize"),
+    ("SizeNWSE", "nwse-resize"),
+    ("SizeNESW", "nesw-resize"),
+    ("SizeAll", "move"),
+    ("UpArrow", "up-arrow"),
+    ("Hand", "pointer"),
+    ("Pin", "pin"),
+    ("Person", "person"),
+];
+
+/// Pick the file to use for each role from `cursors` (an X cursor name and file name each).
+///
+/// A cursor named after the standard name of a role is preferred over the other names of that
+/// cursor (e.g., `default` over `left_ptr`).
+pub fn assign_roles(cursors: &[(String, String)]) -> Vec<(&'static str, String)> {
+    ROLES
+        .iter()
+        .filter_map(|&(role, standard)| {
+            let exact = cursors.iter().find(|(name, _)| name == standard);
+            let alias = || {
+                cursors
+                    .iter()
+                    .find(|(name, _)| names::canonical(name) == Some(standard))
+            };
+
+            exact
+                .or_else(alias)
+                .map(|(_, file_name)| (role, file_name.clone()))
+        })
+        .collect()
+}
+
+/// Render an `Install.inf` that copies the `files` (a role and file name each) to
+/// `C:\Windows\Cursors\<scheme>`, then registers and applies them as the cursor scheme `scheme`.
+///
+/// Roles without a file are left empty, so Windows uses its own cursor for them.
+pub fn render_install_inf(scheme: &str, files: &[(&str, String)]) -> String {
+    let file = |role: &str| {
+        files
+            .iter()
+            .find(|&&(other, _)| other == role)
+            .map(|(_, file_name)| file_name)
+    };
+
+    let mut contents = String::from(
+        "[Version]\n\
+        signature=\"$CHICAGO$\"\n\
+        \n\
+        [DefaultInstall]\n\
+        CopyFiles = Scheme.Cur\n\
+        AddReg    = Scheme.Reg, Wreg\n\
+        \n\
+        [DestinationDirs]\n\
+        Scheme.Cur = 10,\"%CUR_DIR%\"\n\
+        \n\
+        [Scheme.Reg]\n",
+    );
+
+    let paths = ROLES
+        .iter()
+        .map(|&(role, _)| {
+            file(role).map_or_else(String::new, |_| format!("%10%\\%CUR_DIR%\\%{role}%"))
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    _ = writeln!(
+        contents,
+        "HKCU,\"Control Panel\\Cursors\\Schemes\",\"%SCHEME_NAME%\",,\"{paths}\""
+    );
+
+    // Apply the scheme right away, instead of only adding it to the list in the settings.
+    contents.push_str("\n[Wreg]\n");
+    _ = writeln!(
+        contents,
+        "HKCU,\"Control Panel\\Cursors\",,0x00020000,\"%SCHEME_NAME%\""
+    );
+    for &(role, _) in ROLES {
+        if file(role).is_some() {
+            _ = writeln!(
+                contents,
+                "HKCU,\"Control Panel\\Cursors\",{role},0x00020000,\"%10%\\%CUR_DIR%\\%{role}%\""
+            );
+        }
+    }
+
+    contents.push_str("\n[Scheme.Cur]\n");
+    for (_, file_name) in files {
+        _ = writeln!(contents, "\"{file_name}\"");
+    }
+
+    contents.push_str("\n[Strings]\n");
+    _ = writeln!(contents, "CUR_DIR     = \"Cursors\\{scheme}\"");
+    _ = writeln!(contents, "SCHEME_NAME = \"{scheme}\"");
+    for &(role, _) in ROLES {
+        if let Some(file_name) = file(role) {
+            _ = writeln!(contents, "{role} = \"{file_name}\"");
+        }
+    }
+
+    // Windows expects the CRLF line endings of its own INF files.
+    contents.replace('\n', "\r\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inf::Inf;
+
+    #[test]
+    fn install_inf() {
+        let cursors = [
+            ("X_cursor", "X_cursor.cur"),
+            ("default", "default.cur"),
+            ("watch", "watch.ani"),
+            ("alias", "alias.cur"),
+        ]
+        .map(|(name, file_name)| (name.to_owned(), file_name.to_owned()));
+
+        let files = assign_roles(&cursors);
+        assert_eq!(
+            files,
+            [
+                ("Arrow", "default.cur".to_owned()),
+                ("Wait", "watch.ani".to_owned()),
+            ]
+        );
+
+        let inf = Inf::parse(&render_install_inf("Theme", &files)).unwrap();
+
+        let scheme = inf.section("Scheme.Reg").unwrap().entries()[0].values();
+        let paths = inf.expand(&scheme[4]);
+        let paths = paths.split(',').collect::<Vec<_>>();
+        assert_eq!(paths.len(), ROLES.len());
+        assert_eq!(paths[0], r"%10%\Cursors\Theme\default.cur");
+        assert_eq!(paths[1], "");
+        assert_eq!(paths[3], r"%10%\Cursors\Theme\watch.ani");
+
+        let strings = inf.section("Strings").unwrap();
+        assert_eq!(strings.value("SCHEME_NAME"), Some("Theme"));
+    }
+}
@@ -0,0 +1,238 @@
+//! Read the Xcursor file format produced by `xcursorgen`.
+//!
+//! <https://www.x.org/releases/current/doc/man/man3/Xcursor.3.xhtml>
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, ensure, Context as _};
+
+/// The file signature found at the start of every Xcursor file.
+pub const MAGIC: [u8; 4] = *b"Xcur";
+
+/// The chunk type used for images.
+const IMAGE_TYPE: u32 = 0xfffd_0002;
+
+/// The chunk type used for comments.
+const COMMENT_TYPE: u32 = 0xfffe_0001;
+
+/// The subtype of comments holding the copyright notice.
+const COPYRIGHT: u32 = 1;
+
+/// The largest width or height allowed by the specification.
+const MAX_DIMENSION: u32 = 0x7fff;
+
+/// Represents a single image stored within an Xcursor file.
+#[derive(Debug, Clone)]
+pub struct Image {
+    size: u32,
+    width: u32,
+    height: u32,
+    hotspot: (u32, u32),
+    delay: u32,
+
+    /// The pixels, as non-premultiplied RGBA.
+    rgba: Vec<u8>,
+}
+
+impl Image {
+    /// The nominal size of the cursor this image belongs to.
+    pub const fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub const fn hotspot(&self) -> (u32, u32) {
+        self.hotspot
+    }
+
+    /// How long the image is shown for, in milliseconds (if it is part of an animation).
+    pub const fn delay(&self) -> u32 {
+        self.delay
+    }
+
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+}
+
+/// Represents the contents of an Xcursor file.
+#[derive(Debug, Clone)]
+pub struct Xcursor {
+    images: Vec<Image>,
+    copyright: Option<String>,
+}
+
+impl Xcursor {
+    /// Read and decode an Xcursor file.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let data = fs::read(path).context("failed to read Xcursor file")?;
+        Self::from_bytes(&data)
+    }
+
+    /// Decode Xcursor data.
+    pub fn from_bytes(data: &[u8]) -> anyhow::Result<Self> {
+        ensure!(data.get(..4) == Some(&MAGIC), "invalid Xcursor signature");
+
+        let header_size = read_u32(data, 4)?;
+        let toc_count = read_u32(data, 12)?;
+        let mut images = Vec::new();
+        let mut copyright = None;
+
+        for i in 0..toc_count {
+            let entry = usize::try_from(u64::from(header_size) + u64::from(i) * 12)
+                .context("table of contents entry out of bounds")?;
+
+            let chunk_type = read_u32(data, entry)?;
+            let subtype = read_u32(data, entry + 4)?;
+            let position = usize::try_from(read_u32(data, entry + 8)?)
+                .context("chunk position out of bounds")?;
+
+            match (chunk_type, subtype) {
+                (IMAGE_TYPE, _) => images.push(
+                    read_image(data, position).with_context(|| format!("invalid image {i}"))?,
+                ),
+                (COMMENT_TYPE, COPYRIGHT) => {
+                    copyright = Some(
+                        read_comment(data, position)
+                            .with_context(|| format!("invalid comment {i}"))?,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        ensure!(!images.is_empty(), "Xcursor file contains no images");
+        Ok(Self { images, copyright })
+    }
+
+    /// Every image, in the order they are stored (grouped by size, in animation order).
+    pub fn images(&self) -> &[Image] {
+        &self.images
+    }
+
+    /// The copyright notice, if the file has one.
+    pub fn copyright(&self) -> Option<&str> {
+        self.copyright.as_deref()
+    }
+}
+
+fn read_image(data: &[u8], position: usize) -> anyhow::Result<Image> {
+    let header_size = read_u32(data, position)?;
+    let chunk_type = read_u32(data, position + 4)?;
+    ensure!(chunk_type == IMAGE_TYPE, "expected an image chunk");
+
+    let size = read_u32(data, position + 8)?;
+    let width = read_u32(data, position + 16)?;
+    let height = read_u32(data, position + 20)?;
+    let xhot = read_u32(data, position + 24)?;
+    let yhot = read_u32(data, position + 28)?;
+    let delay = read_u32(data, position + 32)?;
+
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        bail!("image dimensions too large: {width}x{height}");
+    }
+
+    if xhot > width || yhot > height {
+        bail!("hotspot ({xhot}, {yhot}) is outside of the image ({width}x{height})");
+    }
+
+    let start = position + usize::try_from(header_size).context("invalid header size")?;
+    let length = usize::try_from(width * height * 4).context("image too large")?;
+    ensure!(
+        start
+            .checked_add(length)
+            .is_some_and(|end| end <= data.len()),
+        "image data is truncated"
+    );
+
+    // Pixels are stored as premultiplied ARGB in little-endian words, i.e., BGRA bytes.
+    let rgba = data[start..start + length]
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let [b, g, r, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            let unpremultiply = |c: u8| match a {
+                0 => 0,
+                _ => u8::try_from((u32::from(c) * 255 + u32::from(a) / 2) / u32::from(a))
+                    .unwrap_or(u8::MAX),
+            };
+
+            [unpremultiply(r), unpremultiply(g), unpremultiply(b), a]
+        })
+        .collect();
+
+    Ok(Image {
+        size,
+        width,
+        height,
+        hotspot: (xhot, yhot),
+        delay,
+        rgba,
+    })
+}
+
+fn read_comment(data: &[u8], position: usize) -> anyhow::Result<String> {
+    let header_size = read_u32(data, position)?;
+    let length = read_u32(data, position + 16)?;
+
+    let start = position + usize::try_from(header_size).context("invalid header size")?;
+    let bytes = usize::try_from(length)
+        .ok()
+        .and_then(|length| data.get(start..start.checked_add(length)?))
+        .context("comment is truncated")?;
+
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> anyhow::Result<u32> {
+    let bytes = offset
+        .checked_add(4)
+        .and_then(|end| data.get(offset..end))
+        .context("unexpected end of Xcursor data")?;
+
+    Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn single_image() {
+        let mut data = MAGIC.to_vec();
+        data.extend(encode(&[16, 0x1_0000, 1]));
+        data.extend(encode(&[IMAGE_TYPE, 32, 28]));
+        data.extend(encode(&[36, IMAGE_TYPE, 32, 1, 1, 2, 0, 1, 50]));
+        data.extend(encode(&[0xff00_0000, 0xffff_ffff]));
+
+        let xcursor = Xcursor::from_bytes(&data).expect("expected hardcoded bytes to be valid");
+        let image = &xcursor.images()[0];
+
+        assert_eq!(xcursor.images().len(), 1);
+        assert_eq!(image.size(), 32);
+        assert_eq!(image.hotspot(), (0, 1));
+        assert_eq!(image.delay(), 50);
+        assert_eq!(image.rgba(), [0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    fn truncated_image() {
+        let mut data = MAGIC.to_vec();
+        data.extend(encode(&[16, 0x1_0000, 1]));
+        data.extend(encode(&[IMAGE_TYPE, 32, 28]));
+        data.extend(encode(&[36, IMAGE_TYPE, 32, 1, 4, 4, 0, 0, 50]));
+
+        assert!(Xcursor::from_bytes(&data).is_err());
+    }
+}